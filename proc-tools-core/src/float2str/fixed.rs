@@ -0,0 +1,234 @@
+use crate::float2str::d2s::{self, DOUBLE_EXPONENT_BITS, DOUBLE_MANTISSA_BITS, d2d};
+use crate::float2str::f2s::{FLOAT_EXPONENT_BITS, FLOAT_MANTISSA_BITS, f2d};
+use crate::float2str::pretty::write_mantissa_long;
+use core::cmp::Ordering;
+use core::ptr;
+
+/// f64 定点格式化所需的最小缓冲区长度（不含小数位），实际长度需再加上小数位数
+pub const F64_FIXED_LEN: usize = 311;
+/// f32 定点格式化所需的最小缓冲区长度（不含小数位），实际长度需再加上小数位数
+pub const F32_FIXED_LEN: usize = 41;
+
+static POW10: [u64; 20] = [
+    1,
+    10,
+    100,
+    1_000,
+    10_000,
+    100_000,
+    1_000_000,
+    10_000_000,
+    100_000_000,
+    1_000_000_000,
+    10_000_000_000,
+    100_000_000_000,
+    1_000_000_000_000,
+    10_000_000_000_000,
+    100_000_000_000_000,
+    1_000_000_000_000_000,
+    10_000_000_000_000_000,
+    100_000_000_000_000_000,
+    1_000_000_000_000_000_000,
+    10_000_000_000_000_000_000,
+];
+
+#[inline]
+pub(crate) unsafe fn format64_fixed(f: f64, precision: usize, result: *mut u8) -> usize {
+    let bits = f.to_bits();
+    let sign = ((bits >> (DOUBLE_MANTISSA_BITS + DOUBLE_EXPONENT_BITS)) & 1) != 0;
+    let ieee_mantissa = bits & ((1u64 << DOUBLE_MANTISSA_BITS) - 1);
+    let ieee_exponent = (bits >> DOUBLE_MANTISSA_BITS) as u32 & ((1u32 << DOUBLE_EXPONENT_BITS) - 1);
+
+    if ieee_exponent == 0 && ieee_mantissa == 0 {
+        return unsafe { write_fixed(sign, 0, 0, (0, 0), precision, result) };
+    }
+    // 精确的二进制值 m2 * 2^e2
+    let exact = if ieee_exponent == 0 {
+        (ieee_mantissa, 1 - 1075)
+    } else {
+        (ieee_mantissa | (1u64 << DOUBLE_MANTISSA_BITS), ieee_exponent as i32 - 1075)
+    };
+    let v = d2d(ieee_mantissa, ieee_exponent);
+    unsafe { write_fixed(sign, v.mantissa, v.exponent, exact, precision, result) }
+}
+
+#[inline]
+pub(crate) unsafe fn format32_fixed(f: f32, precision: usize, result: *mut u8) -> usize {
+    let bits = f.to_bits();
+    let sign = ((bits >> (FLOAT_MANTISSA_BITS + FLOAT_EXPONENT_BITS)) & 1) != 0;
+    let ieee_mantissa = bits & ((1u32 << FLOAT_MANTISSA_BITS) - 1);
+    let ieee_exponent = (bits >> FLOAT_MANTISSA_BITS) & ((1u32 << FLOAT_EXPONENT_BITS) - 1);
+
+    if ieee_exponent == 0 && ieee_mantissa == 0 {
+        return unsafe { write_fixed(sign, 0, 0, (0, 0), precision, result) };
+    }
+    // 精确的二进制值 m2 * 2^e2
+    let exact = if ieee_exponent == 0 {
+        (ieee_mantissa as u64, 1 - 150)
+    } else {
+        ((ieee_mantissa | (1u32 << FLOAT_MANTISSA_BITS)) as u64, ieee_exponent as i32 - 150)
+    };
+    let v = f2d(ieee_mantissa, ieee_exponent);
+    unsafe { write_fixed(sign, v.mantissa as u64, v.exponent, exact, precision, result) }
+}
+
+/// 将最短表示 `mantissa * 10^exponent` 按固定小数位数写入 `result`
+/// - 按精确的二进制值 `exact.0 * 2^exact.1` 舍入，恰好为一半时向偶数舍入，与 `format!("{:.N}")` 一致
+///   - 最短表示与精确值之间不会跨过舍入的中点，只有最短表示恰好落在中点上时才需要比较精确值
+unsafe fn write_fixed(sign: bool, mantissa: u64, exponent: i32, exact: (u64, i32), precision: usize, result: *mut u8) -> usize {
+    let length = if mantissa == 0 { 0 } else { d2s::decimal_length17(mantissa) };
+    // 保留的有效数字个数
+    let keep = length + exponent as isize + precision as isize;
+
+    // 舍入后的数字为 digits 后接 zeros 个 0，整体表示 N * 10^-precision
+    let (digits, zeros) = if mantissa == 0 || keep < 0 {
+        (0, 0)
+    } else if keep >= length {
+        (mantissa, (keep - length) as usize)
+    } else {
+        let removed = (length - keep) as usize;
+        let mut digits = mantissa / POW10[removed];
+        let rest = mantissa % POW10[removed];
+        let half = 5 * POW10[removed - 1];
+        let round_up = match rest.cmp(&half) {
+            Ordering::Greater => true,
+            Ordering::Less => false,
+            Ordering::Equal => match cmp_exact(exact.0, exact.1, mantissa, exponent) {
+                Ordering::Greater => true,
+                Ordering::Less => false,
+                Ordering::Equal => digits & 1 == 1,
+            },
+        };
+        if round_up {
+            digits += 1;
+        }
+        (digits, 0)
+    };
+    let digits_len = if digits == 0 { 0 } else { d2s::decimal_length17(digits) as usize };
+    let total = digits_len + zeros;
+
+    let mut index = 0usize;
+    unsafe {
+        if sign {
+            *result = b'-';
+            index += 1;
+        }
+        if total <= precision {
+            // 0.00ddd
+            *result.add(index) = b'0';
+            index += 1;
+            if precision > 0 {
+                *result.add(index) = b'.';
+                index += 1;
+                ptr::write_bytes(result.add(index), b'0', precision - total);
+                index += precision - total;
+                if digits_len > 0 {
+                    write_mantissa_long(digits, result.add(index + digits_len));
+                    index += digits_len;
+                }
+                ptr::write_bytes(result.add(index), b'0', zeros);
+                index += zeros;
+            }
+        } else {
+            // ddd000.ddd：先写出所有数字，再在整数位之后插入小数点
+            let int_len = total - precision;
+            write_mantissa_long(digits, result.add(index + digits_len));
+            ptr::write_bytes(result.add(index + digits_len), b'0', zeros);
+            if precision > 0 {
+                ptr::copy(result.add(index + int_len), result.add(index + int_len + 1), precision);
+                *result.add(index + int_len) = b'.';
+                index += 1;
+            }
+            index += total;
+        }
+    }
+    index
+}
+
+/// 比较 `m2 * 2^e2` 与 `m10 * 10^e10` 的大小
+/// - 两边同乘以 2、5 的幂消去负指数后，以大整数精确比较
+fn cmp_exact(m2: u64, e2: i32, m10: u64, e10: i32) -> Ordering {
+    let mut left = BigUint::new(m2);
+    let mut right = BigUint::new(m10);
+    // m2 * 2^e2 与 m10 * 2^e10 * 5^e10 比较，先约去公共的 2^e10
+    let shift = e2 - e10;
+    if shift >= 0 {
+        left.mul_pow2(shift as u32);
+    } else {
+        right.mul_pow2(shift.unsigned_abs());
+    }
+    if e10 >= 0 {
+        right.mul_pow5(e10 as u32);
+    } else {
+        left.mul_pow5(e10.unsigned_abs());
+    }
+    left.cmp(&right)
+}
+
+/// 定长的无符号大整数，低位在前
+/// - 容量足以容纳 `cmp_exact` 中 f64 的全部取值范围（约 850 位）
+struct BigUint {
+    limbs: [u32; 40],
+    len: usize,
+}
+
+impl BigUint {
+    fn new(value: u64) -> Self {
+        let mut limbs = [0u32; 40];
+        limbs[0] = value as u32;
+        limbs[1] = (value >> 32) as u32;
+        let len = if limbs[1] != 0 { 2 } else if limbs[0] != 0 { 1 } else { 0 };
+        BigUint { limbs, len }
+    }
+
+    fn mul_small(&mut self, factor: u32) {
+        let mut carry = 0u64;
+        for limb in &mut self.limbs[..self.len] {
+            let product = *limb as u64 * factor as u64 + carry;
+            *limb = product as u32;
+            carry = product >> 32;
+        }
+        if carry != 0 {
+            self.limbs[self.len] = carry as u32;
+            self.len += 1;
+        }
+    }
+
+    fn mul_pow5(&mut self, mut exp: u32) {
+        // 5^13 是不超过 u32 的最大的 5 的幂
+        while exp >= 13 {
+            self.mul_small(1_220_703_125);
+            exp -= 13;
+        }
+        self.mul_small(5u32.pow(exp));
+    }
+
+    fn mul_pow2(&mut self, exp: u32) {
+        if self.len == 0 {
+            return;
+        }
+        let words = (exp / 32) as usize;
+        let bits = exp % 32;
+        if words > 0 {
+            self.limbs.copy_within(..self.len, words);
+            self.limbs[..words].fill(0);
+            self.len += words;
+        }
+        if bits > 0 {
+            let mut carry = 0u32;
+            for limb in &mut self.limbs[words..self.len] {
+                let next = *limb >> (32 - bits);
+                *limb = (*limb << bits) | carry;
+                carry = next;
+            }
+            if carry != 0 {
+                self.limbs[self.len] = carry;
+                self.len += 1;
+            }
+        }
+    }
+
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.len.cmp(&other.len).then_with(|| self.limbs[..self.len].iter().rev().cmp(other.limbs[..other.len].iter().rev()))
+    }
+}
@@ -1,6 +1,21 @@
+#![no_std]
+
+#[doc(hidden)]
+pub extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub extern crate serde;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
 pub mod float2str;
 pub mod utils_core;
 
+pub use utils_core::byte_encode::{ByteEncode, DecodeError, EncodeError};
+
 /// 将多个字符串片段安全、高效地拼接成一个 [`String`]。
 ///
 /// 该宏会：
@@ -35,7 +50,7 @@ macro_rules! concat_str {
         $(
             total_len += $suffix.len();
         )+
-        let mut s = String::with_capacity(total_len);
+        let mut s = $crate::alloc::string::String::with_capacity(total_len);
         s.push_str($first);
         $(
             s.push_str($suffix);
@@ -149,7 +164,7 @@ pub fn replace_multiple_patterns(input: &str, patterns: &[(&str, &str)]) -> Stri
 
                 if i == pattern_len {
                     // 复制替换内容
-                    std::ptr::copy_nonoverlapping(replacement_bytes.as_ptr(), result_ptr.add(write_pos), replacement_bytes.len());
+                    core::ptr::copy_nonoverlapping(replacement_bytes.as_ptr(), result_ptr.add(write_pos), replacement_bytes.len());
                     write_pos += replacement_bytes.len();
                     read_pos += pattern_len;
                     matched = true;
@@ -179,7 +194,7 @@ pub fn replace_multiple_patterns(input: &str, patterns: &[(&str, &str)]) -> Stri
 
                     // 确保不会越界
                     let actual_len = char_len.min(input_len - read_pos);
-                    std::ptr::copy_nonoverlapping(input_bytes.as_ptr().add(read_pos), result_ptr.add(write_pos), actual_len);
+                    core::ptr::copy_nonoverlapping(input_bytes.as_ptr().add(read_pos), result_ptr.add(write_pos), actual_len);
                     write_pos += actual_len;
                     read_pos += actual_len;
                 }
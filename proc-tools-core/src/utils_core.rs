@@ -1 +1,14 @@
+pub mod builder;
+pub mod byte_encode;
+#[cfg(feature = "serde")]
+pub mod byte_encode_serde;
+pub mod checksum;
+pub mod half;
 pub mod impl_to_ascii;
+#[cfg(feature = "syn")]
+pub mod kv_args;
+pub mod lang_extract;
+pub mod net;
+pub mod sink;
+pub mod timestamp;
+pub mod transform;
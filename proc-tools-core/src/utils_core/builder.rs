@@ -0,0 +1,39 @@
+use core::fmt;
+
+/// `#[derive(Builder)]` 生成的 `build()` 在必填字段未设置时返回的错误
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::builder::BuildError;
+///
+/// let err = BuildError::missing("host");
+/// assert_eq!(err.field(), "host");
+/// assert_eq!(err.to_string(), "required field `host` was not set");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuildError {
+    field: &'static str,
+}
+
+impl BuildError {
+    /// 创建表示字段 `field` 未设置的错误
+    #[inline]
+    pub const fn missing(field: &'static str) -> Self {
+        Self { field }
+    }
+
+    /// 未设置的字段名
+    #[inline]
+    pub const fn field(&self) -> &'static str {
+        self.field
+    }
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "required field `{}` was not set", self.field)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BuildError {}
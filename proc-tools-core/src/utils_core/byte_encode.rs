@@ -0,0 +1,452 @@
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+#[cfg(feature = "std")]
+use std::io;
+
+/// 固定大小的字节编码/解码，由 `#[derive(ByteEncode)]` 实现
+/// - 编码结果恰好为 `SIZE` 个字节，字段按声明顺序依次排列
+/// - 可以作为泛型约束，对不同的报文类型使用同一套收发逻辑
+/// - 不依赖 `std`，可以在固件和主机程序之间共享报文定义；`read_from` 需要 `std` 特性
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::{ByteEncode, DecodeError, EncodeError};
+///
+/// // 通常由 `#[derive(ByteEncode)]` 生成
+/// struct Ping {
+///     seq: u32,
+/// }
+///
+/// impl ByteEncode for Ping {
+///     const SIZE: usize = 4;
+///     type Bytes = [u8; 4];
+///
+///     fn to_bytes(&self) -> [u8; 4] {
+///         self.seq.to_le_bytes()
+///     }
+///
+///     fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+///         let seq = bytes.try_into().map_err(|_| DecodeError::UnexpectedLength { expected: 4, got: bytes.len() })?;
+///         Ok(Ping { seq: u32::from_le_bytes(seq) })
+///     }
+///
+///     fn write_to(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+///         let got = buf.len();
+///         let buf = buf.get_mut(..Self::SIZE).ok_or(EncodeError::BufferTooSmall { needed: Self::SIZE, got })?;
+///         buf.copy_from_slice(&self.to_bytes());
+///         Ok(Self::SIZE)
+///     }
+/// }
+///
+/// fn send<T: ByteEncode>(value: &T, out: &mut Vec<u8>) {
+///     out.extend_from_slice(value.to_bytes().as_ref());
+/// }
+///
+/// let mut out = Vec::new();
+/// send(&Ping { seq: 7 }, &mut out);
+/// assert_eq!(out, [7, 0, 0, 0]);
+/// assert_eq!(Ping::from_bytes(&out).unwrap().seq, 7);
+/// assert_eq!(Ping::read_from(&mut &out[..]).unwrap().seq, 7);
+/// assert_eq!(Ping::from_bytes(&out[..3]).err(), Some(DecodeError::UnexpectedLength { expected: 4, got: 3 }));
+/// ```
+pub trait ByteEncode: Sized {
+    /// 编码后的字节数
+    const SIZE: usize;
+
+    /// `to_bytes` 返回的字节数组类型，即 `[u8; Self::SIZE]`
+    /// - 泛型类型的 `SIZE` 依赖泛型参数，不能用作数组长度，此时为长度为 `SIZE` 的 `Vec<u8>`
+    type Bytes: AsRef<[u8]> + AsMut<[u8]>;
+
+    /// 编码为字节数组
+    ///
+    /// # 返回值
+    /// - 长度为 `SIZE` 的字节数组
+    fn to_bytes(&self) -> Self::Bytes;
+
+    /// 从字节切片解码
+    ///
+    /// # 参数
+    /// - `bytes`: 长度必须恰好为 `SIZE`
+    ///
+    /// # 返回值
+    /// - 长度不匹配或字段内容无效时返回 [`DecodeError`]，指明失败的字段
+    fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError>;
+
+    /// 解码到调用者提供的存储中，如预先分配的内存池中的位置
+    /// - 默认实现调用 `from_bytes` 后整体写入；`#[byte_encode(in_place)]` 的结构体逐个字段写入，
+    ///   不在栈上构造完整的值，适合较大的记录
+    ///
+    /// # 参数
+    /// - `bytes`: 与 `from_bytes` 相同
+    /// - `out`: 解码结果的存储位置
+    ///
+    /// # 返回值
+    /// - 成功时 `out` 已初始化；失败时返回与 `from_bytes` 相同的错误，`out` 仍视为未初始化，已写入的字段不会被释放
+    fn decode_into(bytes: &[u8], out: &mut MaybeUninit<Self>) -> Result<(), DecodeError> {
+        out.write(Self::from_bytes(bytes)?);
+        Ok(())
+    }
+
+    /// 编码到 `buf` 的开头，不需要中间数组
+    ///
+    /// # 参数
+    /// - `buf`: 长度至少为 `SIZE`，只写入前 `SIZE` 个字节
+    ///
+    /// # 返回值
+    /// - 写入的字节数，即 `SIZE`；`buf` 长度不足时返回 [`EncodeError::BufferTooSmall`]
+    fn write_to(&self, buf: &mut [u8]) -> Result<usize, EncodeError>;
+
+    /// 从 `reader` 读取恰好 `SIZE` 个字节并解码，如从 `TcpStream` 读取一个报文
+    ///
+    /// # 参数
+    /// - `reader`: 数据来源
+    ///
+    /// # 返回值
+    /// - 数据不足 `SIZE` 个字节时返回 `UnexpectedEof` 错误，字段内容无效时返回 `InvalidData` 错误，
+    ///   其内部错误为 [`DecodeError`]，可以用 `get_ref()` 和 `downcast_ref` 取出
+    /// - 默认实现读取到堆上的缓冲区，`#[derive(ByteEncode)]` 在未启用 `proc-tools` 的 `no_std` 特性时生成使用栈上数组的实现
+    #[cfg(feature = "std")]
+    fn read_from<R: io::Read + ?Sized>(reader: &mut R) -> Result<Self, io::Error> {
+        let mut buf = alloc::vec![0u8; Self::SIZE];
+        reader.read_exact(&mut buf)?;
+        Ok(Self::from_bytes(&buf)?)
+    }
+
+    /// 将多条记录依次编码到一个连续的缓冲区，每条占 `SIZE` 个字节
+    ///
+    /// # 参数
+    /// - `items`: 要编码的记录
+    ///
+    /// # 返回值
+    /// - 长度为 `items.len() * SIZE` 的字节数组
+    fn encode_slice(items: &[Self]) -> Vec<u8> {
+        let mut out = alloc::vec![0u8; items.len() * Self::SIZE];
+        for (i, item) in items.iter().enumerate() {
+            // 剩余长度不小于 `SIZE`，不会失败
+            let _ = item.write_to(&mut out[i * Self::SIZE..]);
+        }
+        out
+    }
+
+    /// 将连续存放的多条记录解码，如读取整个定长记录文件
+    ///
+    /// # 参数
+    /// - `bytes`: 长度必须为 `SIZE` 的整数倍
+    ///
+    /// # 返回值
+    /// - 第一个解码失败的记录的错误；末尾不足 `SIZE` 个字节时返回 [`DecodeError::UnexpectedLength`]
+    fn decode_slice(bytes: &[u8]) -> Result<Vec<Self>, DecodeError> {
+        Self::decode_iter(bytes).collect()
+    }
+
+    /// 逐条解码连续存放的记录，借用输入，不分配内存
+    ///
+    /// # 参数
+    /// - `bytes`: 连续存放的记录
+    ///
+    /// # 返回值
+    /// - 每条记录的解码结果；末尾不足 `SIZE` 个字节时最后产生一个 [`DecodeError::UnexpectedLength`]
+    fn decode_iter(bytes: &[u8]) -> DecodeIter<'_, Self> {
+        DecodeIter { bytes, marker: PhantomData }
+    }
+}
+
+/// [`ByteEncode::decode_iter`] 返回的迭代器，每次解码 `SIZE` 个字节
+pub struct DecodeIter<'a, T> {
+    bytes: &'a [u8],
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<'a, T> DecodeIter<'a, T> {
+    /// 尚未解码的字节
+    #[inline]
+    pub fn remainder(&self) -> &'a [u8] {
+        self.bytes
+    }
+}
+
+impl<T: ByteEncode> Iterator for DecodeIter<'_, T> {
+    type Item = Result<T, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bytes.is_empty() {
+            return None;
+        }
+        // 长度为 0 的类型无法前进，与末尾不完整的记录一样作为错误结束
+        if self.bytes.len() < T::SIZE || T::SIZE == 0 {
+            let got = core::mem::take(&mut self.bytes).len();
+            return Some(Err(DecodeError::UnexpectedLength { expected: T::SIZE, got }));
+        }
+        let (record, rest) = self.bytes.split_at(T::SIZE);
+        self.bytes = rest;
+        Some(T::from_bytes(record))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = match T::SIZE {
+            0 => usize::from(!self.bytes.is_empty()),
+            size => self.bytes.len().div_ceil(size),
+        };
+        (len, Some(len))
+    }
+}
+
+impl<T: ByteEncode> ExactSizeIterator for DecodeIter<'_, T> {}
+
+impl<T: ByteEncode> FusedIterator for DecodeIter<'_, T> {}
+
+/// 按字段布局标注的十六进制转储，由 `#[derive(ByteEncode)]` 生成的 `describe` 返回
+/// - 每行依次为十进制偏移、字段名和该字段的字节；布局之外的字节（保留区域、版本号、多余的数据）标为 `..`
+/// - 数据不足时字段缺少的部分不输出
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::byte_encode::Describe;
+///
+/// let layout = &[("kind", 0, 1), ("length", 2, 2)];
+/// let dump = Describe::new(layout, &[0x01, 0x00, 0x34, 0x12]).to_string();
+/// assert_eq!(dump, "   0  kind    01\n   1  ..      00\n   2  length  34 12\n");
+/// ```
+pub struct Describe<'a> {
+    layout: &'static [(&'static str, usize, usize)],
+    bytes: &'a [u8],
+}
+
+impl<'a> Describe<'a> {
+    /// # 参数
+    /// - `layout`: 各字段的 `(名称, 偏移, 字节数)`，按偏移排列
+    /// - `bytes`: 要标注的数据
+    #[inline]
+    pub fn new(layout: &'static [(&'static str, usize, usize)], bytes: &'a [u8]) -> Self {
+        Describe { layout, bytes }
+    }
+}
+
+impl fmt::Display for Describe<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self.layout.iter().map(|(name, ..)| name.len()).max().unwrap_or(0).max(2);
+        let line = |f: &mut fmt::Formatter<'_>, offset: usize, name: &str, end: usize| {
+            write!(f, "{:>4}  {:<width$}", offset, name, width = width)?;
+            let bytes = self.bytes.get(offset..end.min(self.bytes.len())).unwrap_or_default();
+            bytes.iter().enumerate().try_for_each(|(i, b)| write!(f, "{}{:02x}", if i == 0 { "  " } else { " " }, b))?;
+            writeln!(f)
+        };
+        let mut pos = 0;
+        for &(name, offset, size) in self.layout {
+            if offset > pos && pos < self.bytes.len() {
+                line(f, pos, "..", offset)?;
+            }
+            line(f, offset, name, offset + size)?;
+            pos = pos.max(offset + size);
+        }
+        if pos < self.bytes.len() {
+            line(f, pos, "..", self.bytes.len())?;
+        }
+        Ok(())
+    }
+}
+
+/// 带偏移和编码后字节的字段值，由 `#[byte_encode(debug_hex)]` 生成的 `Debug` 实现使用
+/// - 格式为 `值 @偏移 [十六进制字节]`，偏移为十进制
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::byte_encode::DebugHex;
+///
+/// assert_eq!(format!("{:?}", DebugHex::new(&0x1234u16, 2, &[0x34, 0x12])), "4660 @2 [34 12]");
+/// ```
+pub struct DebugHex<'a, T: ?Sized> {
+    value: &'a T,
+    offset: usize,
+    bytes: &'a [u8],
+}
+
+impl<'a, T: ?Sized> DebugHex<'a, T> {
+    /// # 参数
+    /// - `value`: 字段的值
+    /// - `offset`: 字段在编码结果中的偏移
+    /// - `bytes`: 字段编码后的字节
+    #[inline]
+    pub fn new(value: &'a T, offset: usize, bytes: &'a [u8]) -> Self {
+        DebugHex { value, offset, bytes }
+    }
+}
+
+impl<T: fmt::Debug + ?Sized> fmt::Debug for DebugHex<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)?;
+        write!(f, " @{} [", self.offset)?;
+        self.bytes.iter().enumerate().try_for_each(|(i, b)| write!(f, "{}{:02x}", if i == 0 { "" } else { " " }, b))?;
+        f.write_str("]")
+    }
+}
+
+/// `ByteEncode::write_to` 编码失败的原因
+/// - 可以转换为 `InvalidInput` 类型的 `io::Error`
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::EncodeError;
+///
+/// let err = EncodeError::BufferTooSmall { needed: 8, got: 4 };
+/// assert_eq!(err.to_string(), "buffer too small: need 8 bytes, got 4");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EncodeError {
+    /// 缓冲区长度小于编码长度
+    BufferTooSmall {
+        /// 需要的字节数
+        needed: usize,
+        /// 缓冲区的字节数
+        got: usize,
+    },
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncodeError::BufferTooSmall { needed, got } => write!(f, "buffer too small: need {} bytes, got {}", needed, got),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EncodeError {}
+
+#[cfg(feature = "std")]
+impl From<EncodeError> for io::Error {
+    fn from(err: EncodeError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidInput, err)
+    }
+}
+
+/// `ByteEncode::from_bytes` 解码失败的原因，尽量指明失败的字段
+/// - 字段名为结构体中的字段名，元组结构体为下标；嵌套类型中的错误原样返回，字段名为内层类型的字段
+/// - 启用 `std` 特性时可以转换为 `InvalidData` 类型的 `io::Error`
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::DecodeError;
+/// use std::io;
+///
+/// let err = DecodeError::InvalidBool { field: "enabled", value: 2 };
+/// assert_eq!(err.field(), Some("enabled"));
+/// assert_eq!(err.to_string(), "bool field `enabled` must be 0 or 1, got 2");
+///
+/// let io_err = io::Error::from(err);
+/// assert_eq!(io_err.kind(), io::ErrorKind::InvalidData);
+/// assert_eq!(io_err.get_ref().and_then(|e| e.downcast_ref::<DecodeError>()), Some(&err));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DecodeError {
+    /// 输入的字节数与编码长度不一致
+    UnexpectedLength {
+        /// 需要的字节数
+        expected: usize,
+        /// 实际的字节数
+        got: usize,
+    },
+    /// `bool` 字段的值不是 0 或 1
+    InvalidBool {
+        /// 字段名
+        field: &'static str,
+        /// 读到的值
+        value: u8,
+    },
+    /// `char` 字段不是有效的 Unicode 标量值
+    InvalidChar {
+        /// 字段名
+        field: &'static str,
+        /// 读到的值
+        value: u32,
+    },
+    /// NonZero 字段的值为 0
+    ZeroValue {
+        /// 字段名
+        field: &'static str,
+    },
+    /// 枚举的判别值未知
+    InvalidEnumValue {
+        /// 枚举类型名
+        ty: &'static str,
+        /// 读到的判别值，`u128` 的判别值按位转换
+        value: i128,
+    },
+    /// 校验和字段与计算结果不一致
+    ChecksumMismatch {
+        /// 字段名
+        field: &'static str,
+        /// 数据中存储的校验和
+        stored: u32,
+        /// 按之前的字节计算出的校验和
+        computed: u32,
+    },
+    /// 版本号大于支持的最新版本
+    UnsupportedVersion {
+        /// 读到的版本号
+        version: u8,
+        /// 支持的最新版本号
+        max: u8,
+    },
+    /// 自定义解码函数拒绝了字段的值
+    InvalidValue {
+        /// 字段名
+        field: &'static str,
+        /// 原因
+        reason: &'static str,
+    },
+    /// 帧开头的魔数不匹配
+    InvalidMagic,
+}
+
+impl DecodeError {
+    /// 失败的字段名，与具体字段无关的错误返回 `None`
+    pub const fn field(&self) -> Option<&'static str> {
+        match self {
+            DecodeError::InvalidBool { field, .. }
+            | DecodeError::InvalidChar { field, .. }
+            | DecodeError::ZeroValue { field }
+            | DecodeError::ChecksumMismatch { field, .. }
+            | DecodeError::InvalidValue { field, .. } => Some(field),
+            DecodeError::UnexpectedLength { .. }
+            | DecodeError::InvalidEnumValue { .. }
+            | DecodeError::UnsupportedVersion { .. }
+            | DecodeError::InvalidMagic => None,
+        }
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::UnexpectedLength { expected, got } => write!(f, "expected {} bytes, got {}", expected, got),
+            DecodeError::InvalidBool { field, value } => write!(f, "bool field `{}` must be 0 or 1, got {}", field, value),
+            DecodeError::InvalidChar { field, value } => {
+                write!(f, "char field `{}` is not a valid Unicode scalar value: {:#x}", field, value)
+            }
+            DecodeError::ZeroValue { field } => write!(f, "NonZero field `{}` must not be 0", field),
+            DecodeError::InvalidEnumValue { ty, value } => write!(f, "unknown discriminant {} for `{}`", value, ty),
+            DecodeError::ChecksumMismatch { field, stored, computed } => {
+                write!(f, "checksum mismatch in field `{}`: stored {:#x}, computed {:#x}", field, stored, computed)
+            }
+            DecodeError::UnsupportedVersion { version, max } => write!(f, "unsupported version {}, at most {}", version, max),
+            DecodeError::InvalidValue { field, reason } => write!(f, "invalid value in field `{}`: {}", field, reason),
+            DecodeError::InvalidMagic => write!(f, "frame magic number mismatch"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+#[cfg(feature = "std")]
+impl From<DecodeError> for io::Error {
+    fn from(err: DecodeError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, err)
+    }
+}
@@ -0,0 +1,93 @@
+//! 通过字节表示实现 `serde` 的序列化，`#[byte_encode(serde = "...")]` 生成的实现调用这里的函数
+//! - 也可以在其他 `serde` 类型的字段上用 `#[serde(with = "proc_tools_core::utils_core::byte_encode_serde::hex")]` 使用
+
+use crate::ByteEncode;
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+use serde::de::{self, Deserializer, SeqAccess, Visitor};
+use serde::ser::Serializer;
+
+/// 序列化为字节串，二进制格式中最紧凑；JSON 等格式中为数字数组
+pub mod bytes {
+    use super::*;
+
+    /// 将 `value` 的字节表示序列化为字节串
+    pub fn serialize<T: ByteEncode, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(value.to_bytes().as_ref())
+    }
+
+    /// 从字节串或数字数组解码，解码失败时返回 [`DecodeError`](crate::DecodeError) 的描述
+    pub fn deserialize<'de, T: ByteEncode, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+        deserializer.deserialize_bytes(BytesVisitor(PhantomData))
+    }
+
+    struct BytesVisitor<T>(PhantomData<T>);
+
+    impl<'de, T: ByteEncode> Visitor<'de> for BytesVisitor<T> {
+        type Value = T;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{} bytes", T::SIZE)
+        }
+
+        fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<T, E> {
+            T::from_bytes(v).map_err(E::custom)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<T, A::Error> {
+            let mut bytes = Vec::with_capacity(T::SIZE);
+            while let Some(byte) = seq.next_element::<u8>()? {
+                // 超出长度时提前结束，不继续分配
+                if bytes.len() > T::SIZE {
+                    return Err(de::Error::invalid_length(bytes.len() + 1, &self));
+                }
+                bytes.push(byte);
+            }
+            T::from_bytes(&bytes).map_err(de::Error::custom)
+        }
+    }
+}
+
+/// 序列化为小写十六进制字符串，适合 JSON、TOML 等文本格式；解码时不区分大小写
+pub mod hex {
+    use super::*;
+
+    /// 将 `value` 的字节表示序列化为十六进制字符串
+    pub fn serialize<T: ByteEncode, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&Hex(value.to_bytes().as_ref()))
+    }
+
+    /// 从十六进制字符串解码，字符串无效或解码失败时返回错误
+    pub fn deserialize<'de, T: ByteEncode, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+        deserializer.deserialize_str(HexVisitor(PhantomData))
+    }
+
+    struct Hex<'a>(&'a [u8]);
+
+    impl fmt::Display for Hex<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            self.0.iter().try_for_each(|b| write!(f, "{:02x}", b))
+        }
+    }
+
+    struct HexVisitor<T>(PhantomData<T>);
+
+    impl<T: ByteEncode> Visitor<'_> for HexVisitor<T> {
+        type Value = T;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "a hex string of {} bytes", T::SIZE)
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<T, E> {
+            let digit = |c: u8| (c as char).to_digit(16).map(|d| d as u8);
+            let bytes = match v.len() % 2 {
+                0 => v.as_bytes().chunks_exact(2).map(|pair| Some(digit(pair[0])? << 4 | digit(pair[1])?)).collect::<Option<Vec<u8>>>(),
+                _ => None,
+            };
+            let bytes = bytes.ok_or_else(|| E::invalid_value(de::Unexpected::Str(v), &self))?;
+            T::from_bytes(&bytes).map_err(E::custom)
+        }
+    }
+}
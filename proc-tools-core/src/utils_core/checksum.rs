@@ -0,0 +1,53 @@
+/// CRC-32（IEEE 802.3，反射多项式 `0xEDB88320`）查找表
+static CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// 计算 CRC-32 校验和，与 zlib、以太网、PNG 使用的算法相同
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::checksum::crc32;
+/// assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+/// assert_eq!(crc32(b""), 0);
+/// ```
+#[inline]
+pub fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |crc, &b| CRC32_TABLE[((crc ^ b as u32) & 0xFF) as usize] ^ (crc >> 8))
+}
+
+/// 计算所有字节按 `u8` 回绕相加的和
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::checksum::sum8;
+/// assert_eq!(sum8(&[0x01, 0x02, 0xFF]), 0x02);
+/// ```
+#[inline]
+pub fn sum8(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0u8, |sum, &b| sum.wrapping_add(b))
+}
+
+/// 计算所有字节的异或
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::checksum::xor8;
+/// assert_eq!(xor8(&[0x0F, 0xF0, 0x01]), 0xFE);
+/// ```
+#[inline]
+pub fn xor8(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0u8, |acc, &b| acc ^ b)
+}
@@ -0,0 +1,71 @@
+/// 将 `f32` 转换为 IEEE 754 半精度浮点数（binary16）的位模式，按最近偶数舍入
+/// - 超出范围的值变为无穷大，过小的值变为非规格化数或 0，NaN 保持为 NaN
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::half::f32_to_f16_bits;
+/// assert_eq!(f32_to_f16_bits(1.0), 0x3C00);
+/// assert_eq!(f32_to_f16_bits(-2.5), 0xC100);
+/// assert_eq!(f32_to_f16_bits(65520.0), 0x7C00);
+/// assert_eq!(f32_to_f16_bits(5.960_464_5e-8), 0x0001);
+/// ```
+pub const fn f32_to_f16_bits(value: f32) -> u16 {
+    let x = value.to_bits();
+    let sign = ((x >> 16) & 0x8000) as u16;
+    let exp = ((x >> 23) & 0xFF) as i32;
+    let man = x & 0x7F_FFFF;
+    if exp == 0xFF {
+        // 无穷大或 NaN，NaN 置上最高位的尾数，保证截断后仍为 NaN
+        let nan = if man != 0 { 0x0200 | (man >> 13) as u16 } else { 0 };
+        return sign | 0x7C00 | nan;
+    }
+    let e = exp - 127 + 15;
+    if e >= 0x1F {
+        return sign | 0x7C00;
+    }
+    if e <= 0 {
+        // 非规格化数，补上隐含的最高位后右移
+        if e < -10 {
+            return sign;
+        }
+        let man = man | 0x80_0000;
+        let shift = (14 - e) as u32;
+        let half = man >> shift;
+        let rem = man & ((1 << shift) - 1);
+        let halfway = 1 << (shift - 1);
+        let round = rem > halfway || (rem == halfway && half & 1 == 1);
+        return sign | (half + round as u32) as u16;
+    }
+    // 舍入的进位可以进到指数，最大值进位后恰好为无穷大
+    let half = ((e as u32) << 10) | (man >> 13);
+    let rem = man & 0x1FFF;
+    let round = rem > 0x1000 || (rem == 0x1000 && half & 1 == 1);
+    sign | (half + round as u32) as u16
+}
+
+/// 将 IEEE 754 半精度浮点数（binary16）的位模式转换为 `f32`，转换是精确的
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::half::f16_bits_to_f32;
+/// assert_eq!(f16_bits_to_f32(0x3C00), 1.0);
+/// assert_eq!(f16_bits_to_f32(0xC100), -2.5);
+/// assert_eq!(f16_bits_to_f32(0x0001), 5.960_464_5e-8);
+/// assert!(f16_bits_to_f32(0x7E00).is_nan());
+/// ```
+pub const fn f16_bits_to_f32(bits: u16) -> f32 {
+    let sign = ((bits & 0x8000) as u32) << 16;
+    let exp = ((bits >> 10) & 0x1F) as u32;
+    let man = (bits & 0x3FF) as u32;
+    let x = match exp {
+        0 if man == 0 => sign,
+        // 非规格化数在 `f32` 中是规格化数，左移到隐含的最高位
+        0 => {
+            let shift = man.leading_zeros() - 21;
+            sign | ((113 - shift) << 23) | (((man << shift) & 0x3FF) << 13)
+        }
+        0x1F => sign | 0x7F80_0000 | (man << 13),
+        _ => sign | ((exp + 112) << 23) | (man << 13),
+    };
+    f32::from_bits(x)
+}
@@ -1,4 +1,6 @@
+use crate::float2str::fixed::{F32_FIXED_LEN, F64_FIXED_LEN, format32_fixed, format64_fixed};
 use crate::float2str::pretty::{format32, format64};
+use alloc::string::String;
 
 const I82STR_LEN: usize = 4;
 const I162STR_LEN: usize = 6;
@@ -23,7 +25,7 @@ const ISIZE2STR_SIZE: usize = match size_of::<isize>() {
 const USIZE2STR_LEN: usize = match size_of::<usize>() {
     1 => 3usize,   // 8位系统：1字节
     2 => 5usize,   // 16位系统：2字节
-    4 => 101usize, // 32位系统：4字节
+    4 => 10usize,  // 32位系统：4字节
     8 => 20usize,  // 64位系统：8字节
     16 => 39usize, // 128位系统：16字节
     _ => panic!("{}", "不支持的操作系统位数"),
@@ -101,6 +103,54 @@ impl_itoa_unsigned!(itoa_buf_u64, u64, U642STR_LEN);
 impl_itoa_unsigned!(itoa_buf_u128, u128, U1282STR_LEN);
 impl_itoa_unsigned!(itoa_buf_usize, usize, USIZE2STR_LEN);
 
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+const BIN_DIGITS: &[u8; 2] = b"01";
+
+// 按 2 的幂进制（十六进制、二进制）转换整数，有符号整数按补码输出，与 `format!("{:x}")` 一致
+macro_rules! impl_itoa_radix {
+    ($func_name:ident, $ty:ty, $uty:ty, $buf_size:expr, $bits:expr, $digits:expr) => {
+        #[inline]
+        pub fn $func_name(i_buffer: &mut [u8; $buf_size], i: $ty) -> &[u8] {
+            let mut i = i as $uty;
+            if i == 0 {
+                &[b'0']
+            } else {
+                let mut idx = i_buffer.len();
+                while i > 0 {
+                    idx -= 1;
+                    i_buffer[idx] = $digits[(i & ((1 << $bits) - 1)) as usize];
+                    i >>= $bits;
+                }
+                &i_buffer[idx..]
+            }
+        }
+    };
+}
+impl_itoa_radix!(itoa_buf_i8_hex, i8, u8, 2, 4, HEX_DIGITS);
+impl_itoa_radix!(itoa_buf_i16_hex, i16, u16, 4, 4, HEX_DIGITS);
+impl_itoa_radix!(itoa_buf_i32_hex, i32, u32, 8, 4, HEX_DIGITS);
+impl_itoa_radix!(itoa_buf_i64_hex, i64, u64, 16, 4, HEX_DIGITS);
+impl_itoa_radix!(itoa_buf_i128_hex, i128, u128, 32, 4, HEX_DIGITS);
+impl_itoa_radix!(itoa_buf_isize_hex, isize, usize, usize::BITS as usize / 4, 4, HEX_DIGITS);
+impl_itoa_radix!(itoa_buf_u8_hex, u8, u8, 2, 4, HEX_DIGITS);
+impl_itoa_radix!(itoa_buf_u16_hex, u16, u16, 4, 4, HEX_DIGITS);
+impl_itoa_radix!(itoa_buf_u32_hex, u32, u32, 8, 4, HEX_DIGITS);
+impl_itoa_radix!(itoa_buf_u64_hex, u64, u64, 16, 4, HEX_DIGITS);
+impl_itoa_radix!(itoa_buf_u128_hex, u128, u128, 32, 4, HEX_DIGITS);
+impl_itoa_radix!(itoa_buf_usize_hex, usize, usize, usize::BITS as usize / 4, 4, HEX_DIGITS);
+impl_itoa_radix!(itoa_buf_i8_bin, i8, u8, 8, 1, BIN_DIGITS);
+impl_itoa_radix!(itoa_buf_i16_bin, i16, u16, 16, 1, BIN_DIGITS);
+impl_itoa_radix!(itoa_buf_i32_bin, i32, u32, 32, 1, BIN_DIGITS);
+impl_itoa_radix!(itoa_buf_i64_bin, i64, u64, 64, 1, BIN_DIGITS);
+impl_itoa_radix!(itoa_buf_i128_bin, i128, u128, 128, 1, BIN_DIGITS);
+impl_itoa_radix!(itoa_buf_isize_bin, isize, usize, usize::BITS as usize, 1, BIN_DIGITS);
+impl_itoa_radix!(itoa_buf_u8_bin, u8, u8, 8, 1, BIN_DIGITS);
+impl_itoa_radix!(itoa_buf_u16_bin, u16, u16, 16, 1, BIN_DIGITS);
+impl_itoa_radix!(itoa_buf_u32_bin, u32, u32, 32, 1, BIN_DIGITS);
+impl_itoa_radix!(itoa_buf_u64_bin, u64, u64, 64, 1, BIN_DIGITS);
+impl_itoa_radix!(itoa_buf_u128_bin, u128, u128, 128, 1, BIN_DIGITS);
+impl_itoa_radix!(itoa_buf_usize_bin, usize, usize, usize::BITS as usize, 1, BIN_DIGITS);
+
 /// 将 f32 浮点数转换为字符串并写入缓冲区
 /// - 该函数将浮点数转换为字符串表示形式，支持特殊值（NAN、INFINITY等）的处理，
 ///
@@ -191,6 +241,248 @@ pub fn ftoa_buf_f64(buf: &mut [u8; 24], f: f64) -> &[u8] {
     }
 }
 
+/// 将 f32 浮点数按固定小数位数转换为字符串并写入缓冲区
+/// - 与 `format!("{:.N}")` 相同，按二进制的精确值舍入，恰好为一半时向偶数舍入
+///   - 例如 `2.675` 的精确值略小于 `2.675`，保留两位小数得到 `2.67`
+///   - 超过 17 位有效数字的部分以 `0` 补齐，如 `1e300` 不会输出其二进制近似值的全部数字
+///
+/// # 参数
+/// - `buf`: 用于存储结果的缓冲区，长度至少为 `F32_FIXED_LEN + precision`
+/// - `f`: 要转换的 f32 浮点数
+/// - `precision`: 小数位数
+///
+/// # 返回值
+/// - `&[u8]`: 指向缓冲区中转换结果的字节切片引用
+///
+/// # 注意事项
+/// - 缓冲区长度不足时会 panic
+/// - 对于特殊浮点值（NAN、无穷大）返回预定义的字符串
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::float2str::fixed::F32_FIXED_LEN;
+/// use proc_tools_core::utils_core::impl_to_ascii::ftoa_buf_f32_fixed;
+/// let mut buf = [0u8; F32_FIXED_LEN + 2];
+/// let result = ftoa_buf_f32_fixed(&mut buf, 3.14159f32, 2);
+/// assert_eq!(std::str::from_utf8(result).unwrap(), "3.14");
+///
+/// let result = ftoa_buf_f32_fixed(&mut buf, 2.675f32, 2);
+/// assert_eq!(std::str::from_utf8(result).unwrap(), format!("{:.2}", 2.675f32));
+/// ```
+#[inline]
+pub fn ftoa_buf_f32_fixed<const N: usize>(buf: &mut [u8; N], f: f32, precision: usize) -> &[u8] {
+    assert!(N >= F32_FIXED_LEN + precision, "buffer too small for fixed precision float");
+    let bits = f.to_bits();
+    if bits & 0x7f800000 == 0x7f800000 {
+        if bits & 0x007fffff != 0 {
+            b"NAN"
+        } else if bits & 0x80000000 != 0 {
+            b"NEG_INFINITY"
+        } else {
+            b"INFINITY"
+        }
+    } else {
+        unsafe {
+            let n = format32_fixed(f, precision, buf.as_mut_ptr());
+            core::slice::from_raw_parts(buf.as_ptr(), n)
+        }
+    }
+}
+
+/// 将 f64 浮点数按固定小数位数转换为字符串并写入缓冲区
+/// - 与 `format!("{:.N}")` 相同，按二进制的精确值舍入，恰好为一半时向偶数舍入
+///   - 例如 `2.675` 的精确值略小于 `2.675`，保留两位小数得到 `2.67`
+///   - 超过 17 位有效数字的部分以 `0` 补齐，如 `1e300` 不会输出其二进制近似值的全部数字
+///
+/// # 参数
+/// - `buf`: 用于存储结果的缓冲区，长度至少为 `F64_FIXED_LEN + precision`
+/// - `f`: 要转换的 f64 浮点数
+/// - `precision`: 小数位数
+///
+/// # 返回值
+/// - `&[u8]`: 指向缓冲区中转换结果的字节切片引用
+///
+/// # 注意事项
+/// - 缓冲区长度不足时会 panic
+/// - 对于特殊浮点值（NAN、无穷大）返回预定义的字符串
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::float2str::fixed::F64_FIXED_LEN;
+/// use proc_tools_core::utils_core::impl_to_ascii::ftoa_buf_f64_fixed;
+/// let mut buf = [0u8; F64_FIXED_LEN + 2];
+/// let result = ftoa_buf_f64_fixed(&mut buf, 95.5f64, 2);
+/// assert_eq!(std::str::from_utf8(result).unwrap(), "95.50");
+///
+/// let result = ftoa_buf_f64_fixed(&mut buf, -0.004f64, 2);
+/// assert_eq!(std::str::from_utf8(result).unwrap(), "-0.00");
+///
+/// let result = ftoa_buf_f64_fixed(&mut buf, 2.675f64, 2);
+/// assert_eq!(std::str::from_utf8(result).unwrap(), "2.67");
+///
+/// let result = ftoa_buf_f64_fixed(&mut buf, 123456.785f64, 2);
+/// assert_eq!(std::str::from_utf8(result).unwrap(), "123456.79");
+/// ```
+#[inline]
+pub fn ftoa_buf_f64_fixed<const N: usize>(buf: &mut [u8; N], f: f64, precision: usize) -> &[u8] {
+    assert!(N >= F64_FIXED_LEN + precision, "buffer too small for fixed precision float");
+    let bits = f.to_bits();
+    if bits & 0x7ff0000000000000 == 0x7ff0000000000000 {
+        if bits & 0x000fffffffffffff != 0 {
+            b"NAN"
+        } else if bits & 0x8000000000000000 != 0 {
+            b"NEG_INFINITY"
+        } else {
+            b"INFINITY"
+        }
+    } else {
+        unsafe {
+            let n = format64_fixed(f, precision, buf.as_mut_ptr());
+            core::slice::from_raw_parts(buf.as_ptr(), n)
+        }
+    }
+}
+
+/// 栈上缓冲的格式化写入器
+/// - 用于将实现了 `Display` 的任意类型格式化为字节，以参与 `concat_vars!` 的连接
+/// - 结果不超过 `N` 字节时完全在栈上完成，超出时才转移到堆上的 `String`
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::impl_to_ascii::DisplayBuffer;
+/// use std::net::Ipv4Addr;
+///
+/// let mut buf = DisplayBuffer::<64>::new();
+/// let bytes = buf.render(&Ipv4Addr::new(127, 0, 0, 1));
+/// assert_eq!(bytes, b"127.0.0.1");
+///
+/// let mut small = DisplayBuffer::<2>::new();
+/// assert_eq!(small.render(&"longer than two"), b"longer than two");
+/// ```
+pub struct DisplayBuffer<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+    spill: Option<String>,
+}
+
+impl<const N: usize> DisplayBuffer<N> {
+    #[inline]
+    pub fn new() -> Self {
+        Self { buf: [0u8; N], len: 0, spill: None }
+    }
+
+    /// 将值格式化到缓冲区中，并返回格式化结果的字节切片
+    /// - `Display` 实现返回错误时 panic，与 `ToString::to_string` 的行为一致
+    #[inline]
+    pub fn render<T: core::fmt::Display + ?Sized>(&mut self, value: &T) -> &[u8] {
+        use core::fmt::Write;
+        self.write_fmt(format_args!("{}", value))
+            .expect("a Display implementation returned an error unexpectedly");
+        self.as_bytes()
+    }
+
+    /// 将可能失败的值格式化到缓冲区中，失败时返回其错误
+    #[inline]
+    pub fn try_render<T: TryConcat + ?Sized>(&mut self, value: &T) -> Result<&[u8], T::Error> {
+        value.try_concat(self)?;
+        Ok(self.as_bytes())
+    }
+
+    /// 已写入内容的字节切片
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        match &self.spill {
+            Some(spill) => spill.as_bytes(),
+            None => &self.buf[..self.len],
+        }
+    }
+}
+
+impl<const N: usize> Default for DisplayBuffer<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> core::fmt::Write for DisplayBuffer<N> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        if let Some(spill) = &mut self.spill {
+            spill.push_str(s);
+        } else if self.len + s.len() <= N {
+            self.buf[self.len..self.len + s.len()].copy_from_slice(s.as_bytes());
+            self.len += s.len();
+        } else {
+            let mut spill = String::with_capacity((self.len + s.len()) * 2);
+            // 缓冲区中的内容均来自 `write_str`，一定是有效的 UTF-8
+            spill.push_str(unsafe { core::str::from_utf8_unchecked(&self.buf[..self.len]) });
+            spill.push_str(s);
+            self.spill = Some(spill);
+        }
+        Ok(())
+    }
+}
+
+/// 格式化可能失败的类型，供 `try_concat_vars!` 中注解为 `try_display` 的参数使用
+/// - 所有实现了 `Display` 的类型自动实现，错误类型为 `core::fmt::Error`
+/// - 未实现 `Display` 的类型可自行实现，返回自定义的错误类型
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::impl_to_ascii::{DisplayBuffer, TryConcat};
+/// use core::fmt::Write;
+///
+/// struct Checked(i32);
+/// impl TryConcat for Checked {
+///     type Error = &'static str;
+///     fn try_concat<W: Write + ?Sized>(&self, out: &mut W) -> Result<(), Self::Error> {
+///         if self.0 < 0 {
+///             return Err("negative");
+///         }
+///         write!(out, "{}", self.0).map_err(|_| "write failed")
+///     }
+/// }
+///
+/// let mut buf = DisplayBuffer::<16>::new();
+/// assert_eq!(buf.try_render(&Checked(7)), Ok(&b"7"[..]));
+/// assert_eq!(DisplayBuffer::<16>::new().try_render(&Checked(-1)), Err("negative"));
+/// ```
+pub trait TryConcat {
+    type Error;
+    /// 将值写入 `out`
+    fn try_concat<W: core::fmt::Write + ?Sized>(&self, out: &mut W) -> Result<(), Self::Error>;
+}
+
+impl<T: core::fmt::Display + ?Sized> TryConcat for T {
+    type Error = core::fmt::Error;
+
+    #[inline]
+    fn try_concat<W: core::fmt::Write + ?Sized>(&self, out: &mut W) -> Result<(), Self::Error> {
+        write!(out, "{}", self)
+    }
+}
+
+/// 统计 UTF-8 字节序列中的字符数量
+/// - 只统计非后续字节（`0b10xx_xxxx` 以外的字节），不做完整的 UTF-8 校验
+///
+/// # 参数
+/// - `bytes`: 有效的 UTF-8 字节序列
+///
+/// # 返回值
+/// - `usize`: 字符数量
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::impl_to_ascii::utf8_char_count;
+/// assert_eq!(utf8_char_count("abc".as_bytes()), 3);
+/// assert_eq!(utf8_char_count("你好".as_bytes()), 2);
+/// ```
+#[inline]
+pub fn utf8_char_count(bytes: &[u8]) -> usize {
+    bytes.iter().filter(|&&b| (b as i8) >= -0x40).count()
+}
+
 /// 静态大小连接参数 trait
 /// - 用于处理在字符串连接过程中参数大小已知且固定的类型。
 /// - 这些类型在连接前可以预先确定其字符串表示的最大长度。
@@ -252,7 +544,7 @@ pub trait StaticSizeConcatParameter {
     /// - `var`: 参数的字节切片表示
     /// - `offset`: 当前缓冲区的写入偏移量，方法会更新此值
     ///
-    /// # 安全性
+    /// # Safety
     /// - 调用者需确保 `s_ptr` 指向有效的可写内存区域
     /// - 调用者需确保有足够的空间容纳要写入的数据
     /// - 该方法使用 unsafe 代码进行内存操作，但通过参数验证保证安全
@@ -278,7 +570,7 @@ pub trait StaticSizeConcatParameter {
     ///
     /// assert_eq!(result, "123123");
     /// ```
-    fn concat_parameter(&self, s_ptr: *mut u8, var: &[u8], offset: &mut usize);
+    unsafe fn concat_parameter(&self, s_ptr: *mut u8, var: &[u8], offset: &mut usize);
 }
 macro_rules! impl_static_size_concat_for_int {
     ($type:ty, $len_const:ident, $itoa_fn:ident) => {
@@ -297,9 +589,9 @@ macro_rules! impl_static_size_concat_for_int {
                 vb
             }
             #[inline(always)]
-            fn concat_parameter(&self, s_ptr: *mut u8, vb: &[u8], offset: &mut usize) {
+            unsafe fn concat_parameter(&self, s_ptr: *mut u8, vb: &[u8], offset: &mut usize) {
                 unsafe {
-                    std::ptr::copy_nonoverlapping(vb.as_ptr(), s_ptr.add(*offset), vb.len());
+                    core::ptr::copy_nonoverlapping(vb.as_ptr(), s_ptr.add(*offset), vb.len());
                 }
                 *offset += vb.len();
             }
@@ -316,6 +608,8 @@ impl_static_size_concat_for_int!(u16, U162STR_LEN, itoa_buf_u16);
 impl_static_size_concat_for_int!(u32, U322STR_LEN, itoa_buf_u32);
 impl_static_size_concat_for_int!(u64, U642STR_LEN, itoa_buf_u64);
 impl_static_size_concat_for_int!(u128, U1282STR_LEN, itoa_buf_u128);
+impl_static_size_concat_for_int!(isize, ISIZE2STR_SIZE, itoa_buf_isize);
+impl_static_size_concat_for_int!(usize, USIZE2STR_LEN, itoa_buf_usize);
 impl_static_size_concat_for_int!(f32, F2STR_LEN, ftoa_buf_f32);
 impl_static_size_concat_for_int!(f64, F2STR_LEN, ftoa_buf_f64);
 
@@ -383,7 +677,7 @@ pub trait VariableSizeConcatParameter {
     /// - `buf`: 参数的字节切片表示
     /// - `offset`: 当前缓冲区的写入偏移量，方法会更新此值
     ///
-    /// # 安全性
+    /// # Safety
     /// - 调用者需确保 `s_ptr` 指向有效的可写内存区域
     /// - 调用者需确保有足够的空间容纳要写入的数据
     /// - 该方法使用 unsafe 代码进行内存操作，但通过参数验证保证安全
@@ -409,12 +703,12 @@ pub trait VariableSizeConcatParameter {
     ///
     /// assert_eq!(result, "helloworld");
     /// ```
-    fn concat_parameter(&self, s_ptr: *mut u8, buf: &[u8], offset: &mut usize);
+    unsafe fn concat_parameter(&self, s_ptr: *mut u8, buf: &[u8], offset: &mut usize);
 }
 impl VariableSizeConcatParameter for String {
     #[inline(always)]
     fn first_parameter_for_concat<'a>(&'a self, _bytes: &'a mut [u8]) -> (usize, &'a [u8]) {
-        (self.as_bytes().len(), self.as_bytes())
+        (self.len(), self.as_bytes())
     }
     #[inline(always)]
     fn init_concat_parameter<'a>(&'a self, _bytes: &'a mut [u8], total_len: &mut usize) -> &'a [u8] {
@@ -422,9 +716,9 @@ impl VariableSizeConcatParameter for String {
         self.as_bytes()
     }
     #[inline(always)]
-    fn concat_parameter(&self, s_ptr: *mut u8, vb: &[u8], offset: &mut usize) {
+    unsafe fn concat_parameter(&self, s_ptr: *mut u8, vb: &[u8], offset: &mut usize) {
         unsafe {
-            std::ptr::copy_nonoverlapping(vb.as_ptr(), s_ptr.add(*offset), vb.len());
+            core::ptr::copy_nonoverlapping(vb.as_ptr(), s_ptr.add(*offset), vb.len());
         }
         *offset += vb.len();
     }
@@ -432,7 +726,7 @@ impl VariableSizeConcatParameter for String {
 impl VariableSizeConcatParameter for str {
     #[inline(always)]
     fn first_parameter_for_concat<'a>(&'a self, _bytes: &'a mut [u8]) -> (usize, &'a [u8]) {
-        (self.as_bytes().len(), self.as_bytes())
+        (self.len(), self.as_bytes())
     }
     #[inline(always)]
     fn init_concat_parameter<'a>(&'a self, _bytes: &'a mut [u8], total_len: &mut usize) -> &'a [u8] {
@@ -440,9 +734,9 @@ impl VariableSizeConcatParameter for str {
         self.as_bytes()
     }
     #[inline(always)]
-    fn concat_parameter(&self, s_ptr: *mut u8, vb: &[u8], offset: &mut usize) {
+    unsafe fn concat_parameter(&self, s_ptr: *mut u8, vb: &[u8], offset: &mut usize) {
         unsafe {
-            std::ptr::copy_nonoverlapping(vb.as_ptr(), s_ptr.add(*offset), vb.len());
+            core::ptr::copy_nonoverlapping(vb.as_ptr(), s_ptr.add(*offset), vb.len());
         }
         *offset += vb.len();
     }
@@ -460,9 +754,9 @@ impl VariableSizeConcatParameter for char {
         bytes.as_bytes()
     }
     #[inline(always)]
-    fn concat_parameter(&self, s_ptr: *mut u8, vb: &[u8], offset: &mut usize) {
+    unsafe fn concat_parameter(&self, s_ptr: *mut u8, vb: &[u8], offset: &mut usize) {
         unsafe {
-            std::ptr::copy_nonoverlapping(vb.as_ptr(), s_ptr.add(*offset), vb.len());
+            core::ptr::copy_nonoverlapping(vb.as_ptr(), s_ptr.add(*offset), vb.len());
         }
         *offset += vb.len();
     }
@@ -471,21 +765,21 @@ impl VariableSizeConcatParameter for char {
 impl VariableSizeConcatParameter for bool {
     #[inline(always)]
     fn first_parameter_for_concat<'a>(&self, _bytes: &'a mut [u8]) -> (usize, &'a [u8]) {
-        if *self { (4, b"0") } else { (5, b"0") }
+        if *self { (4, b"true") } else { (5, b"false") }
     }
     #[inline(always)]
     fn init_concat_parameter<'a>(&'a self, _bytes: &'a mut [u8], total_len: &mut usize) -> &'a [u8] {
         *total_len += if *self { 4 } else { 5 };
-        if *self { b"0" } else { b"0" }
+        if *self { b"true" } else { b"false" }
     }
     #[inline(always)]
-    fn concat_parameter(&self, s_ptr: *mut u8, _vb: &[u8], offset: &mut usize) {
+    unsafe fn concat_parameter(&self, s_ptr: *mut u8, _vb: &[u8], offset: &mut usize) {
         unsafe {
             if *self {
-                std::ptr::copy_nonoverlapping(b"true".as_ptr(), s_ptr.add(*offset), 4);
+                core::ptr::copy_nonoverlapping(b"true".as_ptr(), s_ptr.add(*offset), 4);
                 *offset += 4;
             } else {
-                std::ptr::copy_nonoverlapping(b"false".as_ptr(), s_ptr.add(*offset), 5);
+                core::ptr::copy_nonoverlapping(b"false".as_ptr(), s_ptr.add(*offset), 5);
                 *offset += 5;
             }
         }
@@ -0,0 +1,139 @@
+//! 过程宏参数中 `名称 = 表达式` 列表的解析，供编写过程宏时复用（需启用 `syn` 特性）
+//! - 解析时检查重复的名称，错误指向重复的名称
+//! - 按名称查找时可以将表达式重新解析为指定的类型，如 `LitStr`、`LitInt`、`Type`
+//!
+//! # 示例
+//! ```
+//! use proc_tools_core::utils_core::kv_args::KvArgs;
+//!
+//! let args: KvArgs = syn::parse_quote!(name = "id", width = 4, ty = u32);
+//! assert_eq!(args.len(), 3);
+//! args.expect_keys(&["name", "width", "ty"]).unwrap();
+//! let name = args.get_as::<syn::LitStr>("name").unwrap().unwrap();
+//! assert_eq!(name.value(), "id");
+//! let width = args.get_as::<syn::LitInt>("width").unwrap().unwrap();
+//! assert_eq!(width.base10_parse::<u8>().unwrap(), 4);
+//! assert!(args.get_as::<syn::LitStr>("ty").is_err());
+//! assert!(args.get("missing").is_none());
+//!
+//! let duplicate = syn::parse_str::<KvArgs>("a = 1, a = 2");
+//! assert!(duplicate.is_err());
+//! ```
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use quote::ToTokens;
+use syn::parse::{Parse, ParseStream};
+use syn::{Expr, Ident, Token};
+
+/// 按 `def_cn` 特性选择错误信息的语言
+fn tr(cn: String, en: String) -> String {
+    if cfg!(feature = "def_cn") { cn } else { en }
+}
+
+/// `名称 = 表达式` 列表，按书写顺序保存
+#[derive(Clone)]
+pub struct KvArgs {
+    entries: Vec<(Ident, Expr)>,
+}
+
+/// 解析逗号分隔的 `名称 = 表达式` 列表，允许末尾的逗号
+/// - 遇到输入结束或顶层的 `;` 时停止，不消耗 `;`，以便调用方继续解析其后的内容
+///
+/// # 参数
+/// - `input`: 过程宏的输入
+///
+/// # 返回值
+/// - `syn::Result<KvArgs>`: 解析结果
+///
+/// # 错误类型
+/// - 语法错误，或名称重复时在重复的名称上报错
+pub fn parse_kv_args(input: ParseStream) -> syn::Result<KvArgs> {
+    let mut entries: Vec<(Ident, Expr)> = Vec::new();
+    while !input.is_empty() && !input.peek(Token![;]) {
+        let key = input.parse::<Ident>()?;
+        input.parse::<Token![=]>()?;
+        let expr = input.parse::<Expr>()?;
+        if entries.iter().any(|(k, _)| *k == key) {
+            let msg = tr(format!("重复的键 `{}`", key), format!("Duplicate key `{}`", key));
+            return Err(syn::Error::new_spanned(&key, msg));
+        }
+        entries.push((key, expr));
+        if input.is_empty() || input.peek(Token![;]) {
+            break;
+        }
+        input.parse::<Token![,]>()?;
+    }
+    Ok(KvArgs { entries })
+}
+
+impl Parse for KvArgs {
+    /// 解析整个输入，`;` 等多余的内容会报错
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let args = parse_kv_args(input)?;
+        if !input.is_empty() {
+            return Err(input.error(tr(String::from("多余的参数"), String::from("Unexpected tokens"))));
+        }
+        Ok(args)
+    }
+}
+
+impl KvArgs {
+    /// 参数的个数
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// 是否没有参数
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 按书写顺序遍历参数
+    pub fn iter(&self) -> impl Iterator<Item = &(Ident, Expr)> {
+        self.entries.iter()
+    }
+
+    /// 查找名称对应的表达式
+    pub fn get(&self, key: &str) -> Option<&Expr> {
+        self.entries.iter().find(|(k, _)| k == key).map(|(_, expr)| expr)
+    }
+
+    /// 查找名称对应的表达式，并重新解析为指定的类型
+    ///
+    /// # 返回值
+    /// - `syn::Result<Option<T>>`: 没有该名称时为 `Ok(None)`
+    ///
+    /// # 错误类型
+    /// - 表达式不能解析为 `T` 时返回指向表达式的错误
+    pub fn get_as<T: Parse>(&self, key: &str) -> syn::Result<Option<T>> {
+        self.get(key).map(|expr| syn::parse2::<T>(expr.to_token_stream())).transpose()
+    }
+
+    /// 移除并返回名称对应的参数
+    pub fn remove(&mut self, key: &str) -> Option<(Ident, Expr)> {
+        let index = self.entries.iter().position(|(k, _)| k == key)?;
+        Some(self.entries.remove(index))
+    }
+
+    /// 检查所有名称都在 `allowed` 中
+    ///
+    /// # 错误类型
+    /// - 在第一个未知的名称上报错，错误信息列出可用的名称
+    pub fn expect_keys(&self, allowed: &[&str]) -> syn::Result<()> {
+        match self.entries.iter().find(|(k, _)| !allowed.iter().any(|a| k == a)) {
+            Some((key, _)) => {
+                let list = allowed.iter().map(|a| format!("`{}`", a)).collect::<Vec<_>>().join(", ");
+                let msg = tr(format!("未知的键 `{}`，可用的键：{}", key, list), format!("Unknown key `{}`, expected one of {}", key, list));
+                Err(syn::Error::new_spanned(key, msg))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// 转换为按书写顺序排列的参数列表
+    pub fn into_vec(self) -> Vec<(Ident, Expr)> {
+        self.entries
+    }
+}
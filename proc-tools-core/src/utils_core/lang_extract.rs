@@ -0,0 +1,585 @@
+//! 提取源码中 `lang_tr!`、`lang_tr_all!` 的翻译，生成交给翻译人员维护的翻译文件
+//! - 在构建脚本中调用 [`extract_dir`] 扫描 `src` 目录，再用 [`to_json`] 或 [`to_po`] 写入 `OUT_DIR` 或项目目录
+//! - 只提取字符串字面量形式的翻译，`format!(...)` 等其他表达式被忽略；注释和字符串中出现的宏名不会被当作调用
+//!
+//! # 示例
+//! ```no_run
+//! // build.rs，`proc-tools-core` 需要加入 `[build-dependencies]`
+//! use proc_tools_core::utils_core::lang_extract::{extract_dir, to_json};
+//!
+//! let translations = extract_dir("src").unwrap();
+//! let out_dir = std::env::var("OUT_DIR").unwrap();
+//! std::fs::write(format!("{}/translations.json", out_dir), to_json(&translations)).unwrap();
+//! println!("cargo:rerun-if-changed=src");
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+/// 一次宏调用中的翻译
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Translation {
+    /// 所在的文件，由 [`extract_dir`] 填写，[`extract_str`] 返回的为空字符串
+    pub file: String,
+    /// 宏调用所在的行号，从 1 开始
+    pub line: usize,
+    /// 翻译目录中的键（`key = "..."`）
+    pub key: Option<String>,
+    /// 按书写顺序排列的语言和翻译
+    pub entries: Vec<(String, String)>,
+}
+
+impl Translation {
+    /// 获取指定语言的翻译
+    pub fn get(&self, lang: &str) -> Option<&str> {
+        self.entries.iter().find(|(l, _)| l == lang).map(|(_, text)| text.as_str())
+    }
+}
+
+/// 从一段源码中提取所有 `lang_tr!`、`lang_tr_all!` 调用的翻译
+/// - 没有任何字符串字面量翻译且没有 `key` 的调用不会出现在结果中
+///
+/// # 参数
+/// - `src`: Rust 源码
+///
+/// # 返回值
+/// - `Vec<Translation>`: 按出现顺序排列的翻译，`file` 为空字符串
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::lang_extract::extract_str;
+///
+/// let src = r#"
+///     // lang_tr!(cn = "注释中的调用", en = "ignored")
+///     let msg = lang_tr!(cn = "文件不存在", en = "file \"{name}\" not found"; name = path);
+///     let dynamic = lang_tr!(cn = format!("{}", x), en = r"raw\n");
+///     let forced = lang_tr!(cn = "是", en = "yes", lang = "en");
+///     let plain = lang_tr!(plain, cn = "**粗体**", en = "**bold**");
+///     let quotes = ['\'','"']; let after = lang_tr!(cn = "引号之后", en = "after quote");
+/// "#;
+/// let found = extract_str(src);
+/// assert_eq!(found.len(), 5);
+/// assert_eq!(found[0].line, 3);
+/// assert_eq!(found[0].get("en"), Some("file \"{name}\" not found"));
+/// assert_eq!(found[1].get("cn"), None);
+/// assert_eq!(found[1].get("en"), Some("raw\\n"));
+/// assert_eq!(found[2].entries.len(), 2);
+/// assert_eq!(found[2].get("lang"), None);
+/// assert_eq!(found[3].get("en"), Some("**bold**"));
+/// assert_eq!(found[4].get("en"), Some("after quote"));
+/// ```
+pub fn extract_str(src: &str) -> Vec<Translation> {
+    let mut scanner = Scanner { src: src.as_bytes(), pos: 0, line: 1, line_pos: 0 };
+    let mut found = Vec::new();
+    while scanner.pos < scanner.src.len() {
+        let c = scanner.src[scanner.pos];
+        if scanner.skip_comment() {
+            continue;
+        }
+        match c {
+            b'"' => {
+                scanner.string(false);
+            }
+            b'\'' => scanner.char_or_lifetime(),
+            c if c.is_ascii_alphabetic() || c == b'_' => {
+                let start = scanner.pos;
+                let ident = scanner.ident();
+                if scanner.literal_prefix(ident) {
+                    scanner.string(ident.ends_with(b"r"));
+                } else if ident == b"lang_tr" || ident == b"lang_tr_all" {
+                    let line = scanner.line_of(start);
+                    if let Some(translation) = scanner.invocation(line) {
+                        found.push(translation);
+                    }
+                }
+            }
+            c if c.is_ascii_digit() => {
+                // 跳过数字字面量，避免 `1u8` 中的后缀被当作标识符
+                while scanner.pos < scanner.src.len() && (scanner.src[scanner.pos].is_ascii_alphanumeric() || scanner.src[scanner.pos] == b'_') {
+                    scanner.pos += 1;
+                }
+            }
+            _ => scanner.pos += 1,
+        }
+    }
+    found
+}
+
+/// 递归扫描目录中所有 `.rs` 文件，提取其中的翻译
+/// - 文件按路径排序，结果的顺序在每次构建中保持一致
+///
+/// # 参数
+/// - `dir`: 要扫描的目录，通常为 `src`
+///
+/// # 返回值
+/// - `std::io::Result<Vec<Translation>>`: 提取的翻译，`file` 为文件路径
+///
+/// # 错误类型
+/// - 目录或文件无法读取、文件不是有效的 UTF-8 时返回错误
+#[cfg(feature = "std")]
+pub fn extract_dir(dir: impl AsRef<std::path::Path>) -> std::io::Result<Vec<Translation>> {
+    fn walk(dir: &std::path::Path, files: &mut Vec<std::path::PathBuf>) -> std::io::Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                walk(&path, files)?;
+            } else if path.extension().is_some_and(|ext| ext == "rs") {
+                files.push(path);
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = Vec::new();
+    walk(dir.as_ref(), &mut files)?;
+    files.sort();
+
+    let mut found = Vec::new();
+    for path in files {
+        let src = std::fs::read_to_string(&path)?;
+        let file = path.to_string_lossy().into_owned();
+        found.extend(extract_str(&src).into_iter().map(|translation| Translation { file: file.clone(), ..translation }));
+    }
+    Ok(found)
+}
+
+/// 将翻译输出为 JSON 数组，每项为 `{"file", "line", "key", "translations": {语言: 翻译}}`
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::lang_extract::{extract_str, to_json};
+///
+/// let found = extract_str(r#"lang_tr!(cn = "你好", en = "Hello")"#);
+/// assert_eq!(to_json(&found), r#"[{"file":"","line":1,"key":null,"translations":{"cn":"你好","en":"Hello"}}]"#);
+/// ```
+pub fn to_json(translations: &[Translation]) -> String {
+    let mut out = String::from("[");
+    for (i, translation) in translations.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str("{\"file\":");
+        json_str(&mut out, &translation.file);
+        let _ = write!(out, ",\"line\":{},\"key\":", translation.line);
+        match &translation.key {
+            Some(key) => json_str(&mut out, key),
+            None => out.push_str("null"),
+        }
+        out.push_str(",\"translations\":{");
+        for (j, (lang, text)) in translation.entries.iter().enumerate() {
+            if j > 0 {
+                out.push(',');
+            }
+            json_str(&mut out, lang);
+            out.push(':');
+            json_str(&mut out, text);
+        }
+        out.push_str("}}");
+    }
+    out.push(']');
+    out
+}
+
+/// 将翻译输出为 gettext PO 文件，以 `source` 语言的翻译为 `msgid`，`target` 语言的翻译为 `msgstr`
+/// - 没有 `source` 语言翻译的调用被跳过，没有 `target` 语言翻译时 `msgstr` 为空，等待翻译
+/// - `key` 作为 `msgctxt` 输出；相同的 `msgctxt` 和 `msgid` 合并为一项，保留所有出现位置
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::lang_extract::{extract_str, to_po};
+///
+/// let found = extract_str(r#"lang_tr!(cn = "你好", en = "Hello"); lang_tr!(en = "Bye")"#);
+/// let po = to_po(&found, "en", "cn");
+/// assert!(po.contains("#: :1\nmsgid \"Hello\"\nmsgstr \"你好\"\n"));
+/// assert!(po.contains("msgid \"Bye\"\nmsgstr \"\"\n"));
+/// ```
+pub fn to_po(translations: &[Translation], source: &str, target: &str) -> String {
+    /// PO 文件中的一项
+    struct Item<'a> {
+        ctxt: Option<&'a str>,
+        msgid: &'a str,
+        msgstr: &'a str,
+        locations: Vec<(&'a str, usize)>,
+    }
+
+    let mut items: Vec<Item<'_>> = Vec::new();
+    for translation in translations {
+        let Some(msgid) = translation.get(source) else {
+            continue;
+        };
+        let ctxt = translation.key.as_deref();
+        let msgstr = translation.get(target).unwrap_or("");
+        let location = (translation.file.as_str(), translation.line);
+        match items.iter_mut().find(|item| item.ctxt == ctxt && item.msgid == msgid) {
+            Some(item) => item.locations.push(location),
+            None => items.push(Item { ctxt, msgid, msgstr, locations: alloc::vec![location] }),
+        }
+    }
+
+    let mut out = String::new();
+    let _ = write!(out, "msgid \"\"\nmsgstr \"\"\n\"Content-Type: text/plain; charset=UTF-8\\n\"\n\"Language: {}\\n\"\n", target);
+    for Item { ctxt, msgid, msgstr, locations } in items {
+        out.push('\n');
+        out.push_str("#:");
+        for (file, line) in locations {
+            let _ = write!(out, " {}:{}", file, line);
+        }
+        out.push('\n');
+        if let Some(ctxt) = ctxt {
+            out.push_str("msgctxt ");
+            po_str(&mut out, ctxt);
+        }
+        out.push_str("msgid ");
+        po_str(&mut out, msgid);
+        out.push_str("msgstr ");
+        po_str(&mut out, msgstr);
+    }
+    out
+}
+
+/// 以 JSON 字符串的形式写入，包括两侧的引号
+fn json_str(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// 以 PO 字符串的形式写入一行，包括两侧的引号和换行
+fn po_str(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out.push_str("\"\n");
+}
+
+/// 简单的词法扫描器，只识别提取翻译所需的注释、字面量和标识符
+struct Scanner<'a> {
+    src: &'a [u8],
+    pos: usize,
+    /// 已计算行号的位置及其行号，位置只会增大，行号增量计算
+    line: usize,
+    line_pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    /// 计算位置所在的行号，`pos` 不能小于上一次计算的位置
+    fn line_of(&mut self, pos: usize) -> usize {
+        self.line += self.src[self.line_pos..pos].iter().filter(|&&b| b == b'\n').count();
+        self.line_pos = pos;
+        self.line
+    }
+
+    fn peek(&self, offset: usize) -> Option<u8> {
+        self.src.get(self.pos + offset).copied()
+    }
+
+    /// 跳过行注释和（可嵌套的）块注释，当前位置不是注释时返回 `false`
+    fn skip_comment(&mut self) -> bool {
+        match (self.peek(0), self.peek(1)) {
+            (Some(b'/'), Some(b'/')) => {
+                while self.pos < self.src.len() && self.src[self.pos] != b'\n' {
+                    self.pos += 1;
+                }
+                true
+            }
+            (Some(b'/'), Some(b'*')) => {
+                let mut depth = 0;
+                while self.pos < self.src.len() {
+                    match (self.peek(0), self.peek(1)) {
+                        (Some(b'/'), Some(b'*')) => {
+                            depth += 1;
+                            self.pos += 2;
+                        }
+                        (Some(b'*'), Some(b'/')) => {
+                            depth -= 1;
+                            self.pos += 2;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        _ => self.pos += 1,
+                    }
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 跳过空白和注释
+    fn skip_trivia(&mut self) {
+        loop {
+            while self.peek(0).is_some_and(|c| c.is_ascii_whitespace()) {
+                self.pos += 1;
+            }
+            if !self.skip_comment() {
+                break;
+            }
+        }
+    }
+
+    fn ident(&mut self) -> &'a [u8] {
+        let start = self.pos;
+        while self.peek(0).is_some_and(|c| c.is_ascii_alphanumeric() || c == b'_') {
+            self.pos += 1;
+        }
+        &self.src[start..self.pos]
+    }
+
+    /// 刚读取的标识符是否为字符串字面量的前缀（`b"..."`、`r#"..."#` 等）
+    fn literal_prefix(&self, ident: &[u8]) -> bool {
+        match ident {
+            b"b" | b"c" => self.peek(0) == Some(b'"'),
+            b"r" | b"br" | b"cr" => matches!(self.peek(0), Some(b'"' | b'#')) && self.raw_start(),
+            _ => false,
+        }
+    }
+
+    /// 当前位置是否为原始字符串的 `#..."` 部分
+    fn raw_start(&self) -> bool {
+        let hashes = self.src[self.pos..].iter().take_while(|&&c| c == b'#').count();
+        self.peek(hashes) == Some(b'"')
+    }
+
+    /// 读取当前位置（`"` 或原始字符串的 `#`）开始的字符串字面量，返回解码后的内容；格式错误时返回 `None`
+    fn string(&mut self, raw: bool) -> Option<String> {
+        if raw {
+            let hashes = self.src[self.pos..].iter().take_while(|&&c| c == b'#').count();
+            self.pos += hashes + 1;
+            let start = self.pos;
+            while self.pos < self.src.len() {
+                if self.src[self.pos] == b'"' && self.src[self.pos + 1..].iter().take(hashes).filter(|&&c| c == b'#').count() == hashes {
+                    let text = core::str::from_utf8(&self.src[start..self.pos]).ok()?;
+                    self.pos += 1 + hashes;
+                    return Some(String::from(text));
+                }
+                self.pos += 1;
+            }
+            return None;
+        }
+
+        self.pos += 1;
+        let start = self.pos;
+        while self.pos < self.src.len() {
+            match self.src[self.pos] {
+                b'\\' => self.pos += 2,
+                b'"' => {
+                    let raw = core::str::from_utf8(&self.src[start..self.pos]).ok()?;
+                    self.pos += 1;
+                    return unescape(raw);
+                }
+                _ => self.pos += 1,
+            }
+        }
+        None
+    }
+
+    /// 跳过字符字面量或生命周期
+    fn char_or_lifetime(&mut self) {
+        if self.peek(1) == Some(b'\\') {
+            // 跳过整个转义序列，`'\''` 中转义的 `'` 不是结束的引号
+            self.pos += 3;
+            while self.pos < self.src.len() && self.src[self.pos] != b'\'' {
+                self.pos += 1;
+            }
+            self.pos += 1;
+            return;
+        }
+        // 字符可能是多字节的 UTF-8，找到下一个字符的起始位置
+        let next = (self.pos + 2..self.src.len().min(self.pos + 6)).find(|&i| self.src[i] & 0xC0 != 0x80);
+        match next {
+            Some(end) if self.src[end] == b'\'' => self.pos = end + 1,
+            _ => self.pos += 1,
+        }
+    }
+
+    /// 跳过一个表达式，停在顶层的 `,`、`;` 或右括号处
+    fn skip_expr(&mut self) {
+        let mut depth = 0usize;
+        while self.pos < self.src.len() {
+            if self.skip_comment() {
+                continue;
+            }
+            match self.src[self.pos] {
+                b'(' | b'[' | b'{' => depth += 1,
+                b')' | b']' | b'}' if depth == 0 => return,
+                b')' | b']' | b'}' => depth -= 1,
+                b',' | b';' if depth == 0 => return,
+                b'"' => {
+                    self.string(false);
+                    continue;
+                }
+                b'\'' => {
+                    self.char_or_lifetime();
+                    continue;
+                }
+                c if c.is_ascii_alphabetic() || c == b'_' => {
+                    let ident = self.ident();
+                    if self.literal_prefix(ident) {
+                        self.string(ident.ends_with(b"r"));
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+            self.pos += 1;
+        }
+    }
+
+    /// 读取当前位置的字符串字面量（含 `r"..."` 形式），不是字符串字面量时不移动位置并返回 `None`
+    fn string_literal(&mut self) -> Option<String> {
+        match self.peek(0) {
+            Some(b'"') => self.string(false),
+            Some(b'r') if matches!(self.peek(1), Some(b'"' | b'#')) => {
+                self.pos += 1;
+                if self.raw_start() {
+                    self.string(true)
+                } else {
+                    self.pos -= 1;
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// 解析宏名之后的 `!(...)`，格式不符时返回 `None`
+    fn invocation(&mut self, line: usize) -> Option<Translation> {
+        self.skip_trivia();
+        if self.peek(0) != Some(b'!') {
+            return None;
+        }
+        self.pos += 1;
+        self.skip_trivia();
+        let close = match self.peek(0)? {
+            b'(' => b')',
+            b'[' => b']',
+            b'{' => b'}',
+            _ => return None,
+        };
+        self.pos += 1;
+
+        let mut translation = Translation { file: String::new(), line, key: None, entries: Vec::new() };
+        loop {
+            self.skip_trivia();
+            match self.peek(0)? {
+                c if c == close => {
+                    self.pos += 1;
+                    break;
+                }
+                // `;` 之后为占位符参数，不含翻译
+                b';' => {
+                    self.pos += 1;
+                    loop {
+                        self.skip_expr();
+                        match self.peek(0) {
+                            Some(b',' | b';') => self.pos += 1,
+                            _ => break,
+                        }
+                    }
+                    continue;
+                }
+                b',' => {
+                    self.pos += 1;
+                    continue;
+                }
+                c if c.is_ascii_alphabetic() || c == b'_' => {}
+                _ => return None,
+            }
+            let name = String::from(core::str::from_utf8(self.ident()).ok()?);
+            self.skip_trivia();
+            // 开头的 `plain` 标志不是翻译
+            if name == "plain" && matches!(self.peek(0), Some(c) if c == close || c == b',') {
+                continue;
+            }
+            if self.peek(0) != Some(b'=') {
+                return None;
+            }
+            self.pos += 1;
+            self.skip_trivia();
+
+            let text = self.string_literal();
+            self.skip_trivia();
+            let complete = matches!(self.peek(0), Some(c) if c == close || c == b',' || c == b';');
+            match text {
+                // 字面量之后还有内容（如 `"a".to_string()`）时不是字面量翻译
+                Some(text) if complete => match name.as_str() {
+                    "key" => translation.key = Some(text),
+                    // `lang = "en"` 指定输出的语言，不是翻译
+                    "lang" => {}
+                    _ => translation.entries.push((name, text)),
+                },
+                _ => self.skip_expr(),
+            }
+            if self.peek(0) == Some(b',') {
+                self.pos += 1;
+            }
+        }
+
+        (translation.key.is_some() || !translation.entries.is_empty()).then_some(translation)
+    }
+}
+
+/// 解码普通字符串字面量中的转义序列，转义无效时返回 `None`
+fn unescape(raw: &str) -> Option<String> {
+    let mut out = String::with_capacity(raw.len());
+    let mut chars = raw.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            // 字符串中的 `\r\n` 换行按 `\n` 处理，与编译器一致
+            if c == '\r' && chars.peek() == Some(&'\n') {
+                continue;
+            }
+            out.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => out.push('\n'),
+            'r' => out.push('\r'),
+            't' => out.push('\t'),
+            '0' => out.push('\0'),
+            '\\' => out.push('\\'),
+            '\'' => out.push('\''),
+            '"' => out.push('"'),
+            'x' => {
+                let hex: String = chars.by_ref().take(2).collect();
+                out.push(char::from(u8::from_str_radix(&hex, 16).ok()?));
+            }
+            'u' => {
+                if chars.next()? != '{' {
+                    return None;
+                }
+                let hex: String = chars.by_ref().take_while(|&c| c != '}').filter(|&c| c != '_').collect();
+                out.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?);
+            }
+            // 行尾的 `\` 跳过换行和下一行开头的空白
+            '\n' | '\r' => {
+                while chars.peek().is_some_and(|c| c.is_whitespace()) {
+                    chars.next();
+                }
+            }
+            _ => return None,
+        }
+    }
+    Some(out)
+}
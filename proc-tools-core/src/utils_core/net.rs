@@ -0,0 +1,252 @@
+use crate::utils_core::impl_to_ascii::{itoa_buf_u16, itoa_buf_u32};
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+/// `Ipv4Addr` 格式化所需的缓冲区长度，如 `255.255.255.255`
+pub const IPV4_LEN: usize = 15;
+/// `Ipv6Addr`、`IpAddr` 格式化所需的缓冲区长度，8 组 4 位十六进制数和 7 个 `:`
+pub const IPV6_LEN: usize = 39;
+/// `SocketAddrV4` 格式化所需的缓冲区长度，如 `255.255.255.255:65535`
+pub const SOCKET_V4_LEN: usize = 21;
+/// `SocketAddrV6`、`SocketAddr` 格式化所需的缓冲区长度，如 `[地址%4294967295]:65535`
+pub const SOCKET_V6_LEN: usize = 58;
+
+static HEX_LOWER: &[u8; 16] = b"0123456789abcdef";
+
+/// 依次向缓冲区写入内容的游标
+struct Cursor<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> Cursor<'a> {
+    #[inline]
+    fn new(buf: &'a mut [u8]) -> Self {
+        Self { buf, len: 0 }
+    }
+
+    #[inline]
+    fn push(&mut self, bytes: &[u8]) {
+        self.buf[self.len..self.len + bytes.len()].copy_from_slice(bytes);
+        self.len += bytes.len();
+    }
+
+    #[inline]
+    fn push_u8(&mut self, mut n: u8) {
+        if n >= 100 {
+            self.buf[self.len] = b'0' + n / 100;
+            self.len += 1;
+            n %= 100;
+            self.buf[self.len] = b'0' + n / 10;
+            self.len += 1;
+        } else if n >= 10 {
+            self.buf[self.len] = b'0' + n / 10;
+            self.len += 1;
+        }
+        self.buf[self.len] = b'0' + n % 10;
+        self.len += 1;
+    }
+
+    /// 以不带前导零的小写十六进制写入一组 IPv6 地址
+    #[inline]
+    fn push_hex(&mut self, n: u16) {
+        let digits = (16 - n.leading_zeros().min(12) as usize).div_ceil(4);
+        for i in (0..digits).rev() {
+            self.buf[self.len] = HEX_LOWER[(n >> (i * 4)) as usize & 0xf];
+            self.len += 1;
+        }
+    }
+
+    #[inline]
+    fn push_ipv4(&mut self, addr: &Ipv4Addr) {
+        for (i, octet) in addr.octets().into_iter().enumerate() {
+            if i > 0 {
+                self.push(b".");
+            }
+            self.push_u8(octet);
+        }
+    }
+
+    /// 与标准库的 `Display` 一致：IPv4 映射地址写为 `::ffff:a.b.c.d`，最长的连续零组（至少两组）压缩为 `::`
+    fn push_ipv6(&mut self, addr: &Ipv6Addr) {
+        if let Some(ipv4) = addr.to_ipv4_mapped() {
+            self.push(b"::ffff:");
+            self.push_ipv4(&ipv4);
+            return;
+        }
+        let segments = addr.segments();
+        let (mut start, mut len, mut current_start, mut current_len) = (0, 0, 0, 0);
+        for (i, &segment) in segments.iter().enumerate() {
+            if segment == 0 {
+                if current_len == 0 {
+                    current_start = i;
+                }
+                current_len += 1;
+                if current_len > len {
+                    (start, len) = (current_start, current_len);
+                }
+            } else {
+                current_len = 0;
+            }
+        }
+        if len > 1 {
+            self.push_segments(&segments[..start]);
+            self.push(b"::");
+            self.push_segments(&segments[start + len..]);
+        } else {
+            self.push_segments(&segments);
+        }
+    }
+
+    #[inline]
+    fn push_segments(&mut self, segments: &[u16]) {
+        for (i, &segment) in segments.iter().enumerate() {
+            if i > 0 {
+                self.push(b":");
+            }
+            self.push_hex(segment);
+        }
+    }
+
+    #[inline]
+    fn push_port(&mut self, port: u16) {
+        let mut buf = [0u8; 5];
+        self.push(b":");
+        self.push(itoa_buf_u16(&mut buf, port));
+    }
+
+    #[inline]
+    fn finish(self) -> &'a [u8] {
+        &self.buf[..self.len]
+    }
+}
+
+/// 将 IPv4 地址格式化并写入缓冲区，结果与标准库的 `Display` 一致
+///
+/// # 参数
+/// - `buf`: 用于存储结果的缓冲区
+/// - `addr`: 要格式化的地址
+///
+/// # 返回值
+/// - `&[u8]`: 指向缓冲区中格式化结果的字节切片引用
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::net::{IPV4_LEN, ipv4_buf};
+/// use std::net::Ipv4Addr;
+///
+/// let mut buf = [0u8; IPV4_LEN];
+/// assert_eq!(ipv4_buf(&mut buf, &Ipv4Addr::new(192, 168, 0, 10)), b"192.168.0.10");
+/// ```
+#[inline]
+pub fn ipv4_buf<'a>(buf: &'a mut [u8; IPV4_LEN], addr: &Ipv4Addr) -> &'a [u8] {
+    let mut cursor = Cursor::new(buf);
+    cursor.push_ipv4(addr);
+    cursor.finish()
+}
+
+/// 将 IPv6 地址格式化并写入缓冲区，结果与标准库的 `Display` 一致
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::net::{IPV6_LEN, ipv6_buf};
+/// use std::net::{Ipv4Addr, Ipv6Addr};
+///
+/// let mut buf = [0u8; IPV6_LEN];
+/// assert_eq!(ipv6_buf(&mut buf, &Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 1, 0, 0, 1)), b"2001:db8::1:0:0:1");
+/// assert_eq!(ipv6_buf(&mut buf, &Ipv6Addr::LOCALHOST), b"::1");
+/// assert_eq!(ipv6_buf(&mut buf, &Ipv4Addr::new(10, 0, 0, 1).to_ipv6_mapped()), b"::ffff:10.0.0.1");
+/// ```
+#[inline]
+pub fn ipv6_buf<'a>(buf: &'a mut [u8; IPV6_LEN], addr: &Ipv6Addr) -> &'a [u8] {
+    let mut cursor = Cursor::new(buf);
+    cursor.push_ipv6(addr);
+    cursor.finish()
+}
+
+/// 将 IP 地址格式化并写入缓冲区，结果与标准库的 `Display` 一致
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::net::{IPV6_LEN, ip_buf};
+/// use std::net::IpAddr;
+///
+/// let mut buf = [0u8; IPV6_LEN];
+/// assert_eq!(ip_buf(&mut buf, &"10.1.2.3".parse::<IpAddr>().unwrap()), b"10.1.2.3");
+/// assert_eq!(ip_buf(&mut buf, &"fe80::1".parse::<IpAddr>().unwrap()), b"fe80::1");
+/// ```
+#[inline]
+pub fn ip_buf<'a>(buf: &'a mut [u8; IPV6_LEN], addr: &IpAddr) -> &'a [u8] {
+    let mut cursor = Cursor::new(buf);
+    match addr {
+        IpAddr::V4(addr) => cursor.push_ipv4(addr),
+        IpAddr::V6(addr) => cursor.push_ipv6(addr),
+    }
+    cursor.finish()
+}
+
+/// 将 IPv4 套接字地址格式化并写入缓冲区，如 `127.0.0.1:8080`
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::net::{SOCKET_V4_LEN, socket_v4_buf};
+/// use std::net::{Ipv4Addr, SocketAddrV4};
+///
+/// let mut buf = [0u8; SOCKET_V4_LEN];
+/// assert_eq!(socket_v4_buf(&mut buf, &SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8080)), b"127.0.0.1:8080");
+/// ```
+#[inline]
+pub fn socket_v4_buf<'a>(buf: &'a mut [u8; SOCKET_V4_LEN], addr: &SocketAddrV4) -> &'a [u8] {
+    let mut cursor = Cursor::new(buf);
+    cursor.push_ipv4(addr.ip());
+    cursor.push_port(addr.port());
+    cursor.finish()
+}
+
+/// 将 IPv6 套接字地址格式化并写入缓冲区，如 `[::1]:443`，`scope_id` 不为 0 时写为 `[fe80::1%2]:443`
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::net::{SOCKET_V6_LEN, socket_v6_buf};
+/// use std::net::{Ipv6Addr, SocketAddrV6};
+///
+/// let mut buf = [0u8; SOCKET_V6_LEN];
+/// assert_eq!(socket_v6_buf(&mut buf, &SocketAddrV6::new(Ipv6Addr::LOCALHOST, 443, 0, 0)), b"[::1]:443");
+/// let link_local = SocketAddrV6::new("fe80::1".parse().unwrap(), 443, 0, 2);
+/// assert_eq!(socket_v6_buf(&mut buf, &link_local), b"[fe80::1%2]:443");
+/// ```
+#[inline]
+pub fn socket_v6_buf<'a>(buf: &'a mut [u8; SOCKET_V6_LEN], addr: &SocketAddrV6) -> &'a [u8] {
+    let mut cursor = Cursor::new(buf);
+    cursor.push(b"[");
+    cursor.push_ipv6(addr.ip());
+    if addr.scope_id() != 0 {
+        let mut scope = [0u8; 10];
+        cursor.push(b"%");
+        cursor.push(itoa_buf_u32(&mut scope, addr.scope_id()));
+    }
+    cursor.push(b"]");
+    cursor.push_port(addr.port());
+    cursor.finish()
+}
+
+/// 将套接字地址格式化并写入缓冲区，结果与标准库的 `Display` 一致
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::net::{SOCKET_V6_LEN, socket_buf};
+/// use std::net::SocketAddr;
+///
+/// let mut buf = [0u8; SOCKET_V6_LEN];
+/// assert_eq!(socket_buf(&mut buf, &"10.0.0.1:53".parse::<SocketAddr>().unwrap()), b"10.0.0.1:53");
+/// assert_eq!(socket_buf(&mut buf, &"[2001:db8::8]:80".parse::<SocketAddr>().unwrap()), b"[2001:db8::8]:80");
+/// ```
+#[inline]
+pub fn socket_buf<'a>(buf: &'a mut [u8; SOCKET_V6_LEN], addr: &SocketAddr) -> &'a [u8] {
+    match addr {
+        SocketAddr::V4(addr) => {
+            let (head, _) = buf.split_first_chunk_mut::<SOCKET_V4_LEN>().unwrap();
+            socket_v4_buf(head, addr)
+        }
+        SocketAddr::V6(addr) => socket_v6_buf(buf, addr),
+    }
+}
@@ -0,0 +1,51 @@
+/// `concat_vars_write!` 的写入目标包装
+/// - 同时支持 `std::io::Write` 与 `core::fmt::Write`：目标实现了 `io::Write` 时优先使用 [`IoSink`]，否则使用 [`FmtSink`]
+/// - 两个 trait 需同时引入作用域，通过 `(&mut SinkWrap(w)).write_concat(s)` 调用，由方法解析选择实现
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::sink::{FmtSink, IoSink, SinkWrap};
+///
+/// let mut out: Vec<u8> = Vec::new();
+/// let res: std::io::Result<()> = (&mut SinkWrap(&mut out)).write_concat("io");
+/// assert!(res.is_ok());
+/// assert_eq!(out, b"io");
+///
+/// let mut text = String::new();
+/// let res: std::fmt::Result = (&mut SinkWrap(&mut text)).write_concat("fmt");
+/// assert!(res.is_ok());
+/// assert_eq!(text, "fmt");
+/// ```
+pub struct SinkWrap<'a, W: ?Sized>(pub &'a mut W);
+
+/// 写入 `std::io::Write` 目标，错误类型为 `std::io::Error`
+/// - 仅在启用 `std` 特性时有实现
+pub trait IoSink {
+    type Error;
+    fn write_concat(&mut self, s: &str) -> Result<(), Self::Error>;
+}
+
+/// 写入 `core::fmt::Write` 目标，错误类型为 `core::fmt::Error`
+pub trait FmtSink {
+    type Error;
+    fn write_concat(&mut self, s: &str) -> Result<(), Self::Error>;
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write + ?Sized> IoSink for SinkWrap<'_, W> {
+    type Error = std::io::Error;
+
+    #[inline]
+    fn write_concat(&mut self, s: &str) -> std::io::Result<()> {
+        self.0.write_all(s.as_bytes())
+    }
+}
+
+impl<W: core::fmt::Write + ?Sized> FmtSink for &mut SinkWrap<'_, W> {
+    type Error = core::fmt::Error;
+
+    #[inline]
+    fn write_concat(&mut self, s: &str) -> core::fmt::Result {
+        self.0.write_str(s)
+    }
+}
@@ -0,0 +1,231 @@
+use crate::utils_core::impl_to_ascii::itoa_buf_u64;
+use core::time::Duration;
+
+/// RFC3339 格式化所需的缓冲区长度
+/// - `YYYY-MM-DDTHH:MM:SS.mmmZ` 为 24 字节，年份超过 4 位时会更长，按 `u64` 秒数的上限预留
+pub const RFC3339_LEN: usize = 32;
+/// 毫秒时间戳格式化所需的缓冲区长度
+pub const MILLIS_LEN: usize = 20;
+/// 时长格式化所需的缓冲区长度
+/// - 最长为以微秒输出 `Duration::MAX`：26 位整数、`.` 和 3 位小数，再加上单位 `us`
+pub const DURATION_LEN: usize = 32;
+
+/// Unix 时间：自 1970-01-01T00:00:00Z 起的秒数，以及可选的毫秒部分
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnixTime {
+    pub secs: u64,
+    /// 毫秒部分（0-999），为 `None` 时 RFC3339 格式不输出小数秒
+    pub millis: Option<u16>,
+}
+
+/// 可转换为 Unix 时间的类型
+/// - `u64`：Unix 秒数
+/// - `Duration`：自 Unix 纪元起经过的时间，精确到毫秒
+/// - `SystemTime`（需启用 `std` 特性）：早于 Unix 纪元的时间按纪元处理
+pub trait ToUnixTime {
+    fn to_unix_time(&self) -> UnixTime;
+}
+
+impl ToUnixTime for u64 {
+    #[inline]
+    fn to_unix_time(&self) -> UnixTime {
+        UnixTime { secs: *self, millis: None }
+    }
+}
+
+impl ToUnixTime for Duration {
+    #[inline]
+    fn to_unix_time(&self) -> UnixTime {
+        UnixTime { secs: self.as_secs(), millis: Some(self.subsec_millis() as u16) }
+    }
+}
+
+#[cfg(feature = "std")]
+impl ToUnixTime for std::time::SystemTime {
+    #[inline]
+    fn to_unix_time(&self) -> UnixTime {
+        self.duration_since(std::time::UNIX_EPOCH).unwrap_or_default().to_unix_time()
+    }
+}
+
+/// 将 Unix 时间格式化为 RFC3339（UTC）字符串并写入缓冲区
+/// - 有毫秒部分时输出 `YYYY-MM-DDTHH:MM:SS.mmmZ`，否则输出 `YYYY-MM-DDTHH:MM:SSZ`
+///
+/// # 参数
+/// - `buf`: 用于存储结果的缓冲区
+/// - `t`: 要格式化的 Unix 时间
+///
+/// # 返回值
+/// - `&[u8]`: 指向缓冲区中格式化结果的字节切片引用
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::timestamp::{RFC3339_LEN, ToUnixTime, timestamp_buf_rfc3339};
+/// use std::time::Duration;
+///
+/// let mut buf = [0u8; RFC3339_LEN];
+/// assert_eq!(timestamp_buf_rfc3339(&mut buf, 1_700_000_000u64.to_unix_time()), b"2023-11-14T22:13:20Z");
+///
+/// let t = Duration::from_millis(951_782_400_007).to_unix_time();
+/// assert_eq!(timestamp_buf_rfc3339(&mut buf, t), b"2000-02-29T00:00:00.007Z");
+/// ```
+pub fn timestamp_buf_rfc3339(buf: &mut [u8; RFC3339_LEN], t: UnixTime) -> &[u8] {
+    let days = t.secs / 86_400;
+    let rem = t.secs % 86_400;
+    let (year, month, day) = civil_from_days(days);
+
+    let mut year_buf = [0u8; 20];
+    let year_bytes = itoa_buf_u64(&mut year_buf, year);
+    let mut idx = 0;
+    // 年份至少 4 位，不足时补 0
+    for _ in year_bytes.len()..4 {
+        buf[idx] = b'0';
+        idx += 1;
+    }
+    buf[idx..idx + year_bytes.len()].copy_from_slice(year_bytes);
+    idx += year_bytes.len();
+
+    for (sep, value) in [(b'-', month), (b'-', day), (b'T', rem / 3600), (b':', rem % 3600 / 60), (b':', rem % 60)] {
+        buf[idx] = sep;
+        buf[idx + 1] = b'0' + (value / 10) as u8;
+        buf[idx + 2] = b'0' + (value % 10) as u8;
+        idx += 3;
+    }
+    if let Some(millis) = t.millis {
+        buf[idx] = b'.';
+        buf[idx + 1] = b'0' + (millis / 100 % 10) as u8;
+        buf[idx + 2] = b'0' + (millis / 10 % 10) as u8;
+        buf[idx + 3] = b'0' + (millis % 10) as u8;
+        idx += 4;
+    }
+    buf[idx] = b'Z';
+    &buf[..idx + 1]
+}
+
+/// 将 Unix 时间格式化为毫秒时间戳并写入缓冲区
+/// - 没有毫秒部分时按 0 毫秒处理，超出 `u64` 范围时饱和到 `u64::MAX`
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::timestamp::{MILLIS_LEN, ToUnixTime, timestamp_buf_millis};
+/// use std::time::Duration;
+///
+/// let mut buf = [0u8; MILLIS_LEN];
+/// assert_eq!(timestamp_buf_millis(&mut buf, Duration::from_millis(1_700_000_000_123).to_unix_time()), b"1700000000123");
+/// assert_eq!(timestamp_buf_millis(&mut buf, 5u64.to_unix_time()), b"5000");
+/// ```
+#[inline]
+pub fn timestamp_buf_millis(buf: &mut [u8; MILLIS_LEN], t: UnixTime) -> &[u8] {
+    let millis = t.secs.saturating_mul(1000).saturating_add(t.millis.unwrap_or(0) as u64);
+    itoa_buf_u64(buf, millis)
+}
+
+/// 将自 Unix 纪元起的天数转换为公历日期 `(年, 月, 日)`
+/// - 算法来自 Howard Hinnant 的 `civil_from_days`
+#[inline]
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as u64;
+    (year, month, day)
+}
+
+/// 将时长以秒为单位格式化并写入缓冲区，保留 3 位小数（毫秒），如 `1.234s`
+///
+/// # 参数
+/// - `buf`: 用于存储结果的缓冲区
+/// - `d`: 要格式化的时长
+///
+/// # 返回值
+/// - `&[u8]`: 指向缓冲区中格式化结果的字节切片引用
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::timestamp::{DURATION_LEN, duration_buf_secs};
+/// use std::time::Duration;
+///
+/// let mut buf = [0u8; DURATION_LEN];
+/// assert_eq!(duration_buf_secs(&mut buf, Duration::from_micros(1_234_567)), b"1.234s");
+/// assert_eq!(duration_buf_secs(&mut buf, Duration::from_millis(5)), b"0.005s");
+/// ```
+#[inline]
+pub fn duration_buf_secs(buf: &mut [u8; DURATION_LEN], d: Duration) -> &[u8] {
+    duration_buf(buf, d.as_secs() as u128, Some(d.subsec_millis()), b"s")
+}
+
+/// 将时长以毫秒为单位格式化并写入缓冲区，保留 3 位小数（微秒），如 `1234.567ms`
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::timestamp::{DURATION_LEN, duration_buf_millis};
+/// use std::time::Duration;
+///
+/// let mut buf = [0u8; DURATION_LEN];
+/// assert_eq!(duration_buf_millis(&mut buf, Duration::from_nanos(1_234_567_890)), b"1234.567ms");
+/// ```
+#[inline]
+pub fn duration_buf_millis(buf: &mut [u8; DURATION_LEN], d: Duration) -> &[u8] {
+    duration_buf(buf, d.as_millis(), Some(d.subsec_micros() % 1000), b"ms")
+}
+
+/// 将时长以微秒为单位格式化并写入缓冲区，保留 3 位小数（纳秒），如 `1234567.890us`
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::timestamp::{DURATION_LEN, duration_buf_micros};
+/// use std::time::Duration;
+///
+/// let mut buf = [0u8; DURATION_LEN];
+/// assert_eq!(duration_buf_micros(&mut buf, Duration::from_nanos(1_234_567_890)), b"1234567.890us");
+/// assert_eq!(duration_buf_micros(&mut buf, Duration::MAX).len(), DURATION_LEN);
+/// ```
+#[inline]
+pub fn duration_buf_micros(buf: &mut [u8; DURATION_LEN], d: Duration) -> &[u8] {
+    duration_buf(buf, d.as_micros(), Some(d.subsec_nanos() % 1000), b"us")
+}
+
+/// 将时长以纳秒为单位格式化并写入缓冲区，如 `1234567890ns`
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::timestamp::{DURATION_LEN, duration_buf_nanos};
+/// use std::time::Duration;
+///
+/// let mut buf = [0u8; DURATION_LEN];
+/// assert_eq!(duration_buf_nanos(&mut buf, Duration::from_nanos(1_234_567_890)), b"1234567890ns");
+/// ```
+#[inline]
+pub fn duration_buf_nanos(buf: &mut [u8; DURATION_LEN], d: Duration) -> &[u8] {
+    duration_buf(buf, d.as_nanos(), None, b"ns")
+}
+
+/// 从缓冲区末尾向前依次写入单位、3 位小数和整数部分
+#[inline]
+fn duration_buf<'a>(buf: &'a mut [u8; DURATION_LEN], mut whole: u128, frac: Option<u32>, unit: &[u8]) -> &'a [u8] {
+    let mut idx = DURATION_LEN - unit.len();
+    buf[idx..].copy_from_slice(unit);
+    if let Some(mut frac) = frac {
+        for _ in 0..3 {
+            idx -= 1;
+            buf[idx] = b'0' + (frac % 10) as u8;
+            frac /= 10;
+        }
+        idx -= 1;
+        buf[idx] = b'.';
+    }
+    loop {
+        idx -= 1;
+        buf[idx] = b'0' + (whole % 10) as u8;
+        whole /= 10;
+        if whole == 0 {
+            break;
+        }
+    }
+    &buf[idx..]
+}
@@ -0,0 +1,353 @@
+use core::ptr;
+
+/// JSON 转义表：0 表示无需转义，`b'u'` 表示以 `\u00XX` 转义，其余为 `\` 之后的转义字符
+static JSON_ESCAPE: [u8; 256] = {
+    let mut table = [0u8; 256];
+    let mut i = 0;
+    while i < 0x20 {
+        table[i] = b'u';
+        i += 1;
+    }
+    table[0x08] = b'b';
+    table[0x09] = b't';
+    table[0x0a] = b'n';
+    table[0x0c] = b'f';
+    table[0x0d] = b'r';
+    table[b'"' as usize] = b'"';
+    table[b'\\' as usize] = b'\\';
+    table
+};
+
+static HEX_LOWER: &[u8; 16] = b"0123456789abcdef";
+
+/// 计算字节序列按 JSON 字符串内容转义后的长度
+/// - 转义双引号、反斜杠和控制字符，不包含两侧的引号
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::transform::json_escaped_len;
+/// assert_eq!(json_escaped_len(b"plain"), 5);
+/// assert_eq!(json_escaped_len(b"a\"b\n\x01"), 12);
+/// ```
+#[inline]
+pub fn json_escaped_len(bytes: &[u8]) -> usize {
+    bytes
+        .iter()
+        .map(|&b| match JSON_ESCAPE[b as usize] {
+            0 => 1,
+            b'u' => 6,
+            _ => 2,
+        })
+        .sum()
+}
+
+/// 将字节序列按 JSON 字符串内容转义后写入 `dst`，返回写入的字节数
+///
+/// # Safety
+/// - `dst` 必须至少有 [`json_escaped_len`] 返回的长度可写
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::transform::{json_escape_to, json_escaped_len};
+/// let src = "say \"hi\"\t\\ 你好".as_bytes();
+/// let mut buf = vec![0u8; json_escaped_len(src)];
+/// let n = unsafe { json_escape_to(src, buf.as_mut_ptr()) };
+/// assert_eq!(&buf[..n], "say \\\"hi\\\"\\t\\\\ 你好".as_bytes());
+/// ```
+#[inline]
+pub unsafe fn json_escape_to(bytes: &[u8], dst: *mut u8) -> usize {
+    let mut offset = 0;
+    let mut start = 0;
+    unsafe {
+        for (i, &b) in bytes.iter().enumerate() {
+            let escape = JSON_ESCAPE[b as usize];
+            if escape == 0 {
+                continue;
+            }
+            // 先整段复制无需转义的部分
+            ptr::copy_nonoverlapping(bytes.as_ptr().add(start), dst.add(offset), i - start);
+            offset += i - start;
+            start = i + 1;
+            *dst.add(offset) = b'\\';
+            if escape == b'u' {
+                ptr::copy_nonoverlapping(b"u00".as_ptr(), dst.add(offset + 1), 3);
+                *dst.add(offset + 4) = HEX_LOWER[(b >> 4) as usize];
+                *dst.add(offset + 5) = HEX_LOWER[(b & 0xf) as usize];
+                offset += 6;
+            } else {
+                *dst.add(offset + 1) = escape;
+                offset += 2;
+            }
+        }
+        ptr::copy_nonoverlapping(bytes.as_ptr().add(start), dst.add(offset), bytes.len() - start);
+    }
+    offset + bytes.len() - start
+}
+
+/// 计算字节序列作为 CSV 字段输出后的长度
+/// - 包含 `,`、`"`、`\r`、`\n` 时两侧加双引号，并将内部的 `"` 写为 `""`，否则原样输出
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::transform::csv_escaped_len;
+/// assert_eq!(csv_escaped_len(b"plain"), 5);
+/// assert_eq!(csv_escaped_len(b"a,b"), 5);
+/// assert_eq!(csv_escaped_len(b"say \"hi\""), 12);
+/// ```
+#[inline]
+pub fn csv_escaped_len(bytes: &[u8]) -> usize {
+    let mut quotes = 0;
+    let mut needs_quote = false;
+    for &b in bytes {
+        match b {
+            b'"' => {
+                quotes += 1;
+                needs_quote = true;
+            }
+            b',' | b'\r' | b'\n' => needs_quote = true,
+            _ => {}
+        }
+    }
+    if needs_quote { bytes.len() + quotes + 2 } else { bytes.len() }
+}
+
+/// 将字节序列作为 CSV 字段写入 `dst`，返回写入的字节数
+///
+/// # Safety
+/// - `dst` 必须至少有 [`csv_escaped_len`] 返回的长度可写
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::transform::{csv_escape_to, csv_escaped_len};
+/// let src = b"say \"hi\", bye";
+/// let mut buf = vec![0u8; csv_escaped_len(src)];
+/// let n = unsafe { csv_escape_to(src, buf.as_mut_ptr()) };
+/// assert_eq!(&buf[..n], b"\"say \"\"hi\"\", bye\"");
+/// ```
+#[inline]
+pub unsafe fn csv_escape_to(bytes: &[u8], dst: *mut u8) -> usize {
+    if !bytes.iter().any(|&b| matches!(b, b'"' | b',' | b'\r' | b'\n')) {
+        unsafe { ptr::copy_nonoverlapping(bytes.as_ptr(), dst, bytes.len()) };
+        return bytes.len();
+    }
+    let mut offset = 1;
+    unsafe {
+        *dst = b'"';
+        for &b in bytes {
+            if b == b'"' {
+                *dst.add(offset) = b'"';
+                offset += 1;
+            }
+            *dst.add(offset) = b;
+            offset += 1;
+        }
+        *dst.add(offset) = b'"';
+    }
+    offset + 1
+}
+
+/// 将字节序列中的 ASCII 字母转换为大写后写入 `dst`，返回写入的字节数
+/// - 非 ASCII 字节原样复制，长度不变
+///
+/// # Safety
+/// - `dst` 必须至少有 `bytes.len()` 字节可写
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::transform::ascii_upper_to;
+/// let mut buf = [0u8; 16];
+/// let n = unsafe { ascii_upper_to("content-type:é".as_bytes(), buf.as_mut_ptr()) };
+/// assert_eq!(&buf[..n], "CONTENT-TYPE:é".as_bytes());
+/// ```
+#[inline]
+pub unsafe fn ascii_upper_to(bytes: &[u8], dst: *mut u8) -> usize {
+    for (i, &b) in bytes.iter().enumerate() {
+        unsafe { *dst.add(i) = b.to_ascii_uppercase() };
+    }
+    bytes.len()
+}
+
+/// 将字节序列中的 ASCII 字母转换为小写后写入 `dst`，返回写入的字节数
+/// - 非 ASCII 字节原样复制，长度不变
+///
+/// # Safety
+/// - `dst` 必须至少有 `bytes.len()` 字节可写
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::transform::ascii_lower_to;
+/// let mut buf = [0u8; 16];
+/// let n = unsafe { ascii_lower_to(b"X-Request-ID", buf.as_mut_ptr()) };
+/// assert_eq!(&buf[..n], b"x-request-id");
+/// ```
+#[inline]
+pub unsafe fn ascii_lower_to(bytes: &[u8], dst: *mut u8) -> usize {
+    for (i, &b) in bytes.iter().enumerate() {
+        unsafe { *dst.add(i) = b.to_ascii_lowercase() };
+    }
+    bytes.len()
+}
+
+/// 计算字节序列以十六进制输出后的长度，每个字节两位，字节之间插入 `sep`
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::transform::hexdump_len;
+/// assert_eq!(hexdump_len(&[0xde, 0xad, 0xbe], b""), 6);
+/// assert_eq!(hexdump_len(&[0xde, 0xad, 0xbe], b" "), 8);
+/// assert_eq!(hexdump_len(&[], b" "), 0);
+/// ```
+#[inline]
+pub fn hexdump_len(bytes: &[u8], sep: &[u8]) -> usize {
+    if bytes.is_empty() { 0 } else { bytes.len() * 2 + sep.len() * (bytes.len() - 1) }
+}
+
+/// 将字节序列以小写十六进制写入 `dst`，字节之间插入 `sep`，返回写入的字节数
+///
+/// # Safety
+/// - `dst` 必须至少有 [`hexdump_len`] 返回的长度可写
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::transform::{hexdump_len, hexdump_to};
+/// let src = [0x01, 0xab, 0xff];
+/// let mut buf = vec![0u8; hexdump_len(&src, b":")];
+/// let n = unsafe { hexdump_to(&src, b":", buf.as_mut_ptr()) };
+/// assert_eq!(&buf[..n], b"01:ab:ff");
+/// ```
+#[inline]
+pub unsafe fn hexdump_to(bytes: &[u8], sep: &[u8], dst: *mut u8) -> usize {
+    let mut offset = 0;
+    for (i, &b) in bytes.iter().enumerate() {
+        unsafe {
+            if i > 0 {
+                ptr::copy_nonoverlapping(sep.as_ptr(), dst.add(offset), sep.len());
+                offset += sep.len();
+            }
+            *dst.add(offset) = HEX_LOWER[(b >> 4) as usize];
+            *dst.add(offset + 1) = HEX_LOWER[(b & 0xf) as usize];
+        }
+        offset += 2;
+    }
+    offset
+}
+
+/// 计算十进制整数按每三位插入分隔符后的长度
+/// - `bytes` 为可带负号的十进制数字，`sep_len` 为分隔符的字节数
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::transform::grouped_len;
+/// assert_eq!(grouped_len(b"1234567", 1), 9);
+/// assert_eq!(grouped_len(b"-123", 1), 4);
+/// ```
+#[inline]
+pub fn grouped_len(bytes: &[u8], sep_len: usize) -> usize {
+    let digits = bytes.len() - bytes.starts_with(b"-") as usize;
+    bytes.len() + digits.saturating_sub(1) / 3 * sep_len
+}
+
+/// 将十进制整数按每三位插入分隔符后写入 `dst`，返回写入的字节数
+///
+/// # Safety
+/// - `dst` 必须至少有 [`grouped_len`] 返回的长度可写
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::transform::grouped_to;
+/// let mut buf = [0u8; 32];
+/// let n = unsafe { grouped_to(b"-1234567", b",", buf.as_mut_ptr()) };
+/// assert_eq!(&buf[..n], b"-1,234,567");
+/// let n = unsafe { grouped_to(b"999", b"_", buf.as_mut_ptr()) };
+/// assert_eq!(&buf[..n], b"999");
+/// ```
+#[inline]
+pub unsafe fn grouped_to(bytes: &[u8], sep: &[u8], dst: *mut u8) -> usize {
+    let sign = bytes.starts_with(b"-") as usize;
+    let digits = &bytes[sign..];
+    let mut offset = 0;
+    unsafe {
+        if sign == 1 {
+            *dst = b'-';
+            offset += 1;
+        }
+        for (i, &b) in digits.iter().enumerate() {
+            if i > 0 && (digits.len() - i) % 3 == 0 {
+                ptr::copy_nonoverlapping(sep.as_ptr(), dst.add(offset), sep.len());
+                offset += sep.len();
+            }
+            *dst.add(offset) = b;
+            offset += 1;
+        }
+    }
+    offset
+}
+
+/// 计算数字文本按地区格式输出后的长度
+/// - `bytes` 为十进制数字文本（可带负号、小数部分或指数），整数部分每三位插入 `group_len` 字节的分隔符，小数点替换后长度不变
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::transform::localized_len;
+/// assert_eq!(localized_len(b"-1234567.25", 1), 13);
+/// assert_eq!(localized_len(b"1234.5", 0), 6);
+/// ```
+#[inline]
+pub fn localized_len(bytes: &[u8], group_len: usize) -> usize {
+    let sign = bytes.starts_with(b"-") as usize;
+    let digits = bytes[sign..].iter().take_while(|b| b.is_ascii_digit()).count();
+    bytes.len() + digits.saturating_sub(1) / 3 * group_len
+}
+
+/// 将数字文本按地区格式写入 `dst`，返回写入的字节数
+/// - 小数点替换为 `decimal`，整数部分每三位插入 `group`，`group` 为空时不分组
+///
+/// # Safety
+/// - `dst` 必须至少有 [`localized_len`] 返回的长度可写
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::transform::localized_to;
+/// let mut buf = [0u8; 32];
+/// let n = unsafe { localized_to(b"-1234567.25", b',', b".", buf.as_mut_ptr()) };
+/// assert_eq!(&buf[..n], b"-1.234.567,25");
+/// let n = unsafe { localized_to(b"0.5", b',', b"", buf.as_mut_ptr()) };
+/// assert_eq!(&buf[..n], b"0,5");
+/// ```
+#[inline]
+pub unsafe fn localized_to(bytes: &[u8], decimal: u8, group: &[u8], dst: *mut u8) -> usize {
+    let sign = bytes.starts_with(b"-") as usize;
+    let digits = bytes[sign..].iter().take_while(|b| b.is_ascii_digit()).count();
+    let mut offset = 0;
+    unsafe {
+        if group.is_empty() {
+            ptr::copy_nonoverlapping(bytes.as_ptr(), dst, sign + digits);
+            offset = sign + digits;
+        } else {
+            offset += grouped_to(&bytes[..sign + digits], group, dst);
+        }
+        for &b in &bytes[sign + digits..] {
+            *dst.add(offset) = if b == b'.' { decimal } else { b };
+            offset += 1;
+        }
+    }
+    offset
+}
+
+/// 原样返回调用方保证为有效 UTF-8 的字节序列，供 `concat_vars!` 中注解为 `raw_bytes` 的参数使用
+/// - 写入时直接复制，不做转换；调试构建中会校验 UTF-8，发布构建中不做检查
+///
+/// # Safety
+/// - `bytes` 必须是有效的 UTF-8，否则连接结果不是有效的 `str`
+///
+/// # 示例
+/// ```
+/// use proc_tools_core::utils_core::transform::assume_utf8;
+/// let cached = br#"{"id":1}"#;
+/// assert_eq!(unsafe { assume_utf8(cached) }, cached);
+/// ```
+#[inline]
+pub unsafe fn assume_utf8(bytes: &[u8]) -> &[u8] {
+    debug_assert!(core::str::from_utf8(bytes).is_ok(), "a raw_bytes argument is not valid UTF-8");
+    bytes
+}
@@ -0,0 +1,11 @@
+//! 声明 `lang_tr!` 读取的构建时环境变量
+//! - 过程宏在展开时读取环境变量，Cargo 不会跟踪这种读取，修改变量后已编译的 crate 不会重新展开
+//! - 在此以 `cargo:rerun-if-env-changed` 声明后，变量变化时重新运行构建脚本并重新编译本 crate，
+//!   依赖本 crate 的 crate（如 `proc_tools` 及下游 crate）随之重新编译，按新的语言展开
+//! - 声明了 `rerun-if-env-changed` 后，项目文件的修改不再使构建脚本重新运行
+
+fn main() {
+    for name in ["PROC_TOOLS_LANG", "PROC_TOOLS_LANG_FALLBACK", "PROC_TOOLS_LANG_CATALOG"] {
+        println!("cargo:rerun-if-env-changed={}", name);
+    }
+}
@@ -1,40 +1,406 @@
 extern crate proc_macro;
 
 use proc_macro::TokenStream;
-use quote::quote;
+use proc_macro2::Span;
+use proc_tools_core::utils_core::kv_args::{KvArgs, parse_kv_args};
+use quote::{format_ident, quote};
+use std::collections::BTreeSet;
+use std::path::PathBuf;
 use syn::Expr;
 use syn::parse::Parse;
 use syn::parse_macro_input;
-use syn::{Error, Ident, Token, parse::ParseStream};
+use syn::spanned::Spanned;
+use syn::{Error, Ident, LitStr, Token, parse::ParseStream};
 
-#[cfg(any(feature = "def_cn"))]
+#[cfg(feature = "def_cn")]
 fn get_def_lang() -> Box<str> {
     Box::from("cn")
 }
-#[cfg(any(feature = "def_en"))]
+#[cfg(all(feature = "def_en", not(feature = "def_cn")))]
 fn get_def_lang() -> Box<str> {
     Box::from("en")
 }
+// 未启用默认语言特性时使用英文，启用 `strict_lang` 时报错
+#[cfg(not(any(feature = "def_cn", feature = "def_en")))]
+fn get_def_lang() -> Box<str> {
+    Box::from("en")
+}
+
+#[cfg(all(feature = "def_cn", feature = "def_en"))]
+compile_error!("Cannot enable both 'def_cn' and 'def_en' features simultaneously\n不能同时启用 'def_cn' 和 'def_en' 特性");
+
+#[cfg(all(feature = "strict_lang", not(any(feature = "def_cn", feature = "def_en"))))]
+compile_error!(
+    "The 'strict_lang' feature requires one of the 'def_cn' or 'def_en' features\n启用 'strict_lang' 特性时必须启用 'def_cn' 或 'def_en' 特性"
+);
+
+/// 按默认语言选择宏自身的错误信息
+fn tr(cn: &str, en: &str) -> String {
+    match get_def_lang().as_ref() {
+        "cn" => cn.to_string(),
+        _ => en.to_string(),
+    }
+}
+
+/// 选择语言的环境变量，设置后优先于 `def_*` 特性
+/// - 特性在工作空间中会合并，环境变量可以为单次构建选择语言，如 `PROC_TOOLS_LANG=en cargo build`
+/// - 过程宏读取的环境变量不被 Cargo 跟踪，由 `build.rs` 中的 `cargo:rerun-if-env-changed` 在变量变化时触发重新编译
+const LANG_ENV: &str = "PROC_TOOLS_LANG";
+/// 回退链的环境变量，逗号分隔，如 `ja,en`
+const LANG_FALLBACK_ENV: &str = "PROC_TOOLS_LANG_FALLBACK";
+
+/// 获取按优先级排列的语言列表
+/// - 依次为 `PROC_TOOLS_LANG`、`PROC_TOOLS_LANG_FALLBACK` 中的语言、`def_*` 特性选择的默认语言，最后为 `en`、`cn`
+/// - 重复的语言只保留第一次出现的位置
+///
+/// # 返回值
+/// - `Vec<String>`: 语言键的列表，`lang_tr!` 选择其中第一个提供了翻译的语言
+fn lang_chain() -> Vec<String> {
+    let env = |name| std::env::var(name).unwrap_or_default();
+    let selected = env(LANG_ENV);
+    let fallback = env(LANG_FALLBACK_ENV);
+    let def_lang = get_def_lang();
+
+    let mut chain: Vec<String> = Vec::new();
+    let langs = std::iter::once(selected.as_str())
+        .chain(fallback.split(','))
+        .chain([def_lang.as_ref(), "en", "cn"])
+        .map(|lang| lang.trim().to_ascii_lowercase())
+        .filter(|lang| !lang.is_empty());
+    for lang in langs {
+        if !chain.contains(&lang) {
+            chain.push(lang);
+        }
+    }
+    chain
+}
+
+/// 翻译目录的环境变量，值为 TOML 文件的路径，相对路径相对于调用方 crate 的 `Cargo.toml` 所在目录
+const CATALOG_ENV: &str = "PROC_TOOLS_LANG_CATALOG";
+
+/// 从翻译目录中查找键对应的各语言翻译
+/// - 键按 `.` 分隔为 TOML 的表路径，最后一级为以语言为键、字符串为值的表
+///
+/// # 参数
+/// - `key`: 翻译的键，如 `"error.file_not_found"`
+///
+/// # 返回值
+/// - `syn::Result<(PathBuf, Vec<(String, String)>)>`: 目录文件的绝对路径，以及按文件中顺序排列的语言和翻译
+///
+/// # 错误类型
+/// - 未设置环境变量、文件无法读取或解析、键不存在、值不是字符串时，在键上报错
+fn catalog_lookup(key: &LitStr) -> syn::Result<(PathBuf, Vec<(String, String)>)> {
+    let Ok(path) = std::env::var(CATALOG_ENV) else {
+        let msg = tr(
+            &format!("使用 `key` 需要通过环境变量 `{}` 指定翻译目录文件", CATALOG_ENV),
+            &format!("Using `key` requires a catalog file set by the `{}` environment variable", CATALOG_ENV),
+        );
+        return Err(Error::new_spanned(key, msg));
+    };
+    let mut path = PathBuf::from(path);
+    if let Some(dir) = std::env::var_os("CARGO_MANIFEST_DIR").filter(|_| path.is_relative()) {
+        path = PathBuf::from(dir).join(path);
+    }
+
+    let catalog = std::fs::read_to_string(&path)
+        .map_err(|err| err.to_string())
+        .and_then(|text| text.parse::<toml::Table>().map_err(|err| err.to_string()));
+    let catalog = match catalog {
+        Ok(catalog) => catalog,
+        Err(err) => {
+            let msg = tr(
+                &format!("无法读取翻译目录 `{}`：{}", path.display(), err),
+                &format!("Failed to read catalog `{}`: {}", path.display(), err),
+            );
+            return Err(Error::new_spanned(key, msg));
+        }
+    };
+
+    let name = key.value();
+    let entry = name.split('.').try_fold(&catalog, |table, part| table.get(part)?.as_table());
+    let Some(entry) = entry else {
+        let msg = tr(
+            &format!("翻译目录 `{}` 中没有键 `{}`", path.display(), name),
+            &format!("Key `{}` not found in catalog `{}`", name, path.display()),
+        );
+        return Err(Error::new_spanned(key, msg));
+    };
+
+    let mut translations = Vec::new();
+    for (lang, value) in entry {
+        let Some(value) = value.as_str() else {
+            let msg = tr(
+                &format!("翻译目录中 `{}.{}` 的值必须是字符串", name, lang),
+                &format!("Value of `{}.{}` in the catalog must be a string", name, lang),
+            );
+            return Err(Error::new_spanned(key, msg));
+        };
+        translations.push((lang.clone(), value.to_string()));
+    }
+    Ok((path, translations))
+}
+
+/// 模板字符串中的一段
+enum Segment {
+    /// 原样输出的文本
+    Text(String),
+    /// `{name}` 形式的占位符
+    Placeholder(String),
+}
+
+/// 将模板字符串拆分为文本和占位符
+/// - `{name}` 为占位符，`name` 必须是标识符；`{{`、`}}` 分别表示 `{`、`}`
+///
+/// # 参数
+/// - `text`: 模板字符串
+///
+/// # 返回值
+/// - `Result<Vec<Segment>, String>`: 按顺序排列的各段，相邻的文本合并为一段；格式错误时返回错误信息
+fn parse_template(text: &str) -> Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    let mut buf = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                buf.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                buf.push('}');
+            }
+            '{' => {
+                let mut name = String::new();
+                let mut closed = false;
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        closed = true;
+                        break;
+                    }
+                    name.push(c);
+                }
+                let valid = closed
+                    && name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+                    && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+                if !valid {
+                    return Err(tr(
+                        &format!("无效的占位符 `{{{}}}`，应为 `{{名称}}` 形式，`{{` 需写为 `{{{{`", name),
+                        &format!("Invalid placeholder `{{{}}}`, expected `{{name}}`; write `{{{{` for a literal `{{`", name),
+                    ));
+                }
+                if !buf.is_empty() {
+                    segments.push(Segment::Text(std::mem::take(&mut buf)));
+                }
+                segments.push(Segment::Placeholder(name));
+            }
+            '}' => return Err(tr("未匹配的 `}`，需写为 `}}`", "Unmatched `}`, write `}}` for a literal `}`")),
+            c => buf.push(c),
+        }
+    }
+    if !buf.is_empty() {
+        segments.push(Segment::Text(buf));
+    }
+    Ok(segments)
+}
+
+/// 检查各语言的翻译中占位符的名称是否一致
+/// - 只检查字符串字面量（包括翻译目录中的翻译），其他表达式在展开前无法得知内容
+/// - 不替换占位符时 `{` 可能是普通字符，只有各翻译都含有占位符时才检查，存在格式错误的翻译时不检查
+///
+/// # 参数
+/// - `translations`: 各语言的翻译
+/// - `strict`: 是否替换占位符，为 `true` 时格式错误的翻译也会报错
+///
+/// # 返回值
+/// - `syn::Result<()>`: 占位符不一致时在不一致的翻译上报错
+fn check_placeholders(translations: &[(String, Expr)], strict: bool) -> syn::Result<()> {
+    let mut found = Vec::new();
+    for (lang, expr) in translations {
+        let Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit), .. }) = expr else {
+            continue;
+        };
+        let segments = match parse_template(&lit.value()) {
+            Ok(segments) => segments,
+            Err(msg) if strict => return Err(Error::new_spanned(lit, msg)),
+            Err(_) => return Ok(()),
+        };
+        let names = segments
+            .into_iter()
+            .filter_map(|segment| match segment {
+                Segment::Placeholder(name) => Some(name),
+                Segment::Text(_) => None,
+            })
+            .collect::<BTreeSet<String>>();
+        found.push((lang, lit, names));
+    }
+    if !strict && found.iter().any(|(_, _, names)| names.is_empty()) {
+        return Ok(());
+    }
+
+    let mut first: Option<(&String, &BTreeSet<String>)> = None;
+    for (lang, lit, names) in &found {
+        match first {
+            None => first = Some((lang, names)),
+            Some((first_lang, first_names)) if first_names != names => {
+                let fmt = |names: &BTreeSet<String>| names.iter().map(|name| format!("{{{}}}", name)).collect::<Vec<_>>().join(", ");
+                let (expected, actual) = (fmt(first_names), fmt(names));
+                let msg = tr(
+                    &format!("`{}` 的占位符 [{}] 与 `{}` 的占位符 [{}] 不一致", lang, actual, first_lang, expected),
+                    &format!("Placeholders [{}] in `{}` do not match placeholders [{}] in `{}`", actual, lang, expected, first_lang),
+                );
+                return Err(Error::new_spanned(lit, msg));
+            }
+            Some(_) => {}
+        }
+    }
+    Ok(())
+}
+
+/// 去掉字符串中的 Markdown 强调和代码标记，得到适合终端输出的纯文本
+/// - 去掉 `` ` ``，代码中的内容原样保留
+/// - 去掉 `*`、`**`、`_`、`__`、`~~` 等强调标记；两侧都是空白的 `*`（如 `a * b`）和单词中的 `_`（如 `file_name`）不是标记，原样保留
+/// - `\*` 等转义的标记字符去掉反斜杠后保留
+///
+/// # 参数
+/// - `text`: Markdown 文本
+///
+/// # 返回值
+/// - `String`: 去掉标记后的文本
+fn strip_markdown(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut in_code = false;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '`' {
+            in_code = !in_code;
+            i += chars[i..].iter().take_while(|&&b| b == '`').count();
+            continue;
+        }
+        if in_code {
+            out.push(c);
+            i += 1;
+            continue;
+        }
+        if c == '\\' && matches!(chars.get(i + 1), Some('*' | '_' | '~' | '`' | '\\')) {
+            out.push(chars[i + 1]);
+            i += 2;
+            continue;
+        }
+        if matches!(c, '*' | '_' | '~') {
+            let run = chars[i..].iter().take_while(|&&b| b == c).count();
+            let before = i.checked_sub(1).map(|j| chars[j]);
+            let after = chars.get(i + run).copied();
+            let solid = |ch: Option<char>| ch.is_some_and(|ch| !ch.is_whitespace());
+            let delimiter = match c {
+                '*' => solid(before) || solid(after),
+                '_' => {
+                    let word = |ch: Option<char>| ch.is_some_and(char::is_alphanumeric);
+                    (solid(before) || solid(after)) && !(word(before) && word(after))
+                }
+                _ => run == 2,
+            };
+            if !delimiter {
+                out.extend(&chars[i..i + run]);
+            }
+            i += run;
+            continue;
+        }
+        out.push(c);
+        i += 1;
+    }
+    out
+}
+
+/// 生成替换占位符后的连接代码，展开为 `proc_tools::concat_vars!`，结果为 `String`
+/// - 占位符的值取自 `;` 之后同名的参数，没有对应参数时直接使用同名的变量
+/// - 每个参数只求值一次，多次出现的占位符共用同一个值
+///
+/// # 参数
+/// - `lit`: 选中的翻译
+/// - `bindings`: `;` 之后的参数
+///
+/// # 返回值
+/// - `syn::Result<proc_macro2::TokenStream>`: 连接代码，模板格式错误时在字符串上报错
+fn interpolate(lit: &LitStr, bindings: &[(Ident, Expr)]) -> syn::Result<proc_macro2::TokenStream> {
+    let segments = parse_template(&lit.value()).map_err(|msg| Error::new_spanned(lit, msg))?;
+
+    let mut lets = Vec::new();
+    let mut bound: Vec<String> = Vec::new();
+    let mut parts = Vec::new();
+    for segment in &segments {
+        match segment {
+            Segment::Text(text) => parts.push(quote! { #text }),
+            Segment::Placeholder(name) => match bindings.iter().find(|(ident, _)| ident == name) {
+                Some((ident, expr)) => {
+                    let var = format_ident!("__lang_tr_{}", ident, span = Span::mixed_site());
+                    if !bound.contains(name) {
+                        bound.push(name.clone());
+                        lets.push(quote! { let #var = &(#expr); });
+                    }
+                    parts.push(quote! { #var });
+                }
+                None => {
+                    let var = Ident::new(name, lit.span());
+                    parts.push(quote! { #var });
+                }
+            },
+        }
+    }
+    if parts.is_empty() {
+        parts.push(quote! { "" });
+    }
+
+    Ok(quote! {{
+        #(#lets)*
+        ::proc_tools::concat_vars!(#(#parts),* => String)
+    }})
+}
 
-// 解析参数结构体
+// 解析参数结构体，按书写顺序保存各语言的翻译
 struct Args {
-    cn: Expr,
-    en: Expr,
+    translations: Vec<(Ident, Expr)>,
+    /// 翻译目录中的键
+    key: Option<LitStr>,
+    /// `;` 之后的占位符参数，没有 `;` 时为 `None`，不替换占位符
+    bindings: Option<Vec<(Ident, Expr)>>,
+    /// `lang = en` 指定的语言，及其所在的表达式
+    lang: Option<(String, Expr)>,
+    /// 开头的 `plain` 标志，去掉翻译中的 Markdown 标记
+    plain: bool,
 }
 
 /// 多语言字符串翻译宏实现
-/// - 根据设置的默认语言选择对应的中英文字符串。
-/// - 这是一个过程宏，用于在编译时根据语言设置选择不同的字符串常量。
+/// - 参数为任意数量的 `语言 = 字符串` 键值对，如 `cn`、`en`、`ja`、`de`，在编译时选择其中一种语言的字符串。
+/// - 默认语言由 `def_cn`、`def_en` 特性决定，都未启用时为 `en`（启用 `strict_lang` 特性时改为编译报错）；设置了构建时的环境变量 `PROC_TOOLS_LANG`（如 `ja`）时优先使用该语言，
+///   如 `PROC_TOOLS_LANG=en cargo build`，不受工作空间中特性合并的影响，变量修改后会自动重新编译。
+/// - 所选语言没有翻译时，按回退链依次查找：环境变量 `PROC_TOOLS_LANG_FALLBACK`（逗号分隔，如 `ja,en`）中的语言、
+///   特性选择的默认语言、`en`、`cn`。
+/// - `key = "error.file_not_found"` 从翻译目录中读取各语言的翻译，目录为环境变量 `PROC_TOOLS_LANG_CATALOG` 指定的 TOML 文件，
+///   翻译放在文件中，由翻译人员维护；同时写出的语言键优先于目录中的翻译。目录文件修改后会触发重新编译。
+/// - 在翻译之后写 `; name = 表达式, ...` 时替换字符串中的 `{name}` 占位符，展开为 `proc_tools::concat_vars!`，
+///   预先计算长度并一次性分配，结果为 `String`，不需要先分别格式化每种语言的字符串
+///   - 没有对应参数的占位符直接使用同名的变量，如 `; ` 之后为空时 `{path}` 使用作用域中的 `path`
+///   - 每个参数只求值一次，参数的类型与 `concat_vars!` 未指定类型的参数相同；`{{`、`}}` 表示 `{`、`}`
+///   - 翻译必须是字符串字面量（或来自翻译目录），调用方需要依赖 `proc_tools` 和 `proc_tools_core`
+/// - 开头写 `plain`（如 `lang_tr!(plain, cn = ..., en = ...)`）时在编译时去掉翻译中的 Markdown 强调和反引号，
+///   同一份翻译（包括翻译目录）可以同时用于 HTML 文档和终端输出；翻译必须是字符串字面量
+/// - `lang = en` 强制使用指定的语言，不受默认语言和环境变量的影响，该语言没有翻译时报错，
+///   用于在中文构建中也必须为英文的字符串，如协议中的错误信息
+/// - 各语言的翻译为字符串字面量且含有 `{name}` 占位符时，在编译时检查占位符的名称是否一致，避免漏译或拼错的占位符
+/// - 可在构建脚本中用 `proc_tools_core::utils_core::lang_extract` 提取 crate 中所有调用的翻译，输出为 JSON 或 PO 文件
 ///
 /// # 参数
-/// - `input`: 宏输入的TokenStream，包含中英文字符串配置
+/// - `input`: 宏输入的TokenStream，包含各语言的字符串配置
 ///
 /// # 返回值
-/// - `TokenStream`: 根据默认语言选择的字符串对应的TokenStream
+/// - `TokenStream`: 根据语言设置选择的字符串对应的TokenStream
 ///
 /// # 错误类型
-/// - 如果未设置默认语言或设置了多个默认语言，会触发panic
-/// - 如果输入参数不符合语法要求，会在编译时报错
+/// - 如果没有提供任何翻译，或回退链中的语言都没有提供翻译，在宏调用处报错
+/// - 如果输入参数不符合语法要求，语言键重复或不是小写的语言代码（如 `cn`、`zh_tw`），在对应的键上报错
 ///
 /// # 示例
 /// ```
@@ -42,63 +408,291 @@ struct Args {
 ///
 /// let message = lang_tr!(cn = "你好世界", en = "Hello World");
 /// // 根据设置语言，message 会是 "你好世界" 或 "Hello World"
+///
+/// let greeting = lang_tr!(cn = "你好", en = "Hello", ja = "こんにちは", de = "Hallo");
+/// // PROC_TOOLS_LANG=ja 时 greeting 为 "こんにちは"
+///
+/// let only_en = lang_tr!(en = "Hello");
+/// // 没有中文翻译时回退到 en
+/// assert_eq!(only_en, "Hello");
+///
+/// let wire = lang_tr!(lang = en, cn = "协议版本不匹配", en = "protocol version mismatch");
+/// // 无论默认语言是什么，都使用英文
+/// assert_eq!(wire, "protocol version mismatch");
+///
+/// let plain = lang_tr!(plain, lang = en, cn = "**不能**修改 `file_name`", en = "**cannot** change `file_name`");
+/// assert_eq!(plain, "cannot change file_name");
+/// ```
+///
+/// 使用翻译目录（`PROC_TOOLS_LANG_CATALOG=i18n/messages.toml`）：
+/// ```toml
+/// [error.file_not_found]
+/// cn = "文件不存在"
+/// en = "file not found"
+/// ja = "ファイルが見つかりません"
+/// ```
+/// ```ignore
+/// use proc_tools_helper::lang_tr;
+///
+/// let message = lang_tr!(key = "error.file_not_found");
+/// // 没有日语翻译的消息可以在调用处补充
+/// let message = lang_tr!(key = "error.timeout", ja = "タイムアウト");
+/// ```
+///
+/// 替换占位符：
+/// ```
+/// use proc_tools_helper::lang_tr;
+///
+/// let path = "config.toml";
+/// let message = lang_tr!(lang = en, cn = "文件 {name} 不存在", en = "file {name} not found"; name = path);
+/// assert_eq!(message, "file config.toml not found");
+///
+/// // 没有对应参数的占位符使用同名的变量
+/// let line = 12u32;
+/// let message = lang_tr!(lang = en, cn = "第 {line} 行：{path}", en = "{path}:{line}";);
+/// assert_eq!(message, "config.toml:12");
+///
+/// // `{{`、`}}` 表示 `{`、`}`
+/// let message = lang_tr!(lang = en, cn = "集合 {{{name}}}", en = "set {{{name}}}"; name = 1);
+/// assert_eq!(message, "set {1}");
+///
+/// // 每个参数只求值一次
+/// let mut calls = 0;
+/// let mut next = || {
+///     calls += 1;
+///     calls
+/// };
+/// let message = lang_tr!(lang = en, cn = "{n} 与 {n}", en = "{n} and {n}"; n = next());
+/// assert_eq!(message, "1 and 1");
+/// assert_eq!(calls, 1);
+/// ```
+///
+/// 各语言的占位符不一致时在编译时报错：
+/// ```compile_fail
+/// use proc_tools_helper::lang_tr;
+///
+/// let message = lang_tr!(cn = "文件 {name} 不存在", en = "file {path} not found");
+/// ```
+///
+/// 重复的语言键在编译时报错：
+/// ```compile_fail
+/// use proc_tools_helper::lang_tr;
+///
+/// let message = lang_tr!(cn = "你好", cn = "您好");
 /// ```
 #[proc_macro]
 pub fn lang_tr(input: TokenStream) -> TokenStream {
     let args = parse_macro_input!(input as Args);
-    let cn = &args.cn;
-    let en = &args.en;
+    // `lang = en` 强制使用指定的语言，不按回退链查找
+    let chain = match &args.lang {
+        Some((lang, _)) => vec![lang.clone()],
+        None => lang_chain(),
+    };
 
-    let result = match get_def_lang().as_ref() {
-        "cn" => quote! { #cn },
-        "en" => quote! { #en },
-        _ => panic!("必须且只能启用一项默认语言"),
+    let Resolved { translations, track } = match args.resolve() {
+        Ok(resolved) => resolved,
+        Err(err) => return err.to_compile_error().into(),
     };
 
-    TokenStream::from(result)
+    let Some((_, expr)) = chain.iter().find_map(|lang| translations.iter().find(|(key, _)| key == lang)) else {
+        let err = match &args.lang {
+            Some((lang, expr)) => {
+                let msg = tr(&format!("指定的语言 `{}` 没有提供翻译", lang), &format!("No translation provided for the specified language `{}`", lang));
+                Error::new(expr.span(), msg)
+            }
+            None => {
+                let langs = chain.join(", ");
+                let msg = tr(
+                    &format!("回退链 [{}] 中的语言都没有提供翻译", langs),
+                    &format!("No translation provided for any language in the fallback chain [{}]", langs),
+                );
+                Error::new(Span::call_site(), msg)
+            }
+        };
+        return err.to_compile_error().into();
+    };
+
+    let expr = match &args.bindings {
+        Some(bindings) => {
+            let result = match expr {
+                Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit), .. }) => interpolate(lit, bindings),
+                _ => Err(Error::new_spanned(
+                    expr,
+                    tr("替换占位符时翻译必须是字符串字面量", "Translations must be string literals when placeholders are substituted"),
+                )),
+            };
+            match result {
+                Ok(code) => code,
+                Err(err) => return err.to_compile_error().into(),
+            }
+        }
+        None => quote! { #expr },
+    };
+
+    match track {
+        Some(track) => TokenStream::from(quote! {{ #track #expr }}),
+        None => TokenStream::from(quote! { #expr }),
+    }
+}
+
+/// 展开为包含所有翻译的静态表，而不是选择其中一种语言
+/// - 参数与 [`lang_tr!`] 相同（包括 `key = "..."`），但不支持 `;` 之后的占位符参数
+/// - 结果为 `&'static [(&'static str, &'static str)]`，每项为语言和翻译，按书写顺序排列，翻译目录中的翻译在后，
+///   与写出的语言键重复的目录翻译被忽略
+/// - 用于在运行时按用户的设置选择语言，或由工具提取所有字符串；翻译必须是常量表达式，可用于 `const` 和 `static`
+///
+/// # 参数
+/// - `input`: 宏输入的TokenStream，包含各语言的字符串配置
+///
+/// # 返回值
+/// - `TokenStream`: 静态表的TokenStream
+///
+/// # 错误类型
+/// - 与 [`lang_tr!`] 相同，此外使用占位符参数时在 `;` 之后的参数上报错
+///
+/// # 示例
+/// ```
+/// use proc_tools_helper::lang_tr_all;
+///
+/// const GREETING: &[(&str, &str)] = lang_tr_all!(cn = "你好", en = "Hello", ja = "こんにちは");
+/// assert_eq!(GREETING.len(), 3);
+///
+/// let lang = "ja";
+/// let text = GREETING.iter().find(|(l, _)| *l == lang).map_or(GREETING[0].1, |(_, text)| text);
+/// assert_eq!(text, "こんにちは");
+/// ```
+#[proc_macro]
+pub fn lang_tr_all(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input as Args);
+    if let Some((name, _)) = args.bindings.as_ref().and_then(|bindings| bindings.first()) {
+        let msg = tr("`lang_tr_all!` 不支持占位符参数", "`lang_tr_all!` does not support placeholder arguments");
+        return Error::new_spanned(name, msg).to_compile_error().into();
+    }
+    if let Some((_, expr)) = &args.lang {
+        let msg = tr("`lang_tr_all!` 包含所有语言，不支持 `lang`", "`lang_tr_all!` contains every language and does not support `lang`");
+        return Error::new_spanned(expr, msg).to_compile_error().into();
+    }
+
+    let Resolved { translations, track } = match args.resolve() {
+        Ok(resolved) => resolved,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let entries = translations.iter().map(|(lang, expr)| quote! { (#lang, #expr) });
+    TokenStream::from(quote! {{
+        #track
+        const TABLE: &[(&str, &str)] = &[#(#entries),*];
+        TABLE
+    }})
+}
+
+/// 合并后的翻译
+struct Resolved {
+    /// 语言和翻译
+    translations: Vec<(String, Expr)>,
+    /// 跟踪翻译目录文件的代码
+    track: Option<proc_macro2::TokenStream>,
+}
+
+impl Args {
+    /// 合并写出的翻译和翻译目录中的翻译，并检查占位符
+    ///
+    /// # 返回值
+    /// - `syn::Result<Resolved>`: 按书写顺序排列的语言和翻译，使用翻译目录时还包含跟踪目录文件的代码
+    ///
+    /// # 错误类型
+    /// - 翻译目录无法读取或占位符不一致时返回错误
+    fn resolve(&self) -> syn::Result<Resolved> {
+        let mut translations: Vec<(String, Expr)> =
+            self.translations.iter().map(|(lang, expr)| (lang.to_string(), expr.clone())).collect();
+        let mut track = None;
+        if let Some(key) = &self.key {
+            let (path, catalog) = catalog_lookup(key)?;
+            for (lang, text) in catalog {
+                // 写出的语言键优先于目录中的翻译
+                if translations.iter().any(|(l, _)| *l == lang) {
+                    continue;
+                }
+                let lit = LitStr::new(&text, key.span());
+                translations.push((lang, syn::parse_quote! { #lit }));
+            }
+            // 通过 `include_bytes!` 使编译器跟踪目录文件，修改后重新展开
+            let path = path.to_string_lossy().into_owned();
+            track = Some(quote! { const _: &[u8] = ::core::include_bytes!(#path); });
+        }
+
+        if self.plain {
+            for (_, expr) in &mut translations {
+                let Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit), .. }) = expr else {
+                    let msg = tr("使用 `plain` 时翻译必须是字符串字面量", "Translations must be string literals when `plain` is used");
+                    return Err(Error::new_spanned(expr, msg));
+                };
+                *lit = LitStr::new(&strip_markdown(&lit.value()), lit.span());
+            }
+        }
+
+        check_placeholders(&translations, self.bindings.is_some())?;
+        Ok(Resolved { translations, track })
+    }
 }
 
 impl Parse for Args {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        let mut cn = None;
-        let mut en = None;
-
-        // 解析所有键值对（支持任意顺序，逗号分隔）
-        while !input.is_empty() {
-            let key = input.parse::<Ident>()?;
-            input.parse::<Token![=]>()?;
-            let expr = input.parse::<Expr>()?;
-
-            match key.to_string().as_str() {
-                "cn" => {
-                    if cn.is_some() {
-                        return Err(Error::new_spanned(key, "Duplicate 'cn' key"));
-                    }
-                    cn = Some(expr);
-                }
-                "en" => {
-                    if en.is_some() {
-                        return Err(Error::new_spanned(key, "Duplicate 'en' key"));
+        // 解析所有键值对（支持任意顺序，逗号分隔），`key` 为翻译目录中的键，其余为语言
+        // 开头的 `plain` 是不带值的开关
+        let plain = input.peek(Ident) && !input.peek2(Token![=]) && input.fork().parse::<Ident>()? == "plain";
+        if plain {
+            input.parse::<Ident>()?;
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        let mut args = parse_kv_args(input)?;
+        let catalog_key = args.get_as::<LitStr>("key")?;
+        args.remove("key");
+        let lang = match args.remove("lang") {
+            Some((_, expr)) => {
+                let name = match &expr {
+                    Expr::Path(path) => path.path.get_ident().map(|ident| ident.to_string()),
+                    Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit), .. }) => Some(lit.value()),
+                    _ => None,
+                };
+                match name {
+                    Some(name) => Some((name, expr)),
+                    None => {
+                        let msg = tr("`lang` 的值应为语言代码，如 `lang = en`", "The value of `lang` must be a language code, such as `lang = en`");
+                        return Err(Error::new_spanned(expr, msg));
                     }
-                    en = Some(expr);
                 }
-                _ => return Err(Error::new_spanned(key, "Expected key 'cn' or 'en'")),
             }
-
-            // 如果还有逗号，继续解析下一个
-            if input.peek(Token![,]) {
-                input.parse::<Token![,]>()?;
-            } else {
-                break;
+            None => None,
+        };
+        for (key, _) in args.iter() {
+            let name = key.to_string();
+            let valid = name.starts_with(|c: char| c.is_ascii_lowercase())
+                && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+            if !valid {
+                let msg = tr(
+                    &format!("未知的键 `{}`，应为小写的语言代码，如 `cn`、`en`、`ja`", name),
+                    &format!("Unknown key `{}`, expected a lowercase language code such as `cn`, `en` or `ja`", name),
+                );
+                return Err(Error::new_spanned(key, msg));
             }
         }
+        let translations = args.into_vec();
+
+        // `;` 之后为占位符参数
+        let mut bindings = None;
+        if input.peek(Token![;]) {
+            input.parse::<Token![;]>()?;
+            bindings = Some(input.parse::<KvArgs>()?.into_vec());
+        }
+
+        if translations.is_empty() && catalog_key.is_none() {
+            let msg = tr("缺少翻译，应为 `cn = \"...\", en = \"...\"` 形式的键值对", "Missing translations, expected key-value pairs like `cn = \"...\", en = \"...\"`");
+            return Err(Error::new(Span::call_site(), msg));
+        }
 
-        // 确保两个键都存在（否则报错）
-        Ok(Args {
-            cn: cn.ok_or_else(|| panic!("中文：cn值获取失败，缺少必要参数")).unwrap(),
-            en: en
-                .ok_or_else(|| panic!("English: en value retrieval failed, missing necessary parameters"))
-                .unwrap(),
-        })
+        Ok(Args { translations, key: catalog_key, bindings, lang, plain })
     }
 }
@@ -1,85 +1,295 @@
+use crate::diagnostics;
 use proc_macro::TokenStream;
+use proc_tools_core::float2str::fixed::{F32_FIXED_LEN, F64_FIXED_LEN};
+use proc_tools_core::utils_core::net::{IPV4_LEN, IPV6_LEN, SOCKET_V4_LEN, SOCKET_V6_LEN};
+use proc_tools_core::utils_core::timestamp::{DURATION_LEN, MILLIS_LEN, RFC3339_LEN};
 use proc_tools_helper::lang_tr;
 use quote::{format_ident, quote};
 use syn::punctuated::Punctuated;
 use syn::{Expr, Token, parse_macro_input};
 
+// 常量求值中不能调用 `diagnostics::message`，错误码前缀手写在信息中
 const I_SIZE: usize = match size_of::<isize>() {
     1 => 4usize,   // 8位系统：1字节
     2 => 6usize,   // 16位系统：2字节
     4 => 11usize,  // 32位系统：4字节
     8 => 20usize,  // 64位系统：8字节
     16 => 40usize, // 128位系统：16字节
-    _ => panic!("{}", lang_tr!(cn = "不支持的操作系统位数", en = "Parameter exception")),
+    _ => panic!("{}", lang_tr!(cn = "[PT0000] 不支持的指针宽度", en = "[PT0000] Unsupported pointer width")),
 };
 
 const U_SIZE: usize = match size_of::<usize>() {
     1 => 3usize,   // 8位系统：1字节
     2 => 5usize,   // 16位系统：2字节
-    4 => 101usize, // 32位系统：4字节
+    4 => 10usize,  // 32位系统：4字节
     8 => 20usize,  // 64位系统：8字节
     16 => 39usize, // 128位系统：16字节
-    _ => panic!("{}", lang_tr!(cn = "不支持的操作系统位数", en = "Parameter exception")),
+    _ => panic!("{}", lang_tr!(cn = "[PT0000] 不支持的指针宽度", en = "[PT0000] Unsupported pointer width")),
 };
 
+/// `concat_vars_write!` 未指定 `max` 时栈上缓冲区的大小，结果更长时使用一次堆分配
+const WRITE_STACK_LEN: usize = 256;
+
+/// 网络地址：(类型, 缓冲区大小, 缓冲区大小常量, 格式化函数)
+const NET: [(&str, usize, &str, &str); 6] = [
+    ("Ipv4Addr", IPV4_LEN, "IPV4_LEN", "ipv4_buf"),
+    ("Ipv6Addr", IPV6_LEN, "IPV6_LEN", "ipv6_buf"),
+    ("IpAddr", IPV6_LEN, "IPV6_LEN", "ip_buf"),
+    ("SocketAddrV4", SOCKET_V4_LEN, "SOCKET_V4_LEN", "socket_v4_buf"),
+    ("SocketAddrV6", SOCKET_V6_LEN, "SOCKET_V6_LEN", "socket_v6_buf"),
+    ("SocketAddr", SOCKET_V6_LEN, "SOCKET_V6_LEN", "socket_buf"),
+];
+
+/// 整数与浮点数：(类型, 缓冲区大小, 转换函数)
+const NUMERIC: [(&str, usize, &str); 14] = [
+    ("i8", 4, "itoa_buf_i8"),
+    ("i16", 6, "itoa_buf_i16"),
+    ("i32", 11, "itoa_buf_i32"),
+    ("i64", 20, "itoa_buf_i64"),
+    ("i128", 40, "itoa_buf_i128"),
+    ("isize", I_SIZE, "itoa_buf_isize"),
+    ("u8", 3, "itoa_buf_u8"),
+    ("u16", 5, "itoa_buf_u16"),
+    ("u32", 10, "itoa_buf_u32"),
+    ("u64", 20, "itoa_buf_u64"),
+    ("u128", 39, "itoa_buf_u128"),
+    ("usize", U_SIZE, "itoa_buf_usize"),
+    ("f32", 24, "ftoa_buf_f32"),
+    ("f64", 24, "ftoa_buf_f64"),
+];
+
 pub(crate) fn concat_vars_implement(input: TokenStream) -> TokenStream {
-    let vars = parse_macro_input!(input with Punctuated::<TypedVar, Token![,]>::parse_terminated);
-    // 处理第一个参数
-    let first_param_code = if let Some(tv) = vars.get(0) {
-        let var_name = format_ident!("xl_proc_macro_concat_vars_temp_v{}", 0u8);
-        let ident = &tv.ident;
-        match &tv.ty {
-            Some(ty) => first_parameter_for_concat(&tv.ident, ty, var_name),
-            None => quote! {
-                let mut bytes = [0u8; 40];
-                let (mut total_len, mut #var_name)= #ident.first_parameter_for_concat(&mut bytes);
-            },
+    let args = parse_macro_input!(input as ConcatArgs);
+    // 所有参数都是字面量时，在编译期直接折叠为字符串字面量
+    if let Some(folded) = fold_literals(&args) {
+        if let Some(Err(err)) = args.options.max.as_ref().map(|max| check_max_len(&args, max)) {
+            return err.to_compile_error().into();
         }
-    } else {
-        panic!("{}", lang_tr!(cn = "至少需要一个参数", en = "At least one parameter is required"))
+        let folded = syn::LitStr::new(&folded, proc_macro2::Span::call_site());
+        return match &args.output {
+            Some(ty) => TokenStream::from(quote! { <#ty as ::core::convert::From<&'static str>>::from(#folded) }),
+            None => TokenStream::from(quote! { #folded }),
+        };
+    }
+    let (measure, write) = match reject_try(&args).and_then(|_| concat_vars_code(&args, true)) {
+        Ok(code) => code,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let build = match &args.output {
+        Some(ty) => match output_parameter(ty, &write) {
+            Ok(code) => code,
+            Err(err) => return err.to_compile_error().into(),
+        },
+        None => string_output(&write),
     };
 
-    let mut var_idx = 0u8;
-    let init = vars.iter().skip(1).map(|tv| {
-        var_idx += 1;
-        let var_name = format_ident!("xl_proc_macro_concat_vars_temp_v{}", var_idx);
-        let ident = &tv.ident;
-        match &tv.ty {
-            Some(ty) => init_concat_parameter(&tv.ident, ty, var_name),
-            None => quote! {
-                let mut bytes = [0u8; 40];
-                let mut #var_name = #ident.init_concat_parameter(&mut bytes, &mut total_len);
-            },
+    let expanded = quote! {
+        {
+            use proc_tools_core::utils_core::impl_to_ascii;
+            use proc_tools_core::utils_core::impl_to_ascii::StaticSizeConcatParameter;
+            use proc_tools_core::utils_core::impl_to_ascii::VariableSizeConcatParameter;
+            #measure
+            #build
         }
-    });
+    };
 
-    let mut var_idx = 0u8;
-    let format = vars.iter().map(|tv| {
-        let var_name = format_ident!("xl_proc_macro_concat_vars_temp_v{}", var_idx);
-        let ident = &tv.ident;
-        var_idx += 1;
-        match &tv.ty {
-            Some(ty) => concat_parameter(&tv.ident, ty, var_name),
-            None => quote! {
-                #ident.concat_parameter(s_ptr, &mut #var_name, &mut offset);
-            },
+    TokenStream::from(expanded)
+}
+
+/// 生成以 `String` 作为结果的构建代码
+fn string_output(write: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    quote! {
+        let mut res = proc_tools_core::alloc::string::String::with_capacity(total_len);
+        unsafe {
+            let s_ptr: *mut u8 = res.as_mut_vec().as_mut_ptr();
+            let mut offset = 0;
+            #write
+            res.as_mut_vec().set_len(offset);
         }
-    });
+        res
+    }
+}
+
+/// 生成 `=> 类型` 指定的结果构建代码
+/// - `Box<str>`、`Rc<str>`、`Arc<str>` 直接分配 `total_len` 字节的未初始化切片并写入，不经过中间的 `String`
+pub(crate) fn output_parameter(ty: &syn::Type, write: &proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    if is_type(ty, "String") {
+        return Ok(string_output(write));
+    }
+    let container = match ty {
+        syn::Type::Path(path) if path.qself.is_none() => path.path.segments.last().and_then(|segment| {
+            let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+                return None;
+            };
+            let is_str = args.args.len() == 1 && matches!(args.args.first(), Some(syn::GenericArgument::Type(inner)) if is_type(inner, "str"));
+            let kind = segment.ident.to_string();
+            if is_str && matches!(kind.as_str(), "Box" | "Rc" | "Arc") {
+                // 保留用户书写的路径，仅去掉泛型参数，如 `std::sync::Arc<str>` => `std::sync::Arc`
+                let mut container = path.path.clone();
+                if let Some(last) = container.segments.last_mut() {
+                    last.arguments = syn::PathArguments::None;
+                }
+                Some((kind, container))
+            } else {
+                None
+            }
+        }),
+        _ => None,
+    };
+    let Some((kind, container)) = container else {
+        let type_ = quote! { #ty }.to_string();
+        let msg = lang_tr!(
+            cn = format!("不支持的结果类型 `{}`，可用类型：`String`、`Box<str>`、`Rc<str>`、`Arc<str>`", type_),
+            en = format!("Unsupported result type `{}`, expected `String`, `Box<str>`, `Rc<str>` or `Arc<str>`", type_)
+        );
+        return Err(diagnostics::error_spanned("PT0001", ty, msg));
+    };
+    let (slice, into_str) = if kind == "Box" {
+        (quote! { &mut *out }, quote! { #container::from_raw(#container::into_raw(out) as *mut str) })
+    } else {
+        (
+            quote! { #container::get_mut(&mut out).unwrap() },
+            quote! { #container::from_raw(#container::into_raw(out) as *const str) },
+        )
+    };
+    Ok(quote! {
+        let mut out = #container::<[u8]>::new_uninit_slice(total_len);
+        unsafe {
+            let s_ptr: *mut u8 = (#slice).as_mut_ptr() as *mut u8;
+            let mut offset = 0;
+            #write
+            // 未初始化的字节不能暴露出去，写入长度必须与预先计算的长度完全一致
+            assert_eq!(offset, total_len);
+            let out = out.assume_init();
+            #into_str
+        }
+    })
+}
+
+pub(crate) fn concat_vars_into_implement(input: TokenStream) -> TokenStream {
+    let ConcatIntoArgs { buf, args } = parse_macro_input!(input as ConcatIntoArgs);
+    if let Some(ty) = &args.output {
+        let msg = lang_tr!(cn = "`concat_vars_into!` 不支持指定结果类型", en = "`concat_vars_into!` does not support a result type");
+        return diagnostics::error_spanned("PT0002", ty, msg).to_compile_error().into();
+    }
+    let (measure, write) = match reject_try(&args).and_then(|_| concat_vars_code(&args, true)) {
+        Ok(code) => code,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    // `tight` 时只预留本次需要的长度，否则按 `String` 的增长策略预留，便于反复追加
+    let reserve = if args.options.tight { quote! { reserve_exact } } else { quote! { reserve } };
 
     let expanded = quote! {
         {
             use proc_tools_core::utils_core::impl_to_ascii;
             use proc_tools_core::utils_core::impl_to_ascii::StaticSizeConcatParameter;
             use proc_tools_core::utils_core::impl_to_ascii::VariableSizeConcatParameter;
-            #first_param_code
-            #(#init)*
-            let mut res = String::with_capacity(total_len);
+            let xl_proc_macro_concat_vars_buf: &mut proc_tools_core::alloc::string::String = #buf;
+            #measure
+            xl_proc_macro_concat_vars_buf.#reserve(total_len);
             unsafe {
-            let s_ptr: *mut u8 = res.as_mut_vec().as_mut_ptr();
+                let start = xl_proc_macro_concat_vars_buf.len();
+                let s_ptr: *mut u8 = xl_proc_macro_concat_vars_buf.as_mut_vec().as_mut_ptr().add(start);
+                let mut offset = 0;
+                #write
+                xl_proc_macro_concat_vars_buf.as_mut_vec().set_len(start + offset);
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
+
+pub(crate) fn concat_vars_write_implement(input: TokenStream) -> TokenStream {
+    let ConcatIntoArgs { buf: sink, args } = parse_macro_input!(input as ConcatIntoArgs);
+    if let Some(ty) = &args.output {
+        let msg = lang_tr!(cn = "`concat_vars_write!` 不支持指定结果类型", en = "`concat_vars_write!` does not support a result type");
+        return diagnostics::error_spanned("PT0003", ty, msg).to_compile_error().into();
+    }
+    // 全部为字面量时直接写入折叠后的字符串
+    if let Some(folded) = fold_literals(&args) {
+        if let Some(Err(err)) = args.options.max.as_ref().map(|max| check_max_len(&args, max)) {
+            return err.to_compile_error().into();
+        }
+        return TokenStream::from(quote! {
+            {
+                use proc_tools_core::utils_core::sink::{FmtSink as _, IoSink as _};
+                (&mut proc_tools_core::utils_core::sink::SinkWrap(#sink)).write_concat(#folded)
+            }
+        });
+    }
+    let (measure, write) = match reject_try(&args).and_then(|_| concat_vars_code(&args, true)) {
+        Ok(code) => code,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    // 指定了 `max` 时结果长度在编译期有上限，栈上缓冲区按上限分配，不会再使用堆内存
+    let stack_len = match &args.options.max {
+        Some(max) => match max.base10_parse::<usize>() {
+            Ok(max) => max,
+            Err(err) => return err.to_compile_error().into(),
+        },
+        None => WRITE_STACK_LEN,
+    };
+
+    let expanded = quote! {
+        {
+            use proc_tools_core::utils_core::impl_to_ascii;
+            use proc_tools_core::utils_core::impl_to_ascii::StaticSizeConcatParameter;
+            use proc_tools_core::utils_core::impl_to_ascii::VariableSizeConcatParameter;
+            use proc_tools_core::utils_core::sink::{FmtSink as _, IoSink as _};
+            let xl_proc_macro_concat_vars_sink = #sink;
+            #measure
+            let mut xl_proc_macro_concat_vars_stack = [::core::mem::MaybeUninit::<u8>::uninit(); #stack_len];
+            let mut xl_proc_macro_concat_vars_heap = proc_tools_core::alloc::vec::Vec::<u8>::new();
+            let s_ptr: *mut u8 = if total_len <= #stack_len {
+                xl_proc_macro_concat_vars_stack.as_mut_ptr() as *mut u8
+            } else {
+                xl_proc_macro_concat_vars_heap.reserve_exact(total_len);
+                xl_proc_macro_concat_vars_heap.as_mut_ptr()
+            };
             let mut offset = 0;
-            #(#format)*
-            res.as_mut_vec().set_len(offset);
+            let xl_proc_macro_concat_vars_str = unsafe {
+                #write
+                ::core::str::from_utf8_unchecked(::core::slice::from_raw_parts(s_ptr, offset))
+            };
+            (&mut proc_tools_core::utils_core::sink::SinkWrap(xl_proc_macro_concat_vars_sink)).write_concat(xl_proc_macro_concat_vars_str)
         }
+    };
+
+    TokenStream::from(expanded)
+}
+
+pub(crate) fn concat_vars_bytes_implement(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input as ConcatArgs);
+    if let Some(ty) = &args.output {
+        let msg = lang_tr!(cn = "`concat_vars_bytes!` 不支持指定结果类型", en = "`concat_vars_bytes!` does not support a result type");
+        return diagnostics::error_spanned("PT0004", ty, msg).to_compile_error().into();
+    }
+    if let Some(folded) = fold_literals(&args) {
+        if let Some(Err(err)) = args.options.max.as_ref().map(|max| check_max_len(&args, max)) {
+            return err.to_compile_error().into();
+        }
+        let folded = syn::LitByteStr::new(folded.as_bytes(), proc_macro2::Span::call_site());
+        return TokenStream::from(quote! { proc_tools_core::alloc::vec::Vec::<u8>::from(&#folded[..]) });
+    }
+    let (measure, write) = match reject_try(&args).and_then(|_| concat_vars_code(&args, false)) {
+        Ok(code) => code,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let expanded = quote! {
+        {
+            use proc_tools_core::utils_core::impl_to_ascii;
+            use proc_tools_core::utils_core::impl_to_ascii::StaticSizeConcatParameter;
+            use proc_tools_core::utils_core::impl_to_ascii::VariableSizeConcatParameter;
+            #measure
+            let mut res = proc_tools_core::alloc::vec::Vec::<u8>::with_capacity(total_len);
+            unsafe {
+                let s_ptr: *mut u8 = res.as_mut_ptr();
+                let mut offset = 0;
+                #write
+                res.set_len(offset);
+            }
             res
         }
     };
@@ -87,297 +297,1458 @@ pub(crate) fn concat_vars_implement(input: TokenStream) -> TokenStream {
     TokenStream::from(expanded)
 }
 
-pub(crate) struct TypedVar {
-    pub(crate) ident: Expr,
-    pub(crate) ty: Option<syn::Type>,
+pub(crate) fn try_concat_vars_implement(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input as ConcatArgs);
+    if !args.vars.iter().any(|tv| tv.ty.as_ref().is_some_and(uses_try)) {
+        let msg = lang_tr!(
+            cn = "`try_concat_vars!` 至少需要一个 `try_display` 参数，否则请使用 `concat_vars!`",
+            en = "`try_concat_vars!` requires at least one `try_display` parameter, use `concat_vars!` otherwise"
+        );
+        return diagnostics::error("PT0005", proc_macro2::Span::call_site(), msg).to_compile_error().into();
+    }
+    let (measure, write) = match concat_vars_code(&args, true) {
+        Ok(code) => code,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let build = match &args.output {
+        Some(ty) => match output_parameter(ty, &write) {
+            Ok(code) => code,
+            Err(err) => return err.to_compile_error().into(),
+        },
+        None => string_output(&write),
+    };
+    let label = try_label();
+
+    // `try_display` 参数格式化失败时以 `break` 跳出标签块，返回其错误
+    let expanded = quote! {
+        {
+            use proc_tools_core::utils_core::impl_to_ascii;
+            use proc_tools_core::utils_core::impl_to_ascii::StaticSizeConcatParameter;
+            use proc_tools_core::utils_core::impl_to_ascii::VariableSizeConcatParameter;
+            #label: {
+                #measure
+                let xl_proc_macro_concat_vars_res = { #build };
+                ::core::result::Result::Ok(xl_proc_macro_concat_vars_res)
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
 }
 
-impl syn::parse::Parse for TypedVar {
-    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        let ident = input.parse()?;
+/// `try_concat_vars!` 生成代码中的标签
+fn try_label() -> syn::Lifetime {
+    syn::Lifetime::new("'xl_proc_macro_concat_vars_try", proc_macro2::Span::mixed_site())
+}
 
-        // 检查是否有冒号和类型注解
-        if input.peek(Token![:]) {
-            let _colon: Token![:] = input.parse()?;
-            let ty = input.parse()?;
-            Ok(TypedVar { ident, ty: Some(ty) })
-        } else {
-            Ok(TypedVar { ident, ty: None })
+/// 判断类型注解中是否使用了 `try_display`
+fn uses_try(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Slice(slice) => uses_try(&slice.elem),
+        syn::Type::Array(array) => uses_try(&array.elem),
+        syn::Type::Tuple(tuple) => tuple.elems.iter().any(uses_try),
+        ty => is_type(peel_type(ty).0, "try_display"),
+    }
+}
+
+/// 不可失败的宏不接受 `try_display` 参数
+fn reject_try(args: &ConcatArgs) -> syn::Result<()> {
+    match args.vars.iter().find(|tv| tv.ty.as_ref().is_some_and(uses_try)) {
+        Some(tv) => {
+            let msg = lang_tr!(cn = "`try_display` 只能用于 `try_concat_vars!`", en = "`try_display` can only be used in `try_concat_vars!`");
+            Err(diagnostics::error_spanned("PT0006", &tv.ty, msg))
         }
+        None => Ok(()),
     }
 }
 
-/// 生成第一个参数的代码
-pub(crate) fn first_parameter_for_concat(ident: &Expr, ty: &syn::Type, var_name: syn::Ident) -> proc_macro2::TokenStream {
-    if is_type(ty, "String") || is_type(ty, "string") || is_type(ty, "str") || is_type(ty, "&str") {
-        quote! {
-            let mut total_len = #ident.len();
+/// 在编译期连接全部由字面量组成的参数
+/// - 参数必须是未指定类型的字符串、整数、浮点数、布尔值或字符字面量，分隔符也必须是字符串字面量
+/// - 任一参数不满足条件时返回 `None`，由运行时代码处理
+pub(crate) fn fold_literals(args: &ConcatArgs) -> Option<String> {
+    if args.vars.is_empty() {
+        return None;
+    }
+    let sep = match &args.options.sep {
+        Some(Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(sep), .. })) => sep.value(),
+        Some(_) => return None,
+        None => String::new(),
+    };
+    let mut folded = String::new();
+    for (idx, tv) in args.vars.iter().enumerate() {
+        if tv.ty.is_some() || tv.guard.is_some() {
+            return None;
         }
-    } else if is_type(ty, "i8") {
-        quote! {
-            let mut bytes = [0u8; 4];
-            let #var_name = impl_to_ascii::itoa_buf_i8(&mut bytes, #ident);
-            let mut total_len = #var_name.len();
+        if idx > 0 {
+            folded.push_str(&sep);
         }
-    } else if is_type(ty, "i16") {
-        quote! {
-            let mut bytes = [0u8; 6];
-            let #var_name = impl_to_ascii::itoa_buf_i16(&mut bytes, #ident);
-            let mut total_len = #var_name.len();
+        folded.push_str(&literal_text(&tv.ident, false)?);
+    }
+    Some(folded)
+}
+
+/// 将字面量表达式格式化为与运行时一致的文本，`negative` 表示字面量前有负号
+/// - 浮点数使用与运行时相同的 `ftoa_buf_f32`/`ftoa_buf_f64`，保证输出完全一致
+fn literal_text(expr: &Expr, negative: bool) -> Option<String> {
+    let lit = match expr {
+        Expr::Lit(lit) => &lit.lit,
+        Expr::Group(group) => return literal_text(&group.expr, negative),
+        Expr::Paren(paren) => return literal_text(&paren.expr, negative),
+        Expr::Unary(syn::ExprUnary { op: syn::UnOp::Neg(_), expr, .. }) if !negative => return literal_text(expr, true),
+        _ => return None,
+    };
+    let text = match lit {
+        syn::Lit::Str(s) if !negative => s.value(),
+        syn::Lit::Bool(b) if !negative => b.value.to_string(),
+        syn::Lit::Char(c) if !negative => c.value().to_string(),
+        syn::Lit::Int(int) => {
+            // 负的无符号整数交给编译器报错
+            if negative && int.suffix().starts_with('u') {
+                return None;
+            }
+            let digits = int.base10_digits().trim_start_matches('0');
+            match (digits.is_empty(), negative) {
+                (true, _) => String::from("0"),
+                (false, true) => format!("-{}", digits),
+                (false, false) => digits.to_string(),
+            }
         }
-    } else if is_type(ty, "i32") {
-        quote! {
-            let mut bytes = [0u8; 11];
-            let #var_name = impl_to_ascii::itoa_buf_i32(&mut bytes, #ident);
-            let mut total_len = #var_name.len();
+        syn::Lit::Float(float) => {
+            let mut buf = [0u8; 24];
+            let bytes = if float.suffix() == "f32" {
+                let f = float.base10_parse::<f32>().ok()?;
+                proc_tools_core::utils_core::impl_to_ascii::ftoa_buf_f32(&mut buf, if negative { -f } else { f })
+            } else {
+                let f = float.base10_parse::<f64>().ok()?;
+                proc_tools_core::utils_core::impl_to_ascii::ftoa_buf_f64(&mut buf, if negative { -f } else { f })
+            };
+            String::from_utf8_lossy(bytes).into_owned()
         }
-    } else if is_type(ty, "i64") {
-        quote! {
-            let mut bytes = [0u8; 20];
-            let #var_name = impl_to_ascii::itoa_buf_i64(&mut bytes, #ident);
-            let mut total_len = #var_name.len();
+        _ => return None,
+    };
+    Some(text)
+}
+
+/// 生成参数的长度计算代码与写入代码
+/// - 长度计算代码声明并累加 `total_len`
+/// - 写入代码依赖 `s_ptr` 与 `offset`，需放在 `unsafe` 块中
+pub(crate) fn concat_vars_code(args: &ConcatArgs, utf8: bool) -> syn::Result<(proc_macro2::TokenStream, proc_macro2::TokenStream)> {
+    let vars = &args.vars;
+    if vars.is_empty() {
+        let msg = lang_tr!(cn = "至少需要一个参数", en = "At least one parameter is required");
+        return Err(diagnostics::error("PT0007", proc_macro2::Span::call_site(), msg));
+    }
+    if let Some(max) = &args.options.max {
+        check_max_len(args, max)?;
+    }
+
+    // 分隔符：在第一个参数之后的每个参数前写入
+    // 存在条件参数时，由运行时标记记录之前是否已写入参数，跳过的参数不会留下多余的分隔符
+    let sep_flag = format_ident!("xl_proc_macro_concat_vars_sep_started", span = proc_macro2::Span::mixed_site());
+    let flagged = !args.guards.is_empty() && vars.len() > 1;
+    let (sep_init, sep_len) = match &args.options.sep {
+        Some(sep) if flagged => (quote! { let xl_proc_macro_concat_vars_sep: &str = #sep; let mut #sep_flag = false; }, quote! {}),
+        Some(sep) => (quote! { let xl_proc_macro_concat_vars_sep: &str = #sep; }, {
+            let count = vars.len() - 1;
+            quote! { total_len += xl_proc_macro_concat_vars_sep.len() * #count; }
+        }),
+        None => (quote! {}, quote! {}),
+    };
+    let sep_copy = quote! {
+        ::core::ptr::copy_nonoverlapping(xl_proc_macro_concat_vars_sep.as_ptr(), s_ptr.add(offset), xl_proc_macro_concat_vars_sep.len());
+        offset += xl_proc_macro_concat_vars_sep.len();
+    };
+    let write_init = if flagged && args.options.sep.is_some() { quote! { let mut #sep_flag = false; } } else { quote! {} };
+
+    // 所有参数表达式在生成的其他语句之前按顺序各求值一次，条件参数仅在条件成立时求值
+    let mut binder = Binder { scratch: args.options.scratch.then(Vec::new), utf8, ..Binder::default() };
+    let mut measure = Vec::with_capacity(vars.len());
+    let mut write = Vec::with_capacity(vars.len() * 2);
+    for (idx, tv) in vars.iter().enumerate() {
+        binder.guard = tv.guard.map(|guard| format_ident!("xl_proc_macro_concat_vars_cond_g{}", guard, span = proc_macro2::Span::mixed_site()));
+        binder.guarded.clear();
+        binder.prelude.clear();
+        // 同一组条件参数连续排列，条件在组的第一个参数之前求值一次
+        if let (Some(cond_name), Some(guard)) = (&binder.guard, tv.guard) {
+            if idx == 0 || vars[idx - 1].guard != tv.guard {
+                let cond = &args.guards[guard];
+                binder.bindings.push(quote! { let #cond_name: bool = #cond; });
+            }
         }
-    } else if is_type(ty, "i128") {
-        quote! {
-            let mut bytes = [0u8; 40];
-            let #var_name = impl_to_ascii::itoa_buf_i128(&mut bytes, #ident);
-            let mut total_len = #var_name.len();
+        let (sep_measure, sep_write) = match &args.options.sep {
+            Some(_) if flagged => {
+                // 最后一个参数之后不再读取标记，无需更新
+                let update = (idx + 1 < vars.len()).then(|| quote! { #sep_flag = true; });
+                (
+                    quote! { if #sep_flag { total_len += xl_proc_macro_concat_vars_sep.len(); } #update },
+                    quote! { if #sep_flag { #sep_copy } #update },
+                )
+            }
+            Some(_) if idx > 0 => (quote! {}, sep_copy.clone()),
+            _ => (quote! {}, quote! {}),
+        };
+        let (arg_measure, arg_write, rendered) = argument_code(tv, idx, &mut binder)?;
+        let Binder { guard, guarded, prelude, .. } = &binder;
+        match guard {
+            None => {
+                measure.push(quote! { #sep_measure #(#prelude)* #arg_measure });
+                write.push(quote! { #sep_write #arg_write });
+            }
+            Some(cond_name) => {
+                let header = if guarded.is_empty() {
+                    quote! { if #cond_name }
+                } else {
+                    let (vals, opts): (Vec<_>, Vec<_>) = guarded.iter().cloned().unzip();
+                    quote! { if let (#(::core::option::Option::Some(#vals),)*) = (#(#opts,)*) }
+                };
+                // 写入时重新渲染参数；列表参数等的写入代码自行渲染，其余参数重新执行长度计算以声明渲染结果
+                let arg_write = if rendered { arg_write } else { rerender(&arg_measure, &arg_write) };
+                measure.push(quote! { #header { #(#prelude)* #sep_measure #arg_measure } });
+                write.push(quote! {
+                    #[allow(unused_variables, unused_assignments)]
+                    #header { #(#prelude)* #sep_write #arg_write }
+                });
+            }
         }
-    } else if is_type(ty, "isize") {
-        quote! {
-            let mut bytes = [0u8; #I_SIZE];
-            let #var_name = impl_to_ascii::itoa_buf_isize(&mut bytes, #ident);
-            total_len += #var_name.len();
+    }
+
+    // 共享缓冲区的大小取各参数所需字节数的最大值，在编译期求值
+    let scratch = binder.scratch.filter(|sizes| !sizes.is_empty()).map(|sizes| {
+        let mut unique: Vec<proc_macro2::TokenStream> = Vec::new();
+        for size in sizes {
+            if !unique.iter().any(|seen| seen.to_string() == size.to_string()) {
+                unique.push(size);
+            }
         }
-    } else if is_type(ty, "u8") {
+        let count = unique.len();
         quote! {
-            let mut bytes = [0u8; 3];
-            let #var_name = impl_to_ascii::itoa_buf_u8(&mut bytes, #ident);
-            let mut total_len = #var_name.len();
+            let mut xl_proc_macro_concat_vars_scratch = [0u8; {
+                let sizes: [usize; #count] = [#(#unique),*];
+                let mut max = 0;
+                let mut i = 0;
+                while i < sizes.len() {
+                    if sizes[i] > max {
+                        max = sizes[i];
+                    }
+                    i += 1;
+                }
+                max
+            }];
         }
-    } else if is_type(ty, "u16") {
-        quote! {
-            let mut bytes = [0u8; 5];
-            let #var_name = impl_to_ascii::itoa_buf_u16(&mut bytes, #ident);
-            let mut total_len = #var_name.len();
+    });
+    let bindings = &binder.bindings;
+    Ok((
+        quote! { #sep_init #(#bindings)* #scratch let mut total_len = 0usize; #(#measure)* #sep_len },
+        quote! { #write_init #(#write)* },
+    ))
+}
+
+/// 参数绑定的状态
+/// - `bindings` 为在所有其他语句之前求值的绑定，`bound` 记录可复用的无副作用表达式
+/// - 处理条件参数时，`guard` 为条件变量，参数绑定为 `Option<&T>` 并记录在 `guarded` 中，
+///   由此派生的绑定放在 `prelude` 中，在条件成立的分支内声明
+/// - `scratch` 选项下 `scratch` 记录各参数借用共享缓冲区的字节数
+/// - `utf8` 表示结果是否必须是有效的 UTF-8，`concat_vars_bytes!` 的结果为字节序列，不要求 UTF-8
+#[derive(Default)]
+struct Binder {
+    bindings: Vec<proc_macro2::TokenStream>,
+    bound: Vec<(String, syn::Ident)>,
+    guard: Option<syn::Ident>,
+    guarded: Vec<(syn::Ident, syn::Ident)>,
+    prelude: Vec<proc_macro2::TokenStream>,
+    scratch: Option<Vec<proc_macro2::TokenStream>>,
+    utf8: bool,
+}
+
+impl Binder {
+    /// 绑定参数表达式，返回指向其值的引用变量
+    fn bind(&mut self, expr: &Expr, suffix: String) -> syn::Ident {
+        let Some(cond_name) = &self.guard else {
+            return bind_argument(expr, suffix, &mut self.bindings, &mut self.bound);
+        };
+        let val_name = format_ident!("xl_proc_macro_concat_vars_val_v{}", suffix, span = proc_macro2::Span::mixed_site());
+        let opt_name = format_ident!("xl_proc_macro_concat_vars_opt_v{}", suffix, span = proc_macro2::Span::mixed_site());
+        if is_place_expr(expr) {
+            self.bindings.push(quote! {
+                let #opt_name = if #cond_name { ::core::option::Option::Some(&(#expr)) } else { ::core::option::Option::None };
+            });
+        } else {
+            // 其他表达式的结果是临时值，先移动到绑定中再取引用
+            let store_name = format_ident!("xl_proc_macro_concat_vars_store_v{}", suffix, span = proc_macro2::Span::mixed_site());
+            self.bindings.push(quote! {
+                let #store_name = if #cond_name { ::core::option::Option::Some(#expr) } else { ::core::option::Option::None };
+                let #opt_name = #store_name.as_ref();
+            });
         }
-    } else if is_type(ty, "u32") {
-        quote! {
-            let mut bytes = [0u8; 10];
-            let #var_name = impl_to_ascii::itoa_buf_u32(&mut bytes, #ident);
-            let mut total_len = #var_name.len();
+        self.guarded.push((val_name.clone(), opt_name));
+        val_name
+    }
+
+    /// 添加由已绑定的值派生的绑定
+    fn derive(&mut self, binding: proc_macro2::TokenStream) {
+        match self.guard {
+            Some(_) => self.prelude.push(binding),
+            None => self.bindings.push(binding),
         }
-    } else if is_type(ty, "u64") {
-        quote! {
-            let mut bytes = [0u8; 20];
-            let #var_name = impl_to_ascii::itoa_buf_u64(&mut bytes, #ident);
-            let mut total_len = #var_name.len();
+    }
+}
+
+/// 判断表达式是否是位置表达式，可以直接取引用而不移动或产生临时值
+fn is_place_expr(expr: &Expr) -> bool {
+    match expr {
+        Expr::Path(_) | Expr::Lit(_) => true,
+        Expr::Field(field) => is_place_expr(&field.base),
+        Expr::Index(index) => is_place_expr(&index.expr),
+        Expr::Unary(syn::ExprUnary { op: syn::UnOp::Deref(_), .. }) => true,
+        Expr::Paren(paren) => is_place_expr(&paren.expr),
+        Expr::Group(group) => is_place_expr(&group.expr),
+        _ => false,
+    }
+}
+
+/// 生成单个参数的长度计算代码与写入代码，第三个返回值表示写入代码是否自行渲染参数
+/// - 列表参数逐个元素渲染，写入时总是重新渲染
+/// - `scratch` 选项下所有参数在写入时重新渲染，长度计算与写入都不保留渲染结果，共享缓冲区可以被下一个参数复用
+fn argument_code(
+    tv: &TypedVar, idx: usize, binder: &mut Binder,
+) -> syn::Result<(proc_macro2::TokenStream, proc_macro2::TokenStream, bool)> {
+    let var_name = format_ident!("xl_proc_macro_concat_vars_temp_v{}", idx);
+    let bytes_name = format_ident!("xl_proc_macro_concat_vars_bytes_v{}", idx);
+    let scratch = binder.scratch.is_some();
+    // 元组逐个元素输出，元组字面量的各元素分别绑定，不会移动参数
+    if let Some(syn::Type::Tuple(tuple)) = &tv.ty {
+        let elem_names: Vec<syn::Ident> = match &tv.ident {
+            Expr::Tuple(exprs) if exprs.elems.len() == tuple.elems.len() => {
+                exprs.elems.iter().enumerate().map(|(k, expr)| binder.bind(expr, format!("{}_{}", idx, k))).collect()
+            }
+            ident => {
+                let val_name = binder.bind(ident, idx.to_string());
+                (0..tuple.elems.len())
+                    .map(|k| {
+                        let elem_name = format_ident!("xl_proc_macro_concat_vars_val_v{}_{}", idx, k, span = proc_macro2::Span::mixed_site());
+                        let member = syn::Index::from(k);
+                        binder.derive(quote! { let #elem_name = &#val_name.#member; });
+                        elem_name
+                    })
+                    .collect()
+            }
+        };
+        let sep_name = tv.sep.as_ref().map(|sep| {
+            let sep_name = format_ident!("xl_proc_macro_concat_vars_item_sep_v{}", idx, span = proc_macro2::Span::mixed_site());
+            binder.bindings.push(quote! { let #sep_name: &str = #sep; });
+            sep_name
+        });
+        let (mut measure, mut write) = (Vec::new(), Vec::new());
+        for (k, (elem, elem_name)) in tuple.elems.iter().zip(&elem_names).enumerate() {
+            let suffix = format!("{}_{}", idx, k);
+            let var_name = format_ident!("xl_proc_macro_concat_vars_temp_v{}", suffix);
+            let bytes_name = format_ident!("xl_proc_macro_concat_vars_bytes_v{}", suffix);
+            let render = render_value(tv, Some(elem), elem_name, &var_name, &mut Buffer { name: &bytes_name, sizes: binder.scratch.as_mut(), utf8: binder.utf8 })?;
+            let (len, copy) = emit_value(tv, &suffix, &var_name, None);
+            if let (Some(sep_name), true) = (&sep_name, k > 0) {
+                measure.push(quote! { total_len += #sep_name.len(); });
+                write.push(quote! {
+                    ::core::ptr::copy_nonoverlapping(#sep_name.as_ptr(), s_ptr.add(offset), #sep_name.len());
+                    offset += #sep_name.len();
+                });
+            }
+            if scratch {
+                let render = quote! { #render #len };
+                measure.push(quote! { { #render } });
+                write.push(rerender(&render, &copy));
+            } else {
+                measure.push(quote! { #render #len });
+                write.push(copy);
+            }
         }
-    } else if is_type(ty, "u128") {
-        quote! {
-            let mut bytes = [0u8; 39];
-            let #var_name = impl_to_ascii::itoa_buf_u128(&mut bytes, #ident);
-            let mut total_len = #var_name.len();
+        return Ok((quote! { #(#measure)* }, quote! { #(#write)* }, scratch));
+    }
+    // 未指定类型的整数、浮点数、字符、布尔值字面量在编译期转换为文本，与字符串字面量一样处理
+    let literal = if tv.ty.is_none() && !is_str_literal(&tv.ident) { literal_text(&tv.ident, false) } else { None };
+    let val_name = (!is_str_literal(&tv.ident) && literal.is_none()).then(|| binder.bind(&tv.ident, idx.to_string()));
+    let list_elem = match &tv.ty {
+        Some(syn::Type::Slice(slice)) => Some(&slice.elem),
+        Some(syn::Type::Array(array)) => Some(&array.elem),
+        _ => None,
+    };
+    if let (Some(elem), Some(val_name)) = (list_elem, &val_name) {
+        let sep_name = format_ident!("xl_proc_macro_concat_vars_item_sep_v{}", idx, span = proc_macro2::Span::mixed_site());
+        let sep = tv.sep.as_ref().map_or_else(|| quote! { "" }, |sep| quote! { #sep });
+        binder.bindings.push(quote! { let #sep_name: &str = #sep; });
+        let mut buf = Buffer { name: &bytes_name, sizes: binder.scratch.as_mut(), utf8: binder.utf8 };
+        let (len, copy) = repeat_parameter(tv, elem, idx, val_name, &sep_name, &var_name, &mut buf)?;
+        return Ok((len, copy, true));
+    }
+    // `as hexdump` 的 `sep` 为字节之间的分隔符
+    let byte_sep = match &tv.sep {
+        Some(sep) if matches!(tv.mode.as_ref().and_then(transform_of), Some(Transform::HexDump)) => {
+            let sep_name = format_ident!("xl_proc_macro_concat_vars_item_sep_v{}", idx, span = proc_macro2::Span::mixed_site());
+            binder.bindings.push(quote! { let #sep_name: &str = #sep; });
+            Some(sep_name)
         }
-    } else if is_type(ty, "usize") {
-        quote! {
-            let mut bytes = [0u8; #U_SIZE];
-            let #var_name = impl_to_ascii::itoa_buf_usize(&mut bytes, #ident);
-            total_len += #var_name.len();
+        Some(sep) => {
+            let msg = lang_tr!(
+                cn = "只有列表、数组、元组参数和 `as hexdump` 支持 `sep`",
+                en = "Only list, array and tuple parameters and `as hexdump` support `sep`"
+            );
+            return Err(diagnostics::error_spanned("PT0008", sep, msg));
         }
-    } else if is_type(ty, "char") {
-        quote! {
-            let mut bytes = [0; 4];
-            let #var_name = #ident.encode_utf8(&mut bytes);
-            let mut total_len = #var_name.len();
+        None => None,
+    };
+    let render = match &val_name {
+        Some(val_name) => {
+            let mut buf = Buffer { name: &bytes_name, sizes: binder.scratch.as_mut(), utf8: binder.utf8 };
+            render_value(tv, tv.ty.as_ref(), val_name, &var_name, &mut buf)?
         }
-    } else if is_type(ty, "bool") {
-        quote! {
-            let mut total_len = if #ident { 4 } else { 5 };
+        None => {
+            let ident = &tv.ident;
+            let text = literal.map(|text| syn::LitStr::new(&text, proc_macro2::Span::call_site()));
+            match text {
+                Some(text) => quote! { let #var_name: &[u8] = #text.as_bytes(); },
+                None => quote! { let #var_name: &[u8] = #ident.as_bytes(); },
+            }
         }
-    } else if is_type(ty, "f32") {
-        quote! {
-            let mut bytes = [0u8; 24];
-            let #var_name = impl_to_ascii::ftoa_buf_f32(&mut bytes, #ident);
-            let mut total_len = #var_name.len();
+    };
+    let (len, copy) = emit_value(tv, &idx.to_string(), &var_name, byte_sep.as_ref());
+    let render = quote! { #render #len };
+    if scratch {
+        return Ok((quote! { { #render } }, rerender(&render, &copy), true));
+    }
+    Ok((render, copy, false))
+}
+
+/// 生成在写入时重新渲染参数并写入的代码
+/// - 重新计算的长度从当前写入位置起累加，超出预先计算的总长度时 panic，防止 `Display` 等实现两次输出不一致时越界写入
+fn rerender(measure: &proc_macro2::TokenStream, write: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    quote! {
+        {
+            let xl_proc_macro_concat_vars_limit = total_len;
+            let mut total_len = offset;
+            #measure
+            assert!(total_len <= xl_proc_macro_concat_vars_limit);
+            #write
         }
-    } else if is_type(ty, "f64") {
-        quote! {
-            let mut bytes = [0u8; 24];
-            let #var_name = impl_to_ascii::ftoa_buf_f64(&mut bytes, #ident);
-            let mut total_len = #var_name.len();
+    }
+}
+
+/// 渲染参数使用的栈上缓冲区，声明的变量类型为 `&mut [u8; N]`
+/// - 默认每个参数各自声明缓冲区，渲染结果保留到写入阶段
+/// - `scratch` 选项下所有参数借用同一个共享缓冲区的前 N 个字节，`sizes` 记录各参数所需的字节数，用于确定共享缓冲区的大小
+/// - `utf8` 为 `false` 时结果是字节序列，`raw_bytes` 参数不需要是有效的 UTF-8
+pub(crate) struct Buffer<'a> {
+    name: &'a syn::Ident,
+    sizes: Option<&'a mut Vec<proc_macro2::TokenStream>>,
+    utf8: bool,
+}
+
+impl Buffer<'_> {
+    /// 生成声明 `size` 字节缓冲区的代码
+    fn declare(&mut self, size: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        let name = self.name;
+        match &mut self.sizes {
+            Some(sizes) => {
+                let code = quote! { let #name = xl_proc_macro_concat_vars_scratch.first_chunk_mut::<{ #size }>().unwrap(); };
+                sizes.push(size);
+                code
+            }
+            None => quote! { let #name = &mut [0u8; #size]; },
         }
-    } else {
-        panic!("{}", error_msg(ident, ty));
     }
 }
 
-/// 生成后续参数的代码
-pub(crate) fn init_concat_parameter(ident: &Expr, ty: &syn::Type, var_name: syn::Ident) -> proc_macro2::TokenStream {
-    if is_type(ty, "String") || is_type(ty, "string") || is_type(ty, "str") || is_type(ty, "&str") {
-        quote! {
-            total_len += #ident.len();
+/// 生成已渲染为 `var_name` 的值的长度计算代码与写入代码，包括写入时的转换和对齐填充
+fn emit_value(
+    tv: &TypedVar, suffix: &str, var_name: &syn::Ident, byte_sep: Option<&syn::Ident>,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let (value_len, value_copy) = transform_code(tv, var_name, byte_sep);
+    match &tv.align {
+        Some(align) => {
+            let len_name = format_ident!("xl_proc_macro_concat_vars_len_v{}", suffix);
+            let pad_name = format_ident!("xl_proc_macro_concat_vars_pad_v{}", suffix);
+            let (len, copy) = padded_parameter(align, var_name, &len_name, &pad_name, value_copy);
+            (quote! { let #len_name = #value_len; #len }, copy)
         }
-    } else if is_type(ty, "i8") {
-        quote! {
-            let mut bytes = [0u8; 4];
-            let #var_name = impl_to_ascii::itoa_buf_i8(&mut bytes, #ident);
-            total_len += #var_name.len();
+        None => (quote! { total_len += #value_len; }, value_copy),
+    }
+}
+
+/// 将参数表达式以引用的形式绑定到临时变量，返回临时变量名
+/// - 临时变量使用 `mixed_site` 卫生性，不会与用户代码中的同名变量冲突
+/// - 完全相同的无副作用表达式（变量、字段访问、字面量）只绑定一次，其余表达式即使相同也各自求值
+///   - 无后缀的数值字面量的类型取决于参数的类型注解，如 `1.5: f32` 与 `1.5: f64`，不参与复用
+fn bind_argument(expr: &Expr, suffix: String, bindings: &mut Vec<proc_macro2::TokenStream>, bound: &mut Vec<(String, syn::Ident)>) -> syn::Ident {
+    let key = (is_pure_expr(expr) && !is_unsuffixed_number(expr)).then(|| quote! { #expr }.to_string());
+    if let Some(key) = &key {
+        if let Some((_, val_name)) = bound.iter().find(|(bound_key, _)| bound_key == key) {
+            return val_name.clone();
         }
-    } else if is_type(ty, "i16") {
-        quote! {
-            let mut bytes = [0u8; 6];
-            let #var_name = impl_to_ascii::itoa_buf_i16(&mut bytes, #ident);
-            total_len += #var_name.len();
+    }
+    let val_name = format_ident!("xl_proc_macro_concat_vars_val_v{}", suffix, span = proc_macro2::Span::mixed_site());
+    bindings.push(quote! { let #val_name = &(#expr); });
+    if let Some(key) = key {
+        bound.push((key, val_name.clone()));
+    }
+    val_name
+}
+
+/// 判断表达式是否是无后缀的数值字面量及其括号形式
+fn is_unsuffixed_number(expr: &Expr) -> bool {
+    match expr {
+        Expr::Lit(lit) => match &lit.lit {
+            syn::Lit::Int(int) => int.suffix().is_empty(),
+            syn::Lit::Float(float) => float.suffix().is_empty(),
+            _ => false,
+        },
+        Expr::Paren(paren) => is_unsuffixed_number(&paren.expr),
+        Expr::Group(group) => is_unsuffixed_number(&group.expr),
+        _ => false,
+    }
+}
+
+/// 判断表达式是否没有副作用：变量、字段访问、字面量及其括号形式
+fn is_pure_expr(expr: &Expr) -> bool {
+    match expr {
+        Expr::Path(_) | Expr::Lit(_) => true,
+        Expr::Field(field) => is_pure_expr(&field.base),
+        Expr::Paren(paren) => is_pure_expr(&paren.expr),
+        Expr::Group(group) => is_pure_expr(&group.expr),
+        _ => false,
+    }
+}
+
+/// 校验最坏情况下连接结果的字节数不超过 `max = N` 声明的上限
+fn check_max_len(args: &ConcatArgs, max: &syn::LitInt) -> syn::Result<()> {
+    let limit: usize = max.base10_parse()?;
+    let mut total = match &args.options.sep {
+        Some(Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(sep), .. })) => sep.value().len() * (args.vars.len() - 1),
+        Some(sep) => {
+            let msg = lang_tr!(
+                cn = "指定 `max` 时分隔符必须是字符串字面量",
+                en = "The separator must be a string literal when `max` is specified"
+            );
+            return Err(diagnostics::error_spanned("PT0009", sep, msg));
         }
-    } else if is_type(ty, "i32") {
-        quote! {
-            let mut bytes = [0u8; 11];
-            let #var_name = impl_to_ascii::itoa_buf_i32(&mut bytes, #ident);
-            total_len += #var_name.len();
+        None => 0,
+    };
+    for tv in &args.vars {
+        total += max_parameter_len(tv)?;
+    }
+    if total > limit {
+        let msg = lang_tr!(
+            cn = format!("最坏情况下连接结果为 {} 字节，超过了 `max = {}`", total, limit),
+            en = format!("The worst-case concatenated length is {} bytes, which exceeds `max = {}`", total, limit)
+        );
+        return Err(diagnostics::error("PT0010", max.span(), msg));
+    }
+    Ok(())
+}
+
+/// 计算参数在最坏情况下写入的字节数，长度没有上限（如字符串变量、`display`、列表）时返回错误
+pub(crate) fn max_parameter_len(tv: &TypedVar) -> syn::Result<usize> {
+    // 元组和数组按元素逐个计算，元素之间加上分隔符
+    let elems: Vec<Option<&syn::Type>> = match &tv.ty {
+        Some(syn::Type::Tuple(tuple)) => tuple.elems.iter().map(Some).collect(),
+        Some(syn::Type::Array(array)) => match &array.len {
+            Expr::Lit(syn::ExprLit { lit: syn::Lit::Int(len), .. }) => vec![Some(&*array.elem); len.base10_parse()?],
+            _ => return Err(unbounded_error(&tv.ident)),
+        },
+        Some(syn::Type::Slice(_)) => return Err(unbounded_error(&tv.ident)),
+        ty => vec![ty.as_ref()],
+    };
+    let sep_len = match &tv.sep {
+        Some(Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(sep), .. })) => sep.value().len(),
+        Some(sep) if !matches!(tv.mode.as_ref().and_then(transform_of), Some(Transform::HexDump)) => {
+            let msg = lang_tr!(
+                cn = "指定 `max` 时分隔符必须是字符串字面量",
+                en = "The separator must be a string literal when `max` is specified"
+            );
+            return Err(diagnostics::error_spanned("PT0011", sep, msg));
         }
-    } else if is_type(ty, "i64") {
-        quote! {
-            let mut bytes = [0u8; 20];
-            let #var_name = impl_to_ascii::itoa_buf_i64(&mut bytes, #ident);
-            total_len += #var_name.len();
+        _ => 0,
+    };
+    let padding = match &tv.align {
+        Some(align) => align.width.base10_parse::<usize>()? * align.fill.as_ref().map_or(1, |fill| fill.value().len_utf8()),
+        None => 0,
+    };
+    let mut total = sep_len * elems.len().saturating_sub(1);
+    for ty in elems {
+        total += max_value_len(tv, ty)? + padding;
+    }
+    Ok(total)
+}
+
+/// 参数长度没有上限时的错误
+fn unbounded_error(ident: &Expr) -> syn::Error {
+    let var_name = quote! { #ident }.to_string();
+    let msg = lang_tr!(
+        cn = format!("参数 `{}` 的长度没有上限，无法满足 `max` 的要求", var_name),
+        en = format!("The length of parameter `{}` is unbounded, which cannot satisfy `max`", var_name)
+    );
+    diagnostics::error_spanned("PT0012", ident, msg)
+}
+
+/// 计算单个值渲染并转换后的最大字节数，不含对齐填充
+fn max_value_len(tv: &TypedVar, ty: Option<&syn::Type>) -> syn::Result<usize> {
+    let ident = &tv.ident;
+    let unbounded = || unbounded_error(ident);
+    let rendered = match ty {
+        _ if is_str_literal(ident) => literal_text(ident, false).map_or(0, |text| text.len()),
+        None => literal_text(ident, false).ok_or_else(unbounded)?.len(),
+        Some(ty) => {
+            let (ty, _) = peel_type(ty);
+            let (ty, _) = unwrap_integer(ty, quote! {})?;
+            let mode = tv.mode.as_ref().filter(|mode| transform_of(mode).is_none()).map(|mode| mode.name.to_string());
+            if let Some(precision) = &tv.precision {
+                let precision: usize = precision.base10_parse()?;
+                let base = if is_type(&ty, "f32") { F32_FIXED_LEN } else { F64_FIXED_LEN };
+                base + precision
+            } else if is_type(&ty, "timestamp") {
+                if mode.as_deref() == Some("millis") { MILLIS_LEN } else { RFC3339_LEN }
+            } else if is_type(&ty, "Duration") {
+                DURATION_LEN
+            } else if let Some((_, size, _, _)) = NET.iter().find(|(name, _, _, _)| is_type(&ty, name)) {
+                *size
+            } else if let Some(mode) = mode {
+                let bits = NUMERIC[..12].iter().find(|(name, _, _)| is_type(&ty, name)).map_or(128, |(name, _, _)| integer_bits(name));
+                if mode == "hex" { bits / 4 } else { bits }
+            } else if let Some((_, size, _)) = NUMERIC.iter().find(|(name, _, _)| is_type(&ty, name)) {
+                *size
+            } else if is_type(&ty, "char") {
+                4
+            } else if is_type(&ty, "bool") {
+                5
+            } else {
+                return Err(unbounded());
+            }
         }
-    } else if is_type(ty, "i128") {
-        quote! {
-            let mut bytes = [0u8; 40];
-            let #var_name = impl_to_ascii::itoa_buf_i128(&mut bytes, #ident);
-            total_len += #var_name.len();
+    };
+    let transformed = match tv.mode.as_ref().and_then(transform_of) {
+        // 每个字节最多转义为 `\u00XX`
+        Some(Transform::Json) => rendered * 6,
+        // 每个 `"` 写为 `""`，另加两侧引号
+        Some(Transform::Csv) => rendered * 2 + 2,
+        Some(Transform::Upper | Transform::Lower) | None => rendered,
+        Some(Transform::HexDump) => return Err(unbounded()),
+        // 每三位数字最多插入一个分隔符
+        Some(Transform::Grouped) => {
+            let sep_len = tv.mode.as_ref().and_then(|mode| mode.arg.as_ref()).map_or(1, |arg| arg.value().len_utf8());
+            rendered + rendered / 3 * sep_len
         }
-    } else if is_type(ty, "isize") {
-        quote! {
-            let mut bytes = [0u8; #I_SIZE];
-            let #var_name = impl_to_ascii::itoa_buf_isize(&mut bytes, #ident);
-            total_len += #var_name.len();
+        // 分隔符均为单字节
+        Some(Transform::Locale) => {
+            let grouped = tv.mode.as_ref().and_then(|mode| mode.locale).is_some_and(|locale| locale.group.is_some());
+            rendered + if grouped { rendered / 3 } else { 0 }
         }
-    } else if is_type(ty, "u8") {
-        quote! {
-            let mut bytes = [0u8; 3];
-            let #var_name = impl_to_ascii::itoa_buf_u8(&mut bytes, #ident);
-            total_len += #var_name.len();
+    };
+    Ok(transformed)
+}
+
+/// 整数类型的位数，`isize`/`usize` 按宏所在平台计算
+fn integer_bits(name: &str) -> usize {
+    match name {
+        "i8" | "u8" => 8,
+        "i16" | "u16" => 16,
+        "i32" | "u32" => 32,
+        "i64" | "u64" => 64,
+        "isize" | "usize" => usize::BITS as usize,
+        _ => 128,
+    }
+}
+
+/// 生成将引用 `val_name` 指向的值渲染为字节切片 `var_name: &[u8]` 的代码
+/// - 未指定类型（或类型为 `_`）时依赖 `StaticSizeConcatParameter` 等 trait 推断
+pub(crate) fn render_value(
+    tv: &TypedVar, ty: Option<&syn::Type>, val_name: &syn::Ident, var_name: &syn::Ident, buf: &mut Buffer,
+) -> syn::Result<proc_macro2::TokenStream> {
+    if let Some(Mode { name, arg: Some(arg), .. }) = &tv.mode {
+        if name != "grouped" {
+            let msg = lang_tr!(cn = format!("渲染模式 `{}` 不接受参数", name), en = format!("Rendering mode `{}` does not take an argument", name));
+            return Err(diagnostics::error_spanned("PT0013", arg, msg));
+        }
+    }
+    match ty {
+        Some(ty) if !matches!(ty, syn::Type::Infer(_)) => {
+            // 引用和智能指针逐层解引用到内部类型
+            let (ty, levels) = peel_type(ty);
+            let derefs = (0..=levels).map(|_| quote! { * });
+            let (ty, value) = unwrap_integer(ty, quote! { (#(#derefs)* #val_name) })?;
+            let ty = &ty;
+            match (&tv.mode, &tv.precision) {
+                // 十六进制输出任意可视为字节序列的值
+                (Some(mode), None) if matches!(transform_of(mode), Some(Transform::HexDump)) => Ok(quote! {
+                    let #var_name: &[u8] = ::core::convert::AsRef::<[u8]>::as_ref(&#value);
+                }),
+                // 千位分组只适用于整数，先按十进制渲染，写入时再插入分隔符
+                (Some(mode), None) if matches!(transform_of(mode), Some(Transform::Grouped)) => {
+                    if !NUMERIC[..12].iter().any(|(name, _, _)| is_type(ty, name)) {
+                        let mode_ident = &mode.name;
+                        return Err(diagnostics::error_spanned("PT0014", quote! { #ty as #mode_ident }, mode_error_msg(mode, ty)));
+                    }
+                    typed_parameter(&tv.ident, &value, ty, var_name, buf)
+                }
+                // 地区格式只适用于数值，先按默认格式渲染，写入时再替换小数点和分组
+                (Some(mode), _) if matches!(transform_of(mode), Some(Transform::Locale)) && !NUMERIC.iter().any(|(name, _, _)| is_type(ty, name)) => {
+                    let mode_ident = &mode.name;
+                    Err(diagnostics::error_spanned("PT0015", quote! { #ty as #mode_ident }, mode_error_msg(mode, ty)))
+                }
+                (_, Some(precision)) => precision_parameter(&value, ty, precision, var_name, buf),
+                (Some(mode), None) if transform_of(mode).is_none() => mode_parameter(&value, ty, mode, var_name, buf),
+                (_, None) => typed_parameter(&tv.ident, &value, ty, var_name, buf),
+            }
         }
-    } else if is_type(ty, "u16") {
+        _ => {
+            let (decl, bytes_name) = (buf.declare(quote! { 40 }), buf.name);
+            Ok(quote! {
+                #decl
+                let (_, #var_name) = (*#val_name).first_parameter_for_concat(#bytes_name);
+            })
+        }
+    }
+}
+
+/// 将 `NonZero` 与原子整数类型映射为对应的基本类型，并生成取值表达式
+/// - `NonZeroU32`、`NonZero<u32>` 通过 `get()` 取值
+/// - `AtomicU64`、`AtomicBool` 等通过 `load` 取值，默认使用 `Relaxed`，可写为 `AtomicU64<Acquire>` 或 `AtomicU64<SeqCst>` 指定内存序
+/// - 其他类型原样返回
+pub(crate) fn unwrap_integer(ty: &syn::Type, value: proc_macro2::TokenStream) -> syn::Result<(syn::Type, proc_macro2::TokenStream)> {
+    const INTEGERS: [&str; 12] = ["i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize"];
+    let syn::Type::Path(path) = ty else {
+        return Ok((ty.clone(), value));
+    };
+    let Some(segment) = path.path.segments.last() else {
+        return Ok((ty.clone(), value));
+    };
+    let name = segment.ident.to_string();
+    let generic = match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) if args.args.len() == 1 => match args.args.first() {
+            Some(syn::GenericArgument::Type(inner)) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    };
+    // 类型名中的整数部分首字母大写，如 `NonZeroUsize`、`AtomicI64`
+    let capitalize = |int: &str| format!("{}{}", int[..1].to_uppercase(), &int[1..]);
+    let primitive = |int: &str| syn::Type::Path(syn::TypePath { qself: None, path: format_ident!("{}", int).into() });
+
+    if name == "NonZero" {
+        if let Some(inner) = generic.filter(|inner| INTEGERS.iter().any(|int| is_type(inner, int))) {
+            return Ok((inner.clone(), quote! { #value.get() }));
+        }
+    }
+    if let Some(int) = INTEGERS.iter().find(|int| name == format!("NonZero{}", capitalize(int))) {
+        return Ok((primitive(int), quote! { #value.get() }));
+    }
+    let atomic = if name == "AtomicBool" {
+        Some("bool")
+    } else {
+        INTEGERS.iter().find(|int| name == format!("Atomic{}", capitalize(int))).copied()
+    };
+    if let Some(int) = atomic {
+        let ordering = match generic {
+            None => format_ident!("Relaxed"),
+            Some(ordering) if ["Relaxed", "Acquire", "SeqCst"].iter().any(|name| is_type(ordering, name)) => {
+                format_ident!("{}", quote! { #ordering }.to_string())
+            }
+            Some(ordering) => {
+                let msg = lang_tr!(
+                    cn = "原子类型的读取内存序只能是 `Relaxed`、`Acquire` 或 `SeqCst`",
+                    en = "The load ordering of an atomic type must be `Relaxed`, `Acquire` or `SeqCst`"
+                );
+                return Err(diagnostics::error_spanned("PT0016", ordering, msg));
+            }
+        };
+        return Ok((primitive(int), quote! { #value.load(::core::sync::atomic::Ordering::#ordering) }));
+    }
+    Ok((ty.clone(), value))
+}
+
+/// 生成列表参数（如 `items: [u32] sep ","`）的长度计算代码与写入代码
+/// - 参数需提供返回元素引用的 `iter()` 方法，如切片、数组、`Vec`、`VecDeque` 等，元素之间插入可选的分隔符
+/// - 长度计算与写入各遍历一次，元素直接写入结果，不产生中间字符串
+pub(crate) fn repeat_parameter(
+    tv: &TypedVar, elem: &syn::Type, idx: usize, val_name: &syn::Ident, sep_name: &syn::Ident, var_name: &syn::Ident, buf: &mut Buffer,
+) -> syn::Result<(proc_macro2::TokenStream, proc_macro2::TokenStream)> {
+    if let Some(align) = &tv.align {
+        let width = &align.width;
+        let msg = lang_tr!(cn = "列表参数不支持对齐填充", en = "List parameters do not support alignment");
+        return Err(diagnostics::error_spanned("PT0017", width, msg));
+    }
+    let item_name = format_ident!("xl_proc_macro_concat_vars_item_v{}", idx);
+    let render = render_value(tv, Some(elem), &item_name, var_name, buf)?;
+    let (value_len, value_copy) = transform_code(tv, var_name, None);
+
+    let len = quote! {
+        for (i, #item_name) in #val_name.iter().enumerate() {
+            if i > 0 {
+                total_len += #sep_name.len();
+            }
+            #render
+            total_len += #value_len;
+        }
+    };
+    let copy = quote! {
+        for (i, #item_name) in #val_name.iter().enumerate() {
+            #render
+            // 元素在写入时重新渲染，防止 `Display` 等实现两次输出不一致时越界写入
+            assert!(offset + if i > 0 { #sep_name.len() } else { 0 } + #value_len <= total_len);
+            if i > 0 {
+                ::core::ptr::copy_nonoverlapping(#sep_name.as_ptr(), s_ptr.add(offset), #sep_name.len());
+                offset += #sep_name.len();
+            }
+            #value_copy
+        }
+    };
+    Ok((len, copy))
+}
+
+/// 生成指定类型参数的渲染代码
+pub(crate) fn typed_parameter(
+    ident: &Expr, value: &proc_macro2::TokenStream, ty: &syn::Type, var_name: &syn::Ident, buf: &mut Buffer,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let code = if is_type(ty, "String") || is_type(ty, "string") || is_type(ty, "str") {
         quote! {
-            let mut bytes = [0u8; 5];
-            let #var_name = impl_to_ascii::itoa_buf_u16(&mut bytes, #ident);
-            total_len += #var_name.len();
+            let #var_name: &[u8] = #value.as_bytes();
         }
-    } else if is_type(ty, "u32") {
+    } else if let Some((_, size, func)) = NUMERIC.iter().find(|(name, _, _)| is_type(ty, name)) {
+        let (func, decl, bytes_name) = (format_ident!("{}", func), buf.declare(quote! { #size }), buf.name);
         quote! {
-            let mut bytes = [0u8; 10];
-            let #var_name = impl_to_ascii::itoa_buf_u32(&mut bytes, #ident);
-            total_len += #var_name.len();
+            #decl
+            let #var_name: &[u8] = impl_to_ascii::#func(#bytes_name, #value);
         }
-    } else if is_type(ty, "u64") {
+    } else if is_type(ty, "char") {
+        let (decl, bytes_name) = (buf.declare(quote! { 4 }), buf.name);
         quote! {
-            let mut bytes = [0u8; 20];
-            let #var_name = impl_to_ascii::itoa_buf_u64(&mut bytes, #ident);
-            total_len += #var_name.len();
+            #decl
+            let #var_name: &[u8] = #value.encode_utf8(#bytes_name).as_bytes();
         }
-    } else if is_type(ty, "u128") {
+    } else if is_type(ty, "bool") {
         quote! {
-            let mut bytes = [0u8; 39];
-            let #var_name = impl_to_ascii::itoa_buf_u128(&mut bytes, #ident);
-            total_len += #var_name.len();
+            let #var_name: &[u8] = if #value { b"true" } else { b"false" };
         }
-    } else if is_type(ty, "usize") {
+    } else if is_type(ty, "raw_bytes") && !buf.utf8 {
         quote! {
-            let mut bytes = [0u8; #U_SIZE];
-            let #var_name = impl_to_ascii::itoa_buf_usize(&mut bytes, #ident);
-            total_len += #var_name.len();
+            let #var_name: &[u8] = ::core::convert::AsRef::<[u8]>::as_ref(&#value);
         }
-    } else if is_type(ty, "char") {
+    } else if is_type(ty, "raw_bytes") {
+        // 由调用方保证是有效的 UTF-8，长度计算在 `unsafe` 块之外，因此宏调用需位于调用方的 `unsafe` 块中
         quote! {
-            let mut bytes = [0; 4];
-            let #var_name = #ident.encode_utf8(&mut bytes);
-            total_len += #var_name.len();
+            let #var_name: &[u8] = proc_tools_core::utils_core::transform::assume_utf8(::core::convert::AsRef::<[u8]>::as_ref(&#value));
         }
-    } else if is_type(ty, "bool") {
+    } else if is_type(ty, "timestamp") {
+        return timestamp_parameter(value, None, var_name, buf);
+    } else if is_type(ty, "Duration") {
+        return duration_parameter(value, None, var_name, buf);
+    } else if let Some((_, _, size, func)) = NET.iter().find(|(name, _, _, _)| is_type(ty, name)) {
+        let (size, func) = (format_ident!("{}", size), format_ident!("{}", func));
+        let (decl, bytes_name) = (buf.declare(quote! { proc_tools_core::utils_core::net::#size }), buf.name);
         quote! {
-            total_len += if #ident { 4 } else { 5 };
+            #decl
+            let #var_name: &[u8] = proc_tools_core::utils_core::net::#func(#bytes_name, &#value);
         }
-    } else if is_type(ty, "f32") {
+    } else if is_type(ty, "try_display") {
+        let (label, bytes_name) = (try_label(), buf.name);
         quote! {
-            let mut bytes = [0u8; 24];
-            let #var_name = impl_to_ascii::ftoa_buf_f32(&mut bytes, #ident);
-            total_len += #var_name.len();
+            let mut #bytes_name = impl_to_ascii::DisplayBuffer::<128>::new();
+            let #var_name: &[u8] = match #bytes_name.try_render(&#value) {
+                ::core::result::Result::Ok(bytes) => bytes,
+                ::core::result::Result::Err(err) => break #label ::core::result::Result::Err(err),
+            };
         }
-    } else if is_type(ty, "f64") {
+    } else if is_type(ty, "display") {
+        let bytes_name = buf.name;
         quote! {
-            let mut bytes = [0u8; 24];
-            let #var_name = impl_to_ascii::ftoa_buf_f64(&mut bytes, #ident);
-            total_len += #var_name.len();
+            let mut #bytes_name = impl_to_ascii::DisplayBuffer::<128>::new();
+            let #var_name: &[u8] = #bytes_name.render(&#value);
         }
     } else {
-        panic!("{}", error_msg(ident, ty));
+        return Err(diagnostics::error_spanned("PT0018", quote! { #ident: #ty }, error_msg(ident, ty)));
+    };
+    Ok(code)
+}
+
+/// 生成指定小数位数的浮点数参数的渲染代码
+pub(crate) fn precision_parameter(
+    value: &proc_macro2::TokenStream, ty: &syn::Type, precision: &syn::LitInt, var_name: &syn::Ident, buf: &mut Buffer,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let (func, base) = if is_type(ty, "f64") {
+        (quote! { ftoa_buf_f64_fixed }, quote! { proc_tools_core::float2str::fixed::F64_FIXED_LEN })
+    } else if is_type(ty, "f32") {
+        (quote! { ftoa_buf_f32_fixed }, quote! { proc_tools_core::float2str::fixed::F32_FIXED_LEN })
+    } else {
+        let type_ = quote! { #ty }.to_string();
+        let msg = lang_tr!(
+            cn = format!("只有 `f32` 和 `f64` 支持指定小数位数，但实际类型是 `{}`", type_),
+            en = format!("Only `f32` and `f64` support a decimal precision, but the actual type is `{}`", type_)
+        );
+        return Err(diagnostics::error_spanned("PT0019", quote! { #ty . #precision }, msg));
+    };
+    let (decl, bytes_name) = (buf.declare(quote! { #base + #precision }), buf.name);
+    Ok(quote! {
+        #decl
+        let #var_name: &[u8] = impl_to_ascii::#func(#bytes_name, #value, #precision);
+    })
+}
+
+/// 生成带渲染模式（`as hex` 等）参数的渲染代码
+pub(crate) fn mode_parameter(
+    value: &proc_macro2::TokenStream, ty: &syn::Type, mode: &Mode, var_name: &syn::Ident, buf: &mut Buffer,
+) -> syn::Result<proc_macro2::TokenStream> {
+    // 整数类型：(类型, 位数)，`isize`/`usize` 的位数与平台相关
+    let integer: [(&str, proc_macro2::TokenStream); 12] = [
+        ("i8", quote! { 8 }),
+        ("i16", quote! { 16 }),
+        ("i32", quote! { 32 }),
+        ("i64", quote! { 64 }),
+        ("i128", quote! { 128 }),
+        ("isize", quote! { (usize::BITS as usize) }),
+        ("u8", quote! { 8 }),
+        ("u16", quote! { 16 }),
+        ("u32", quote! { 32 }),
+        ("u64", quote! { 64 }),
+        ("u128", quote! { 128 }),
+        ("usize", quote! { (usize::BITS as usize) }),
+    ];
+
+    if is_type(ty, "timestamp") {
+        return timestamp_parameter(value, Some(mode), var_name, buf);
+    }
+    if is_type(ty, "Duration") {
+        return duration_parameter(value, Some(mode), var_name, buf);
+    }
+    let mode_ident = &mode.name;
+    let mode_name = mode.name.to_string();
+    match mode_name.as_str() {
+        "hex" | "bin" => {
+            let Some((name, bits)) = integer.iter().find(|(name, _)| is_type(ty, name)) else {
+                return Err(diagnostics::error_spanned("PT0020", quote! { #ty as #mode_ident }, mode_error_msg(mode, ty)));
+            };
+            let func = format_ident!("itoa_buf_{}_{}", name, mode_name);
+            let size = if mode_name == "hex" { quote! { #bits / 4 } } else { quote! { #bits } };
+            let (decl, bytes_name) = (buf.declare(size), buf.name);
+            Ok(quote! {
+                #decl
+                let #var_name: &[u8] = impl_to_ascii::#func(#bytes_name, #value);
+            })
+        }
+        _ => {
+            let msg = lang_tr!(
+                cn = format!(
+                    "未知的渲染模式 `{}`，可用模式：`hex`、`bin`、`grouped`、`locale`、`json`、`csv`、`upper`、`lower`、`hexdump`",
+                    mode_name
+                ),
+                en = format!(
+                    "Unknown rendering mode `{}`, expected `hex`, `bin`, `grouped`, `locale`, `json`, `csv`, `upper`, `lower` or `hexdump`",
+                    mode_name
+                )
+            );
+            Err(diagnostics::error("PT0021", mode.name.span(), msg))
+        }
     }
 }
 
-/// 生成连接参数的代码
-pub(crate) fn concat_parameter(ident: &Expr, ty: &syn::Type, var_name: syn::Ident) -> proc_macro2::TokenStream {
-    if is_type(ty, "String") || is_type(ty, "string") || is_type(ty, "str") || is_type(ty, "&str") {
-        quote! {
-            std::ptr::copy_nonoverlapping(#ident.as_ptr(), s_ptr.add(offset), #ident.len());
-            offset += #ident.len();
+/// 生成时间戳参数的渲染代码
+/// - 值需实现 `ToUnixTime`（`u64` 秒数、`Duration`、`SystemTime`），默认输出 RFC3339，`as millis` 输出毫秒时间戳
+pub(crate) fn timestamp_parameter(
+    value: &proc_macro2::TokenStream, mode: Option<&Mode>, var_name: &syn::Ident, buf: &mut Buffer,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let mode_name = mode.map_or_else(|| String::from("rfc3339"), |mode| mode.name.to_string());
+    let (size, func) = match mode_name.as_str() {
+        "rfc3339" => (quote! { RFC3339_LEN }, quote! { timestamp_buf_rfc3339 }),
+        "millis" => (quote! { MILLIS_LEN }, quote! { timestamp_buf_millis }),
+        _ => {
+            let msg = lang_tr!(
+                cn = format!("未知的时间戳格式 `{}`，可用格式：`rfc3339`、`millis`", mode_name),
+                en = format!("Unknown timestamp format `{}`, expected `rfc3339` or `millis`", mode_name)
+            );
+            let span = mode.map_or_else(proc_macro2::Span::call_site, |mode| mode.name.span());
+            return Err(diagnostics::error("PT0022", span, msg));
         }
-    } else if is_type(ty, "i8") || is_type(ty, "i16") || is_type(ty, "i32") || is_type(ty, "i64") || is_type(ty, "i128") {
-        quote! {
-            std::ptr::copy_nonoverlapping(#var_name.as_ptr(), s_ptr.add(offset), #var_name.len());
-            offset += #var_name.len();
+    };
+    let (decl, bytes_name) = (buf.declare(quote! { proc_tools_core::utils_core::timestamp::#size }), buf.name);
+    Ok(quote! {
+        #decl
+        let #var_name: &[u8] = proc_tools_core::utils_core::timestamp::#func(
+            #bytes_name,
+            proc_tools_core::utils_core::timestamp::ToUnixTime::to_unix_time(&#value),
+        );
+    })
+}
+
+/// 生成时长参数的渲染代码
+/// - 默认以秒为单位输出 3 位小数，如 `1.234s`，`as ms`、`as us`、`as ns` 选择其他单位
+pub(crate) fn duration_parameter(
+    value: &proc_macro2::TokenStream, mode: Option<&Mode>, var_name: &syn::Ident, buf: &mut Buffer,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let mode_name = mode.map_or_else(|| String::from("s"), |mode| mode.name.to_string());
+    let func = match mode_name.as_str() {
+        "s" => quote! { duration_buf_secs },
+        "ms" => quote! { duration_buf_millis },
+        "us" => quote! { duration_buf_micros },
+        "ns" => quote! { duration_buf_nanos },
+        _ => {
+            let msg = lang_tr!(
+                cn = format!("未知的时长单位 `{}`，可用单位：`s`、`ms`、`us`、`ns`", mode_name),
+                en = format!("Unknown duration unit `{}`, expected `s`, `ms`, `us` or `ns`", mode_name)
+            );
+            let span = mode.map_or_else(proc_macro2::Span::call_site, |mode| mode.name.span());
+            return Err(diagnostics::error("PT0023", span, msg));
         }
-    } else if is_type(ty, "u8") || is_type(ty, "u16") || is_type(ty, "u32") || is_type(ty, "u64") || is_type(ty, "u128") {
-        quote! {
-            std::ptr::copy_nonoverlapping(#var_name.as_ptr(), s_ptr.add(offset), #var_name.len());
-            offset += #var_name.len();
+    };
+    let (decl, bytes_name) = (buf.declare(quote! { proc_tools_core::utils_core::timestamp::DURATION_LEN }), buf.name);
+    Ok(quote! {
+        #decl
+        let #var_name: &[u8] = proc_tools_core::utils_core::timestamp::#func(#bytes_name, #value);
+    })
+}
+
+/// 写入时对渲染结果进行的转换，形如 `as json`
+#[derive(Clone, Copy)]
+pub(crate) enum Transform {
+    /// 按 JSON 字符串内容转义
+    Json,
+    /// 按 CSV 字段规则加引号
+    Csv,
+    /// ASCII 字母转为大写
+    Upper,
+    /// ASCII 字母转为小写
+    Lower,
+    /// 字节序列以十六进制输出
+    HexDump,
+    /// 整数每三位插入分隔符
+    Grouped,
+    /// 按地区替换小数点并可选地分组
+    Locale,
+}
+
+/// 判断渲染模式是否为写入时的转换
+pub(crate) fn transform_of(mode: &Mode) -> Option<Transform> {
+    match mode.name.to_string().as_str() {
+        "json" => Some(Transform::Json),
+        "csv" => Some(Transform::Csv),
+        "upper" => Some(Transform::Upper),
+        "lower" => Some(Transform::Lower),
+        "hexdump" => Some(Transform::HexDump),
+        "grouped" => Some(Transform::Grouped),
+        "locale" => Some(Transform::Locale),
+        _ => None,
+    }
+}
+
+/// 生成将 `var_name` 写入结果的长度表达式与写入代码，写入时按渲染模式进行转换
+/// - `byte_sep` 为 `as hexdump` 的字节分隔符变量
+pub(crate) fn transform_code(
+    tv: &TypedVar, var_name: &syn::Ident, byte_sep: Option<&syn::Ident>,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let (len, write_fn) = match tv.mode.as_ref().and_then(transform_of) {
+        Some(Transform::Grouped) => {
+            let sep = tv.mode.as_ref().and_then(|mode| mode.arg.as_ref()).map_or(',', |arg| arg.value());
+            let sep = syn::LitByteStr::new(sep.encode_utf8(&mut [0u8; 4]).as_bytes(), proc_macro2::Span::call_site());
+            let sep_len = sep.value().len();
+            return (
+                quote! { proc_tools_core::utils_core::transform::grouped_len(#var_name, #sep_len) },
+                quote! { offset += proc_tools_core::utils_core::transform::grouped_to(#var_name, #sep, s_ptr.add(offset)); },
+            );
         }
-    } else if is_type(ty, "isize") || is_type(ty, "usize") {
-        quote! {
-            std::ptr::copy_nonoverlapping(#var_name.as_ptr(), s_ptr.add(offset), #var_name.len());
-            offset += #var_name.len();
+        Some(Transform::Locale) => {
+            let locale = tv.mode.as_ref().and_then(|mode| mode.locale).unwrap_or(Locale { decimal: b'.', group: None });
+            let decimal = locale.decimal;
+            let group = syn::LitByteStr::new(locale.group.as_slice(), proc_macro2::Span::call_site());
+            let group_len = locale.group.is_some() as usize;
+            return (
+                quote! { proc_tools_core::utils_core::transform::localized_len(#var_name, #group_len) },
+                quote! { offset += proc_tools_core::utils_core::transform::localized_to(#var_name, #decimal, #group, s_ptr.add(offset)); },
+            );
         }
-    } else if is_type(ty, "char") {
-        quote! {
-            std::ptr::copy_nonoverlapping(#var_name.as_ptr(), s_ptr.add(offset), #var_name.len());
-            offset += #var_name.len();
+        Some(Transform::HexDump) => {
+            let sep = byte_sep.map_or_else(|| quote! { b"" }, |sep| quote! { #sep.as_bytes() });
+            return (
+                quote! { proc_tools_core::utils_core::transform::hexdump_len(#var_name, #sep) },
+                quote! { offset += proc_tools_core::utils_core::transform::hexdump_to(#var_name, #sep, s_ptr.add(offset)); },
+            );
         }
-    } else if is_type(ty, "bool") {
-        quote! {
-            if #ident {
-                std::ptr::copy_nonoverlapping(b"true".as_ptr(), s_ptr.add(offset), 4);
-                offset += 4;
+        Some(Transform::Json) => (quote! { proc_tools_core::utils_core::transform::json_escaped_len(#var_name) }, quote! { json_escape_to }),
+        Some(Transform::Csv) => (quote! { proc_tools_core::utils_core::transform::csv_escaped_len(#var_name) }, quote! { csv_escape_to }),
+        // 大小写转换不改变长度
+        Some(Transform::Upper) => (quote! { #var_name.len() }, quote! { ascii_upper_to }),
+        Some(Transform::Lower) => (quote! { #var_name.len() }, quote! { ascii_lower_to }),
+        None => {
+            return (
+                quote! { #var_name.len() },
+                quote! {
+                    ::core::ptr::copy_nonoverlapping(#var_name.as_ptr(), s_ptr.add(offset), #var_name.len());
+                    offset += #var_name.len();
+                },
+            );
+        }
+    };
+    (
+        len,
+        quote! { offset += proc_tools_core::utils_core::transform::#write_fn(#var_name, s_ptr.add(offset)); },
+    )
+}
+
+/// 生成带对齐填充的参数长度计算代码与写入代码
+/// - 宽度按字符数计算，不足部分使用填充字符补齐，超出时原样输出
+/// - `len_name` 为写入后的字节数，转换（如 `as json`）新增的字节均为 ASCII，每个字节计为一个字符
+pub(crate) fn padded_parameter(
+    align: &Align, var_name: &syn::Ident, len_name: &syn::Ident, pad_name: &syn::Ident, value: proc_macro2::TokenStream,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let width = &align.width;
+    let fill = align.fill.as_ref().map_or(' ', |c| c.value());
+    let mut fill_buf = [0u8; 4];
+    let fill_bytes = fill.encode_utf8(&mut fill_buf).as_bytes();
+    let fill_len = fill_bytes.len();
+    let fill_lit = syn::LitByteStr::new(fill_bytes, proc_macro2::Span::call_site());
+
+    let len = quote! {
+        let #pad_name = (#width as usize).saturating_sub(impl_to_ascii::utf8_char_count(#var_name) + #len_name - #var_name.len());
+        total_len += #len_name + #pad_name * #fill_len;
+    };
+    let fill_code = |count: proc_macro2::TokenStream| {
+        if fill_len == 1 {
+            let byte = fill_bytes[0];
+            quote! {
+                ::core::ptr::write_bytes(s_ptr.add(offset), #byte, #count);
+                offset += #count;
+            }
+        } else {
+            quote! {
+                for _ in 0..#count {
+                    ::core::ptr::copy_nonoverlapping(#fill_lit.as_ptr(), s_ptr.add(offset), #fill_len);
+                    offset += #fill_len;
+                }
+            }
+        }
+    };
+    let copy = match align.kind {
+        AlignKind::Left => {
+            let after = fill_code(quote! { #pad_name });
+            quote! { #value #after }
+        }
+        AlignKind::Right => {
+            let before = fill_code(quote! { #pad_name });
+            quote! { #before #value }
+        }
+        AlignKind::Center => {
+            let before = fill_code(quote! { #pad_name / 2 });
+            let after = fill_code(quote! { #pad_name - #pad_name / 2 });
+            quote! { #before #value #after }
+        }
+    };
+    (len, copy)
+}
+
+/// `concat_vars!` 的参数：可选的选项列表（以 `;` 结尾）、待连接的参数列表和可选的结果类型（`=> Box<str>`）
+pub(crate) struct ConcatArgs {
+    pub(crate) options: ConcatOptions,
+    pub(crate) vars: Punctuated<TypedVar, Token![,]>,
+    /// 条件参数组的条件，按出现顺序排列
+    pub(crate) guards: Vec<Expr>,
+    pub(crate) output: Option<syn::Type>,
+}
+
+/// `concat_vars!` 的选项，形如 `sep = ", "`
+#[derive(Default)]
+pub(crate) struct ConcatOptions {
+    /// 参数之间的分隔符
+    pub(crate) sep: Option<Expr>,
+    /// 连接结果的字节数上限，在编译期按最坏情况校验
+    pub(crate) max: Option<syn::LitInt>,
+    /// 按精确长度预留容量，不保留额外的增长空间
+    pub(crate) tight: bool,
+    /// 所有参数共用一个栈上缓冲区，写入时重新渲染
+    pub(crate) scratch: bool,
+}
+
+impl syn::parse::Parse for ConcatArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut options = ConcatOptions::default();
+        if has_options(input) {
+            while !input.peek(Token![;]) {
+                let key: syn::Ident = input.parse()?;
+                // `tight`、`scratch` 是不带值的开关
+                if key == "tight" || key == "scratch" {
+                    if key == "tight" {
+                        options.tight = true;
+                    } else {
+                        options.scratch = true;
+                    }
+                    if !input.peek(Token![;]) {
+                        input.parse::<Token![,]>()?;
+                    }
+                    continue;
+                }
+                input.parse::<Token![=]>()?;
+                match key.to_string().as_str() {
+                    "sep" => options.sep = Some(input.parse()?),
+                    "max" => options.max = Some(input.parse()?),
+                    _ => {
+                        let msg = lang_tr!(
+                            cn = format!("未知的选项 `{}`，可用选项：`sep`、`max`、`tight`、`scratch`", key),
+                            en = format!("Unknown option `{}`, expected `sep`, `max`, `tight` or `scratch`", key)
+                        );
+                        return Err(diagnostics::error("PT0024", key.span(), msg));
+                    }
+                }
+                if !input.peek(Token![;]) {
+                    input.parse::<Token![,]>()?;
+                }
+            }
+            input.parse::<Token![;]>()?;
+        }
+        let mut vars = Punctuated::new();
+        let mut guards = Vec::new();
+        while !input.is_empty() && !input.peek(Token![=>]) {
+            if input.peek(Token![if]) {
+                // 条件参数组：`if 条件 { 参数, ... }`
+                input.parse::<Token![if]>()?;
+                let cond = Expr::parse_without_eager_brace(input)?;
+                let content;
+                let braces = syn::braced!(content in input);
+                let group = Punctuated::<TypedVar, Token![,]>::parse_terminated(&content)?;
+                if group.is_empty() {
+                    let msg = lang_tr!(cn = "条件参数组至少需要一个参数", en = "A conditional group requires at least one parameter");
+                    return Err(diagnostics::error("PT0025", braces.span.join(), msg));
+                }
+                for mut tv in group {
+                    tv.guard = Some(guards.len());
+                    vars.push(tv);
+                }
+                guards.push(cond);
             } else {
-                std::ptr::copy_nonoverlapping(b"false".as_ptr(), s_ptr.add(offset), 5);
-                offset += 5;
+                vars.push_value(input.parse()?);
             }
+            if input.is_empty() || input.peek(Token![=>]) {
+                break;
+            }
+            vars.push_punct(input.parse()?);
         }
-    } else if is_type(ty, "f32") || is_type(ty, "f64") {
-        quote! {
-            std::ptr::copy_nonoverlapping(#var_name.as_ptr(), s_ptr.add(offset), #var_name.len());
-            offset += #var_name.len();
+        let output = if input.peek(Token![=>]) {
+            input.parse::<Token![=>]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(ConcatArgs { options, vars, guards, output })
+    }
+}
+
+/// 判断参数列表的顶层是否存在 `;`，存在则说明以选项列表开头
+fn has_options(input: syn::parse::ParseStream) -> bool {
+    let fork = input.fork();
+    while let Ok(tt) = fork.parse::<proc_macro2::TokenTree>() {
+        if let proc_macro2::TokenTree::Punct(punct) = &tt {
+            if punct.as_char() == ';' {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// `concat_vars_into!`、`concat_vars_write!` 的参数：目标缓冲区或写入目标，以及待连接的参数
+pub(crate) struct ConcatIntoArgs {
+    pub(crate) buf: Expr,
+    pub(crate) args: ConcatArgs,
+}
+
+impl syn::parse::Parse for ConcatIntoArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let buf = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let args = input.parse()?;
+        Ok(ConcatIntoArgs { buf, args })
+    }
+}
+
+pub(crate) struct TypedVar {
+    pub(crate) ident: Expr,
+    pub(crate) ty: Option<syn::Type>,
+    pub(crate) mode: Option<Mode>,
+    pub(crate) precision: Option<syn::LitInt>,
+    pub(crate) align: Option<Align>,
+    /// 列表参数元素之间的分隔符
+    pub(crate) sep: Option<Expr>,
+    /// 所属条件参数组在 `ConcatArgs::guards` 中的下标
+    pub(crate) guard: Option<usize>,
+}
+
+/// 渲染模式，形如 `as hex`
+pub(crate) struct Mode {
+    pub(crate) name: syn::Ident,
+    /// 模式参数，如 `as grouped '_'` 中的分组分隔符
+    pub(crate) arg: Option<syn::LitChar>,
+    /// `as locale(de)` 解析得到的数字格式
+    pub(crate) locale: Option<Locale>,
+}
+
+/// 地区的数字格式：小数点，以及启用分组时的千位分隔符
+#[derive(Clone, Copy)]
+pub(crate) struct Locale {
+    pub(crate) decimal: u8,
+    pub(crate) group: Option<u8>,
+}
+
+/// 支持的地区：(名称, 小数点, 千位分隔符)
+const LOCALES: [(&str, u8, u8); 10] = [
+    ("en", b'.', b','),
+    ("zh", b'.', b','),
+    ("ch", b'.', b'\''),
+    ("de", b',', b'.'),
+    ("es", b',', b'.'),
+    ("it", b',', b'.'),
+    ("nl", b',', b'.'),
+    ("pt", b',', b'.'),
+    ("fr", b',', b' '),
+    ("ru", b',', b' '),
+];
+
+/// 对齐方式：`<` 左对齐，`>` 右对齐，`^` 居中
+#[derive(Clone, Copy)]
+pub(crate) enum AlignKind {
+    Left,
+    Right,
+    Center,
+}
+
+/// 对齐填充说明，形如 `> 8` 或 `< 20 '*'`
+pub(crate) struct Align {
+    pub(crate) kind: AlignKind,
+    pub(crate) width: syn::LitInt,
+    pub(crate) fill: Option<syn::LitChar>,
+}
+
+impl syn::parse::Parse for TypedVar {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let ident = input.parse()?;
+
+        // 检查是否有冒号和类型注解
+        if input.peek(Token![:]) {
+            let _colon: Token![:] = input.parse()?;
+            let ty = parse_arg_type(input)?;
+            let mode = parse_mode(input)?;
+            let precision = if input.peek(Token![.]) {
+                input.parse::<Token![.]>()?;
+                Some(input.parse()?)
+            } else {
+                None
+            };
+            let sep = parse_item_sep(input)?;
+            let align = parse_align(input)?;
+            Ok(TypedVar { ident, ty: Some(ty), mode, precision, align, sep, guard: None })
+        } else {
+            Ok(TypedVar { ident, ty: None, mode: None, precision: None, align: None, sep: None, guard: None })
         }
+    }
+}
+
+/// 解析参数类型
+/// - `str < 20` 这类写法中 `<` 表示左对齐而不是泛型参数，需要单独处理
+fn parse_arg_type(input: syn::parse::ParseStream) -> syn::Result<syn::Type> {
+    if input.peek(syn::Ident) && input.peek2(Token![<]) && input.peek3(syn::LitInt) {
+        let ident: syn::Ident = input.parse()?;
+        Ok(syn::Type::Path(syn::TypePath { qself: None, path: ident.into() }))
+    } else if input.peek(Token![&]) && !input.peek2(syn::Lifetime) {
+        let and_token = input.parse()?;
+        let mutability = input.parse()?;
+        let elem = Box::new(parse_arg_type(input)?);
+        Ok(syn::Type::Reference(syn::TypeReference { and_token, lifetime: None, mutability, elem }))
     } else {
-        panic!("{}", error_msg(ident, ty));
+        input.parse()
     }
 }
 
-#[inline]
-pub(crate) fn error_msg(ident: &Expr, ty: &syn::Type) -> String {
-    let type_ = if let syn::Type::Path(path) = ty {
-        path.path.segments[0].clone().ident.to_string()
+/// 解析可选的渲染模式
+fn parse_mode(input: syn::parse::ParseStream) -> syn::Result<Option<Mode>> {
+    if !input.peek(Token![as]) {
+        return Ok(None);
+    }
+    input.parse::<Token![as]>()?;
+    let name: syn::Ident = input.parse()?;
+    let arg = if input.peek(syn::LitChar) { Some(input.parse()?) } else { None };
+    let locale = if name == "locale" { Some(parse_locale(input, &name)?) } else { None };
+    Ok(Some(Mode { name, arg, locale }))
+}
+
+/// 解析 `locale` 模式的参数，形如 `(de)` 或 `(de, grouped)`
+fn parse_locale(input: syn::parse::ParseStream, name: &syn::Ident) -> syn::Result<Locale> {
+    if !input.peek(syn::token::Paren) {
+        let msg = lang_tr!(cn = "`locale` 模式需要指定地区，如 `as locale(de)`", en = "The `locale` mode requires a locale, such as `as locale(de)`");
+        return Err(diagnostics::error("PT0026", name.span(), msg));
+    }
+    let content;
+    syn::parenthesized!(content in input);
+    let params = Punctuated::<syn::Ident, Token![,]>::parse_terminated(&content)?;
+    let mut params = params.iter();
+    let Some(code) = params.next() else {
+        let msg = lang_tr!(cn = "`locale` 模式需要指定地区，如 `as locale(de)`", en = "The `locale` mode requires a locale, such as `as locale(de)`");
+        return Err(diagnostics::error("PT0027", name.span(), msg));
+    };
+    let Some(&(_, decimal, group)) = LOCALES.iter().find(|(locale, _, _)| code == locale) else {
+        let names = LOCALES.iter().map(|(locale, _, _)| format!("`{}`", locale)).collect::<Vec<_>>().join(", ");
+        let msg = lang_tr!(
+            cn = format!("不支持的地区 `{}`，可用地区：{}", code, names),
+            en = format!("Unsupported locale `{}`, expected one of {}", code, names)
+        );
+        return Err(diagnostics::error("PT0028", code.span(), msg));
+    };
+    let grouped = match params.next() {
+        Some(flag) if flag == "grouped" => true,
+        Some(flag) => {
+            let msg = lang_tr!(cn = format!("未知的地区选项 `{}`，可用选项：`grouped`", flag), en = format!("Unknown locale option `{}`, expected `grouped`", flag));
+            return Err(diagnostics::error("PT0029", flag.span(), msg));
+        }
+        None => false,
+    };
+    if let Some(extra) = params.next() {
+        let msg = lang_tr!(cn = "`locale` 模式最多接受两个参数", en = "The `locale` mode accepts at most two arguments");
+        return Err(diagnostics::error("PT0030", extra.span(), msg));
+    }
+    Ok(Locale { decimal, group: grouped.then_some(group) })
+}
+
+/// 解析列表参数可选的元素分隔符，形如 `sep ","`
+/// - 分隔符可以是字符串字面量、变量或括号包裹的表达式
+fn parse_item_sep(input: syn::parse::ParseStream) -> syn::Result<Option<Expr>> {
+    if !input.fork().parse::<syn::Ident>().is_ok_and(|ident| ident == "sep") {
+        return Ok(None);
+    }
+    input.parse::<syn::Ident>()?;
+    let sep = if input.peek(syn::LitStr) {
+        Expr::Lit(input.parse()?)
+    } else if input.peek(syn::token::Paren) {
+        Expr::Paren(input.parse()?)
     } else {
-        panic!("{}", lang_tr!(cn = "参数异常", en = "Parameter exception"))
+        Expr::Path(input.parse()?)
     };
-    let var_name = if let Expr::Path(path) = ident {
-        path.path.segments[0].clone().ident.to_string()
+    Ok(Some(sep))
+}
+
+/// 解析可选的对齐填充说明
+fn parse_align(input: syn::parse::ParseStream) -> syn::Result<Option<Align>> {
+    let kind = if input.peek(Token![<]) {
+        input.parse::<Token![<]>()?;
+        AlignKind::Left
+    } else if input.peek(Token![>]) {
+        input.parse::<Token![>]>()?;
+        AlignKind::Right
+    } else if input.peek(Token![^]) {
+        input.parse::<Token![^]>()?;
+        AlignKind::Center
     } else {
-        panic!("{}", lang_tr!(cn = "参数异常", en = "Parameter exception"))
+        return Ok(None);
     };
+    let width = input.parse()?;
+    let fill = if input.peek(syn::LitChar) { Some(input.parse()?) } else { None };
+    Ok(Some(Align { kind, width, fill }))
+}
+
+#[inline]
+pub(crate) fn error_msg(ident: &Expr, ty: &syn::Type) -> String {
+    let type_ = quote! { #ty }.to_string();
+    let var_name = quote! { #ident }.to_string();
     let _cn_msg = format!(
-        "参数类型错误，参数 `{:?}` 类型必须是 `基本数据类型` 或者是 `字符串`，但实际是 `{}`",
+        "参数类型错误，参数 `{}` 类型必须是 `基本数据类型` 或者是 `字符串`，但实际是 `{}`",
         var_name, type_
     );
     let _en_msg = format!(
@@ -387,6 +1758,53 @@ pub(crate) fn error_msg(ident: &Expr, ty: &syn::Type) -> String {
     lang_tr!(cn = _cn_msg, en = _en_msg)
 }
 
+/// 剥离引用和智能指针（`Box`、`Rc`、`Arc`、`Cow`），返回内部类型和需要额外解引用的层数
+/// - 例如 `&&str` 返回 `(str, 2)`，`Rc<String>` 返回 `(String, 1)`
+pub(crate) fn peel_type(ty: &syn::Type) -> (&syn::Type, usize) {
+    match ty {
+        syn::Type::Reference(reference) => {
+            let (inner, levels) = peel_type(&reference.elem);
+            (inner, levels + 1)
+        }
+        syn::Type::Paren(paren) => peel_type(&paren.elem),
+        syn::Type::Path(path) if path.qself.is_none() => {
+            let Some(segment) = path.path.segments.last() else {
+                return (ty, 0);
+            };
+            if !["Box", "Rc", "Arc", "Cow"].iter().any(|name| segment.ident == name) {
+                return (ty, 0);
+            }
+            let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+                return (ty, 0);
+            };
+            let inner = args.args.iter().find_map(|arg| if let syn::GenericArgument::Type(inner) = arg { Some(inner) } else { None });
+            match inner {
+                Some(inner) => {
+                    let (inner, levels) = peel_type(inner);
+                    (inner, levels + 1)
+                }
+                None => (ty, 0),
+            }
+        }
+        _ => (ty, 0),
+    }
+}
+
+/// 判断参数是否为字符串字面量
+#[inline]
+pub(crate) fn is_str_literal(ident: &Expr) -> bool {
+    matches!(ident, Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(_), .. }))
+}
+
+#[inline]
+pub(crate) fn mode_error_msg(mode: &Mode, ty: &syn::Type) -> String {
+    let type_ = quote! { #ty }.to_string();
+    lang_tr!(
+        cn = format!("渲染模式 `{}` 不支持类型 `{}`", mode.name, type_),
+        en = format!("Rendering mode `{}` does not support type `{}`", mode.name, type_)
+    )
+}
+
 #[inline]
 pub(crate) fn is_type(ty: &syn::Type, s: &str) -> bool {
     if let syn::Type::Path(path) = ty {
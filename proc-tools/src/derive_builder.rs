@@ -0,0 +1,161 @@
+use crate::derive_nwe::is_phantom_data;
+use crate::diagnostics;
+use proc_macro::TokenStream;
+use proc_tools_helper::lang_tr;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::{Data, DeriveInput, Expr, Fields, parse_macro_input};
+
+pub(crate) fn derive_builder_implement(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match builder_code(&input) {
+        Ok(code) => TokenStream::from(code),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// 生成 `类型名Builder` 构建器及 `类型名::builder()`
+/// - 每个字段在构建器中保存为 `Option<字段类型>`，同名的设置方法按值接收构建器并返回，可以链式调用
+/// - 字段类型为 `Option<T>` 时设置方法接收 `T`，未设置时为 `None`；带 `#[builder(default)]` 的字段未设置时使用默认值
+/// - 其余字段为必填，`build()` 在必填字段未设置时返回 `BuildError`
+/// - `PhantomData` 字段没有设置方法
+fn builder_code(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            fields => {
+                let msg = lang_tr!(cn = "仅支持具有命名字段的结构体", en = "Only structs with named fields are supported");
+                return Err(diagnostics::error_spanned("PT0301", fields, msg));
+            }
+        },
+        _ => {
+            let msg = lang_tr!(cn = "仅支持结构体", en = "Only structs are supported");
+            return Err(diagnostics::error_spanned("PT0302", name, msg));
+        }
+    };
+    let vis = &input.vis;
+    let builder = format_ident!("{}Builder", name);
+    let generics = &input.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let mut slots = Vec::new();
+    let mut empties = Vec::new();
+    let mut setters = Vec::new();
+    let mut builds = Vec::new();
+    for field in fields {
+        let options = BuilderOptions::parse(&field.attrs)?;
+        let (ident, ty) = (&field.ident, &field.ty);
+        if is_phantom_data(ty) {
+            slots.push(quote! { #ident: #ty });
+            empties.push(quote! { #ident: ::core::marker::PhantomData });
+            builds.push(quote! { #ident: ::core::marker::PhantomData });
+            continue;
+        }
+        let field_name = ident.as_ref().map(|ident| ident.unraw().to_string()).unwrap_or_default();
+        let (slot_ty, value_ty, build) = match (option_inner(ty), options.default) {
+            (Some(inner), None) => (quote! { #ty }, quote! { #inner }, quote! { self.#ident }),
+            (Some(inner), Some(default)) => (quote! { #ty }, quote! { #inner }, quote! {
+                match self.#ident {
+                    ::core::option::Option::Some(value) => ::core::option::Option::Some(value),
+                    ::core::option::Option::None => #default,
+                }
+            }),
+            (None, Some(default)) => (quote! { ::core::option::Option<#ty> }, quote! { #ty }, quote! {
+                match self.#ident {
+                    ::core::option::Option::Some(value) => value,
+                    ::core::option::Option::None => #default,
+                }
+            }),
+            (None, None) => (quote! { ::core::option::Option<#ty> }, quote! { #ty }, quote! {
+                match self.#ident {
+                    ::core::option::Option::Some(value) => value,
+                    ::core::option::Option::None => {
+                        return ::core::result::Result::Err(proc_tools_core::utils_core::builder::BuildError::missing(#field_name));
+                    }
+                }
+            }),
+        };
+        let doc = format!("设置 `{}` 字段", field_name);
+        slots.push(quote! { #ident: #slot_ty });
+        empties.push(quote! { #ident: ::core::option::Option::None });
+        setters.push(quote! {
+            #[doc = #doc]
+            #vis fn #ident(mut self, value: #value_ty) -> Self {
+                self.#ident = ::core::option::Option::Some(value);
+                self
+            }
+        });
+        builds.push(quote! { #ident: #build });
+    }
+
+    let builder_doc = format!("[`{}`] 的构建器，由 [`{}::builder`] 创建", name, name);
+    let new_doc = format!("创建 [`{}`]，所有字段均未设置", builder);
+    Ok(quote! {
+        #[doc = #builder_doc]
+        #[must_use]
+        #vis struct #builder #generics #where_clause {
+            #(#slots),*
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            #[doc = #new_doc]
+            #vis fn builder() -> #builder #ty_generics {
+                #builder { #(#empties),* }
+            }
+        }
+
+        impl #impl_generics #builder #ty_generics #where_clause {
+            #(#setters)*
+
+            /// 构建结构体，必填字段未设置时返回错误
+            #vis fn build(self) -> ::core::result::Result<#name #ty_generics, proc_tools_core::utils_core::builder::BuildError> {
+                ::core::result::Result::Ok(#name { #(#builds),* })
+            }
+        }
+    })
+}
+
+/// 字段类型为 `Option<T>` 时返回 `T`
+fn option_inner(ty: &syn::Type) -> Option<&syn::Type> {
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last().filter(|segment| path.qself.is_none() && segment.ident == "Option")?;
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first() {
+        Some(syn::GenericArgument::Type(inner)) if args.args.len() == 1 => Some(inner),
+        _ => None,
+    }
+}
+
+/// 字段上的 `#[builder(...)]` 选项
+#[derive(Default)]
+struct BuilderOptions {
+    /// 字段未设置时的默认值
+    /// - `#[builder(default)]` 为 `Default::default()`，`#[builder(default = "8080")]` 为给定的表达式
+    default: Option<proc_macro2::TokenStream>,
+}
+
+impl BuilderOptions {
+    fn parse(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut options = BuilderOptions::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("builder")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("default") {
+                    options.default = Some(if meta.input.peek(syn::Token![=]) {
+                        let expr = meta.value()?.parse::<syn::LitStr>()?.parse::<Expr>()?;
+                        quote! { #expr }
+                    } else {
+                        quote! { ::core::default::Default::default() }
+                    });
+                    return Ok(());
+                }
+                Err(diagnostics::unknown_option("PT0303", &meta, "`default`", "`default`"))
+            })?;
+        }
+        Ok(options)
+    }
+}
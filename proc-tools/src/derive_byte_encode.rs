@@ -1,172 +1,1999 @@
+use crate::diagnostics;
 use proc_macro::TokenStream;
 use proc_tools_helper::lang_tr;
-use quote::quote;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
 use syn::spanned::Spanned;
-use syn::{parse_macro_input, Data, DeriveInput, Expr, Fields, Lit, LitInt, Type};
+use syn::{Data, DeriveInput, Fields, LitInt, Type, parse_macro_input};
 
 pub(crate) fn byte_encode_implement(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
-    let name = input.ident;
+    match byte_encode_code(&input) {
+        Ok(code) => TokenStream::from(code),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
 
-    let fields = if let Data::Struct(data) = input.data {
-        match data.fields {
-            Fields::Named(fields) => fields.named,
-            _ => panic!(lang_tr!(
-                cn = "字段类型不支持，仅支持具有命名字段的结构体",
-                en = "Only structs with named fields are supported"
-            )),
+/// 生成 `proc_tools_core::ByteEncode` 的实现
+/// - 结构体的字段按声明顺序依次编码，无字段枚举编码为 `#[repr]` 指定的整数类型的判别值，带数据的枚举编码为标签和内容
+/// - 多字节数值的字节序依次取字段上的 `#[byte_encode(big_endian)]`/`#[byte_encode(little_endian)]`、
+///   类型上的 `#[byte_encode(endian = "big")]`，默认为小端
+fn byte_encode_code(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let struct_options = StructOptions::parse(&input.attrs)?;
+    if let (Some(version), Data::Enum(_)) = (&struct_options.version, &input.data) {
+        let msg = lang_tr!(cn = "`version` 仅支持结构体", en = "`version` is only supported on structs");
+        return Err(diagnostics::error_spanned("PT0101", version, msg));
+    }
+    if let (Some(verify_layout), Data::Enum(_)) = (&struct_options.verify_layout, &input.data) {
+        let msg = lang_tr!(cn = "`verify_layout` 仅支持结构体", en = "`verify_layout` is only supported on structs");
+        return Err(diagnostics::error_spanned("PT0102", verify_layout, msg));
+    }
+    if let (true, Data::Enum(_)) = (struct_options.in_place, &input.data) {
+        let msg = lang_tr!(cn = "`in_place` 仅支持结构体", en = "`in_place` is only supported on structs");
+        return Err(diagnostics::error_spanned("PT0103", name, msg));
+    }
+    // 泛型类型的 `SIZE` 依赖泛型参数，不能用作数组长度，改用 `Vec<u8>`
+    let generic = !input.generics.params.is_empty();
+    if generic && (struct_options.view || struct_options.verify_layout.is_some()) {
+        let msg = lang_tr!(
+            cn = "泛型类型不支持 `view` 和 `verify_layout`",
+            en = "`view` and `verify_layout` are not supported on generic types"
+        );
+        return Err(diagnostics::error_spanned("PT0104", &input.generics, msg));
+    }
+    let Body { sizes: field_sizes, ser: field_ser, value, accessors, version, layout, field_layout, in_place, fallible } = match &input.data {
+        Data::Struct(data) => struct_body(input, &data.fields, &struct_options)?,
+        Data::Enum(data) if data.variants.iter().any(|variant| !matches!(variant.fields, Fields::Unit)) => union_body(input, data, &struct_options)?,
+        Data::Enum(data) => enum_body(input, data, &struct_options)?,
+        Data::Union(_) => {
+            let msg = lang_tr!(cn = "仅支持结构体和枚举", en = "Only structs and enums are supported");
+            return Err(diagnostics::error_spanned("PT0105", name, msg));
         }
-    } else {
-        panic!(lang_tr!(cn = "仅支持结构体", en = "Only structs are supported"));
+    };
+    let view = match (&input.data, struct_options.view) {
+        (Data::Struct(_), true) if version.is_some() => {
+            let msg = lang_tr!(cn = "`view` 不能与 `version` 一起使用", en = "`view` cannot be combined with `version`");
+            return Err(diagnostics::error_spanned("PT0106", name, msg));
+        }
+        (Data::Struct(_), true) => Some(view_code(input, &accessors)),
+        (_, true) => {
+            let msg = lang_tr!(cn = "`view` 仅支持结构体", en = "`view` is only supported on structs");
+            return Err(diagnostics::error_spanned("PT0107", name, msg));
+        }
+        _ => None,
     };
 
-    // 在编译时计算结构体总大小
-    let total_size = fields.iter().fold(0, |acc, field| acc + get_type_size(&field.ty));
-
-    // 创建字面量常量
-    let total_size_lit = LitInt::new(&total_size.to_string(), name.span());
-
-    // 序列化实现
-    let to_bytes_impl = {
-        let field_ser = fields.iter().map(|f| {
-            let field_name = &f.ident;
-            let field_ty = &f.ty;
-            let field_size = get_type_size(field_ty);
-            let field_size_lit = LitInt::new(&field_size.to_string(), f.ident.span());
-
-            // 检查字段类型是否为 [u8; N]
-            if let Type::Array(array_ty) = field_ty {
-                if let Type::Path(type_path) = &*array_ty.elem {
-                    if type_path.path.is_ident("u8") {
-                        return quote! {
-                            buffer[pos..pos + #field_size_lit].copy_from_slice(&self.#field_name);
-                            pos += #field_size_lit;
-                        };
-                    }
+    let layout = struct_options.verify_layout.is_some().then(|| {
+        let vis = &input.vis;
+        let size_msg = lang_tr!(
+            cn = format!("`{}` 在内存中的大小与 `SIZE` 不一致，可能有填充字节", name),
+            en = format!("the memory size of `{}` differs from `SIZE`, it may contain padding", name)
+        );
+        quote! {
+            const _: () = {
+                assert!(::core::mem::size_of::<#name>() == <#name as proc_tools_core::ByteEncode>::SIZE, #size_msg);
+                #(#layout)*
+            };
+
+            impl #name {
+                /// 直接借用内存中的字节，与 `to_bytes()` 的结果相同，不需要逐个字段编码
+                #[inline]
+                #vis fn as_bytes(&self) -> &[u8; <Self as proc_tools_core::ByteEncode>::SIZE] {
+                    // SAFETY: 上面的常量断言保证结构体没有填充字节，各字段的偏移、大小、字节序与编码结果一致，
+                    // 对齐为 1，因此内存中的每个字节都已初始化，且与编码结果相同
+                    unsafe { &*::core::ptr::from_ref(self).cast::<[u8; <Self as proc_tools_core::ByteEncode>::SIZE]>() }
                 }
             }
+        }
+    });
 
-            // 对于其他类型，使用 to_le_bytes 方法
+    // 字段布局，用于调试时标注十六进制转储
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let describe = matches!(input.data, Data::Struct(_)).then(|| {
+        let vis = &input.vis;
+        let entries = field_layout.iter().map(|LayoutEntry { label, offset, size, .. }| quote! { (#label, #offset, #size) });
+        let offsets = field_layout.iter().map(|LayoutEntry { label, offset, .. }| {
+            let ident = format_ident!("OFFSET_{}", label.to_uppercase());
+            let doc = format!("`{}` 字段在编码结果中的字节偏移，可以直接修改已编码数据中的该字段", label);
             quote! {
-                let bytes = self.#field_name.to_le_bytes();
-                buffer[pos..pos + bytes.len()].copy_from_slice(&bytes);
-                pos += bytes.len();
+                #[doc = #doc]
+                #vis const #ident: usize = #offset;
             }
         });
-
         quote! {
-            impl #name {
-                pub const SIZE: usize = #total_size_lit;
+            impl #impl_generics #name #ty_generics #where_clause {
+                #(#offsets)*
 
-                pub fn to_bytes(&self) -> [u8; Self::SIZE] {
-                    let mut buffer = [0u8; Self::SIZE];
-                    let mut pos = 0;
-                    #(#field_ser)*
-                    buffer
+                /// 各字段的名称、编码后的字节偏移和字节数，按编码顺序排列；跳过的字段不在其中，保留区域和版本号不单独列出
+                #vis const LAYOUT: &'static [(&'static str, usize, usize)] = &[#(#entries),*];
+
+                /// 按 `LAYOUT` 逐字段标注的十六进制转储，用于调试时打印报文
+                #[inline]
+                #vis fn describe(bytes: &[u8]) -> proc_tools_core::utils_core::byte_encode::Describe<'_> {
+                    proc_tools_core::utils_core::byte_encode::Describe::new(Self::LAYOUT, bytes)
                 }
             }
         }
-    };
+    });
 
-    // 反序列化实现
-    let from_bytes_impl = {
-        let err_msg = lang_tr!(cn = "切片长度不匹配", en = "slice length mismatch");
-        let field_deser = fields.iter().map(|f| {
-            let field_name = &f.ident;
-            let field_ty = &f.ty;
-            let field_size = get_type_size(field_ty);
-            let field_size_lit = LitInt::new(&field_size.to_string(), f.ident.span());
-
-            // 检查字段类型是否为 [u8; N]
-            if let Type::Array(array_ty) = field_ty {
-                if let Type::Path(type_path) = &*array_ty.elem {
-                    if type_path.path.is_ident("u8") {
-                        return quote! {
-                            #field_name: {
-                                let mut arr = [0u8; #field_size_lit];
-                                arr.copy_from_slice(&bytes[pos..pos + #field_size_lit]);
-                                pos += #field_size_lit;
-                                arr
-                            }
-                        };
-                    }
+    // 按字段标注偏移和编码后字节的 `Debug`，跳过的字段不显示
+    let debug_hex = match (&input.data, struct_options.debug_hex) {
+        (Data::Struct(data), true) => {
+            let packed = is_repr_packed(&input.attrs);
+            let fields = field_layout.iter().map(|LayoutEntry { member, label, offset, size, .. }| {
+                // packed 结构体的字段不能借用，复制一份
+                let value = match packed {
+                    true => quote! { &{ self.#member } },
+                    false => quote! { &self.#member },
+                };
+                let hex = quote! { &proc_tools_core::utils_core::byte_encode::DebugHex::new(#value, #offset, &bytes[#offset..#offset + #size]) };
+                match member {
+                    syn::Member::Named(_) => quote! { s.field(#label, #hex); },
+                    syn::Member::Unnamed(_) => quote! { s.field(#hex); },
+                }
+            });
+            let (builder, finish) = match (&data.fields, data.fields.len() == field_layout.len()) {
+                (Fields::Unnamed(_), true) => (quote! { debug_tuple }, quote! { finish }),
+                (Fields::Unnamed(_), false) => (quote! { debug_tuple }, quote! { finish_non_exhaustive }),
+                (_, true) => (quote! { debug_struct }, quote! { finish }),
+                (_, false) => (quote! { debug_struct }, quote! { finish_non_exhaustive }),
+            };
+            let mut generics = input.generics.clone();
+            if generic {
+                let where_clause = generics.make_where_clause();
+                for LayoutEntry { ty, .. } in &field_layout {
+                    where_clause.predicates.push(syn::parse_quote! { #ty: ::core::fmt::Debug });
                 }
             }
+            let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+            let name_str = name.to_string();
+            Some(quote! {
+                impl #impl_generics ::core::fmt::Debug for #name #ty_generics #where_clause {
+                    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        let bytes = proc_tools_core::ByteEncode::to_bytes(self);
+                        let bytes: &[u8] = ::core::convert::AsRef::as_ref(&bytes);
+                        let mut s = f.#builder(#name_str);
+                        #(#fields)*
+                        s.#finish()
+                    }
+                }
+            })
+        }
+        (_, true) => {
+            let msg = lang_tr!(cn = "`debug_hex` 仅支持结构体", en = "`debug_hex` is only supported on structs");
+            return Err(diagnostics::error_spanned("PT0108", name, msg));
+        }
+        (_, false) => None,
+    };
 
-            // 对于其他类型，使用 from_le_bytes 方法
+    // 已知长度的字节数组可以跳过长度检查，解码不会失败时还可以省去错误处理
+    let from_array = (matches!(input.data, Data::Struct(_)) && version.is_none() && !generic).then(|| {
+        let vis = &input.vis;
+        let (output, result) = match fallible {
+            false => (quote! { Self }, quote! { value }),
+            true => (quote! { ::core::result::Result<Self, proc_tools_core::DecodeError> }, quote! { ::core::result::Result::Ok(value) }),
+        };
+        let unchecked = (!fallible).then(|| {
             quote! {
-                #field_name: {
-                    let value = <#field_ty>::from_le_bytes(
-                        bytes[pos..pos + #field_size_lit]
-                            .try_into()
-                            .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, #err_msg))?
-                    );
-                    pos += #field_size_lit;
+                /// 从字节切片解码，不检查长度，所有字段的解码都不会失败
+                /// - 长度不足时 panic，多余的字节被忽略
+                #[inline]
+                #vis fn from_bytes_unchecked(bytes: &[u8]) -> Self {
+                    let mut pos = 0;
+                    let value = #value;
+                    let _ = pos;
                     value
                 }
             }
         });
-
         quote! {
             impl #name {
-                pub fn from_bytes(bytes: &[u8]) -> Result<Self, std::io::Error> {
-                    if bytes.len() != Self::SIZE {
-                        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, #err_msg));
-                    }
+                /// 从字节数组解码，长度已由类型保证，不需要长度检查
+                #[inline]
+                #vis fn from_array(bytes: &[u8; <Self as proc_tools_core::ByteEncode>::SIZE]) -> #output {
+                    let bytes: &[u8] = bytes;
                     let mut pos = 0;
-                    Ok(Self {
-                        #(#field_deser),*
-                    })
+                    let value = #value;
+                    let _ = pos;
+                    #result
                 }
+
+                #unchecked
             }
         }
+    });
+
+    let size = quote! { <Self as proc_tools_core::ByteEncode>::SIZE };
+    let (bytes_ty, zeroed) = match generic {
+        true => (quote! { proc_tools_core::alloc::vec::Vec<u8> }, quote! { proc_tools_core::alloc::vec![0u8; #size] }),
+        false => (quote! { [u8; #size] }, quote! { [0u8; #size] }),
     };
+    // 带版本号的结构体先读出版本号，旧版本的数据较短，按版本号计算长度
+    let (read_from, len_check) = match &version {
+        Some((current, version_size)) => {
+            // `from_bytes` 与 `read_from` 共用，错误类型分别为 `DecodeError` 和 `io::Error`
+            let version_check = quote! {
+                if version > #current {
+                    let err = proc_tools_core::DecodeError::UnsupportedVersion { version, max: #current };
+                    return ::core::result::Result::Err(::core::convert::Into::into(err));
+                }
+            };
+            (
+                quote! {
+                    let mut buffer = #zeroed;
+                    ::std::io::Read::read_exact(reader, &mut buffer[..1])?;
+                    let version = buffer[0];
+                    #version_check
+                    let len = #version_size;
+                    ::std::io::Read::read_exact(reader, &mut buffer[1..len])?;
+                    ::core::result::Result::Ok(<Self as proc_tools_core::ByteEncode>::from_bytes(&buffer[..len])?)
+                },
+                quote! {
+                    let version = match bytes.first() {
+                        ::core::option::Option::Some(&version) => version,
+                        ::core::option::Option::None => {
+                            return ::core::result::Result::Err(proc_tools_core::DecodeError::UnexpectedLength { expected: 1, got: 0 });
+                        }
+                    };
+                    #version_check
+                    let len = #version_size;
+                    if bytes.len() != len {
+                        return ::core::result::Result::Err(proc_tools_core::DecodeError::UnexpectedLength { expected: len, got: bytes.len() });
+                    }
+                },
+            )
+        }
+        None => (
+            quote! {
+                let mut buffer = #zeroed;
+                ::std::io::Read::read_exact(reader, &mut buffer)?;
+                ::core::result::Result::Ok(<Self as proc_tools_core::ByteEncode>::from_bytes(&buffer)?)
+            },
+            quote! {
+                if bytes.len() != #size {
+                    return ::core::result::Result::Err(proc_tools_core::DecodeError::UnexpectedLength { expected: #size, got: bytes.len() });
+                }
+            },
+        ),
+    };
+    // 通过字节表示序列化，`'de` 加在原有的泛型参数之前
+    let serde = struct_options.serde.as_ref().map(|repr| {
+        let module = format_ident!("{}", repr.value());
+        let helper = quote! { proc_tools_core::utils_core::byte_encode_serde::#module };
+        let mut de_generics = input.generics.clone();
+        de_generics.params.insert(0, syn::parse_quote! { 'de });
+        let (de_impl_generics, _, _) = de_generics.split_for_impl();
+        quote! {
+            impl #impl_generics proc_tools_core::serde::Serialize for #name #ty_generics #where_clause {
+                fn serialize<S: proc_tools_core::serde::Serializer>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error> {
+                    #helper::serialize(self, serializer)
+                }
+            }
 
-    let expanded = quote! {
-        #to_bytes_impl
-        #from_bytes_impl
+            impl #de_impl_generics proc_tools_core::serde::Deserialize<'de> for #name #ty_generics #where_clause {
+                fn deserialize<D: proc_tools_core::serde::Deserializer<'de>>(deserializer: D) -> ::core::result::Result<Self, D::Error> {
+                    #helper::deserialize(deserializer)
+                }
+            }
+        }
+    });
+    let decode_into = in_place.map(|in_place| {
+        quote! {
+            fn decode_into(bytes: &[u8], out: &mut ::core::mem::MaybeUninit<Self>) -> ::core::result::Result<(), proc_tools_core::DecodeError> {
+                #len_check
+                let out = out.as_mut_ptr();
+                let mut pos = 0;
+                #in_place
+                let _ = pos;
+                ::core::result::Result::Ok(())
+            }
+        }
+    });
+    let frame = struct_options.frame.as_ref().map(|frame| frame_code(input, frame, struct_options.endian, &zeroed));
+    // `no_std` 下 `read_from` 不存在于特征中，使用默认实现（启用 `std` 时）
+    let read_from = match cfg!(feature = "no_std") {
+        true => quote! {},
+        false => quote! {
+            fn read_from<R: ::std::io::Read + ?Sized>(reader: &mut R) -> ::core::result::Result<Self, ::std::io::Error> {
+                #read_from
+            }
+        },
     };
+    Ok(quote! {
+        impl #impl_generics proc_tools_core::ByteEncode for #name #ty_generics #where_clause {
+            const SIZE: usize = 0 #(+ #field_sizes)*;
+
+            type Bytes = #bytes_ty;
+
+            fn to_bytes(&self) -> Self::Bytes {
+                let mut buffer = #zeroed;
+                // 数组长度恰好为 `SIZE`，不会失败
+                let _ = proc_tools_core::ByteEncode::write_to(self, &mut buffer);
+                buffer
+            }
+
+            fn write_to(&self, buf: &mut [u8]) -> ::core::result::Result<usize, proc_tools_core::EncodeError> {
+                let got = buf.len();
+                let buffer = match buf.get_mut(..#size) {
+                    ::core::option::Option::Some(buffer) => buffer,
+                    ::core::option::Option::None => {
+                        return ::core::result::Result::Err(proc_tools_core::EncodeError::BufferTooSmall { needed: #size, got });
+                    }
+                };
+                let mut pos = 0;
+                #(#field_ser)*
+                let _ = (pos, buffer);
+                ::core::result::Result::Ok(#size)
+            }
+
+            #read_from
 
-    TokenStream::from(expanded)
+            #decode_into
+
+            fn from_bytes(bytes: &[u8]) -> ::core::result::Result<Self, proc_tools_core::DecodeError> {
+                #len_check
+                let mut pos = 0;
+                let value = #value;
+                let _ = pos;
+                ::core::result::Result::Ok(value)
+            }
+        }
+
+        #from_array
+        #view
+        #layout
+        #describe
+        #debug_hex
+        #serde
+        #frame
+    })
 }
 
-/// 辅助函数：获取类型的大小
-fn get_type_size(ty: &Type) -> usize {
-    match ty {
-        Type::Array(array) => {
-            if let Expr::Lit(expr_lit) = &array.len {
-                if let Lit::Int(lit_int) = &expr_lit.lit {
-                    if let Ok(size) = lit_int.base10_parse::<usize>() {
-                        return size;
+/// `#[byte_encode(frame(...))]` 生成的带帧头编码和解码方法，帧头为可选的魔数和按类型字节序编码的长度
+fn frame_code(input: &DeriveInput, frame: &FrameOptions, endian: Endian, zeroed: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let (name, vis) = (&input.ident, &input.vis);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let size = quote! { <Self as proc_tools_core::ByteEncode>::SIZE };
+    let len_ty = &frame.len;
+    let header = match len_ty.to_string().as_str() {
+        "u16" => quote! { 2 },
+        _ => quote! { 4 },
+    };
+    let (to_bytes, from_bytes) = endian.methods();
+    let magic = frame.magic.as_ref();
+    let magic_len = magic.map_or(0, |magic| magic.value().len());
+    let too_large = lang_tr!(
+        cn = format!("`{}` 的 `SIZE` 超出帧头长度 `{}` 的范围", name, len_ty),
+        en = format!("the `SIZE` of `{}` exceeds the range of the frame length `{}`", name, len_ty)
+    );
+    let write_magic = magic.map(|magic| quote! { out.extend_from_slice(#magic); });
+    let strip_magic = magic.map(|magic| {
+        quote! {
+            let bytes = match bytes.strip_prefix(#magic) {
+                ::core::option::Option::Some(bytes) => bytes,
+                ::core::option::Option::None => return ::core::result::Result::Err(proc_tools_core::DecodeError::InvalidMagic),
+            };
+        }
+    });
+    let read_magic = magic.map(|magic| {
+        quote! {
+            let mut magic = [0u8; #magic_len];
+            ::std::io::Read::read_exact(reader, &mut magic)?;
+            if magic != *#magic {
+                return ::core::result::Result::Err(::core::convert::Into::into(proc_tools_core::DecodeError::InvalidMagic));
+            }
+        }
+    });
+    let from_framed = (!cfg!(feature = "no_std")).then(|| {
+        quote! {
+            /// 从 `reader` 读取一帧并解码，先校验魔数，长度超过 `SIZE` 时不读取内容
+            #vis fn from_framed<R: ::std::io::Read + ?Sized>(reader: &mut R) -> ::core::result::Result<Self, ::std::io::Error> {
+                #read_magic
+                let mut header = [0u8; #header];
+                ::std::io::Read::read_exact(reader, &mut header)?;
+                let len = <#len_ty>::#from_bytes(header) as usize;
+                if len > #size {
+                    let err = proc_tools_core::DecodeError::UnexpectedLength { expected: #size, got: len };
+                    return ::core::result::Result::Err(::core::convert::Into::into(err));
+                }
+                let mut buffer = #zeroed;
+                ::std::io::Read::read_exact(reader, &mut buffer[..len])?;
+                ::core::result::Result::Ok(<Self as proc_tools_core::ByteEncode>::from_bytes(&buffer[..len])?)
+            }
+        }
+    });
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// 编码为一帧：魔数、内容的长度、`to_bytes()` 的结果
+            #vis fn to_framed_bytes(&self) -> proc_tools_core::alloc::vec::Vec<u8> {
+                const { assert!(#size <= <#len_ty>::MAX as usize, #too_large) };
+                let mut out = proc_tools_core::alloc::vec::Vec::with_capacity(#magic_len + #header + #size);
+                #write_magic
+                out.extend_from_slice(&(#size as #len_ty).#to_bytes());
+                out.extend_from_slice(::core::convert::AsRef::<[u8]>::as_ref(&proc_tools_core::ByteEncode::to_bytes(self)));
+                out
+            }
+
+            /// 从恰好一帧的字节解码，校验魔数，长度字段须与内容的长度一致
+            #vis fn from_framed_bytes(bytes: &[u8]) -> ::core::result::Result<Self, proc_tools_core::DecodeError> {
+                let total = bytes.len();
+                #strip_magic
+                if bytes.len() < #header {
+                    return ::core::result::Result::Err(proc_tools_core::DecodeError::UnexpectedLength { expected: #magic_len + #header, got: total });
+                }
+                let (header, payload) = bytes.split_at(#header);
+                let mut arr = [0u8; #header];
+                arr.copy_from_slice(header);
+                let len = <#len_ty>::#from_bytes(arr) as usize;
+                if payload.len() != len {
+                    return ::core::result::Result::Err(proc_tools_core::DecodeError::UnexpectedLength { expected: #magic_len + #header + len, got: total });
+                }
+                <Self as proc_tools_core::ByteEncode>::from_bytes(payload)
+            }
+
+            #from_framed
+        }
+    }
+}
+
+/// 生成按需解码字段的视图类型 `类型名View<'a>`，借用恰好 `SIZE` 个字节
+fn view_code(input: &DeriveInput, accessors: &[proc_macro2::TokenStream]) -> proc_macro2::TokenStream {
+    let (name, vis) = (&input.ident, &input.vis);
+    let view = format_ident!("{}View", name);
+    let size = quote! { <#name as proc_tools_core::ByteEncode>::SIZE };
+    let view_doc = format!("[`{}`] 编码后字节的只读视图，各字段的方法只解码对应的字节", name);
+    let decode_doc = format!("解码出完整的 [`{}`]，同 `from_bytes`", name);
+    quote! {
+        #[doc = #view_doc]
+        #[derive(Clone, Copy)]
+        #vis struct #view<'a> {
+            bytes: &'a [u8; #size],
+        }
+
+        impl<'a> #view<'a> {
+            /// 从字节切片创建视图，长度必须恰好为 `SIZE`，不解码任何字段
+            #vis fn new(bytes: &'a [u8]) -> ::core::result::Result<Self, proc_tools_core::DecodeError> {
+                match <&'a [u8; #size]>::try_from(bytes) {
+                    ::core::result::Result::Ok(bytes) => ::core::result::Result::Ok(Self { bytes }),
+                    ::core::result::Result::Err(_) => {
+                        ::core::result::Result::Err(proc_tools_core::DecodeError::UnexpectedLength { expected: #size, got: bytes.len() })
                     }
                 }
             }
-            panic!(lang_tr!(cn = "无法获取数组大小", en = "Unable to determine array size"));
-        }
-        Type::Path(type_path) => {
-            let seg = type_path.path.segments.last().unwrap();
-            match seg.ident.to_string().as_str() {
-                "u8" => 1,
-                "u16" => 2,
-                "u32" => 4,
-                "u64" => 8,
-                "u128" => 16,
-                "i8" => 1,
-                "i16" => 2,
-                "i32" => 4,
-                "i64" => 8,
-                "i128" => 16,
-                "f32" => 4,
-                "f64" => 8,
+
+            /// 从字节数组创建视图
+            #[inline]
+            #vis const fn from_array(bytes: &'a [u8; #size]) -> Self {
+                Self { bytes }
+            }
+
+            /// 底层的字节
+            #[inline]
+            #vis const fn as_bytes(&self) -> &'a [u8; #size] {
+                self.bytes
+            }
+
+            #[doc = #decode_doc]
+            #vis fn decode(&self) -> ::core::result::Result<#name, proc_tools_core::DecodeError> {
+                <#name as proc_tools_core::ByteEncode>::from_bytes(self.bytes)
+            }
+
+            #(#accessors)*
+        }
+    }
+}
+
+/// 编码代码的组成部分
+struct Body {
+    /// 各部分的字节数
+    sizes: Vec<proc_macro2::TokenStream>,
+    /// 写入 `buffer` 的语句
+    ser: Vec<proc_macro2::TokenStream>,
+    /// 从 `bytes` 解码出 `Self` 的表达式
+    value: proc_macro2::TokenStream,
+    /// 视图类型中按需解码各字段的方法
+    accessors: Vec<proc_macro2::TokenStream>,
+    /// 当前版本号，及按开头的 `version` 计算该版本编码后字节数的表达式
+    version: Option<(LitInt, proc_macro2::TokenStream)>,
+    /// 校验内存布局与编码结果一致的常量断言
+    layout: Vec<proc_macro2::TokenStream>,
+    /// 各字段的编码位置，用于生成 `LAYOUT` 和 `OFFSET_字段名` 常量
+    field_layout: Vec<LayoutEntry>,
+    /// 带 `in_place` 时逐个字段写入 `out` 的语句，`out` 为 `*mut Self`
+    in_place: Option<proc_macro2::TokenStream>,
+    /// 解码是否可能失败，不会失败时额外生成 `from_array`
+    fallible: bool,
+}
+
+/// 结构体的编码代码
+/// - 带 `#[byte_encode(skip)]` 的字段不参与编码，`#[byte_encode(pad = N)]` 在字段之前插入 N 个保留字节
+/// - 带 `#[byte_encode(checksum = "crc32")]` 的字段写入之前所有字节的校验和
+/// - 连续的 `#[byte_encode(bits = N)]` 字段按位拼接在一起编码
+fn struct_body(input: &DeriveInput, fields: &Fields, struct_options: &StructOptions) -> syn::Result<Body> {
+    let (name, vis) = (&input.ident, &input.vis);
+    let fields = match fields {
+        Fields::Named(fields) => &fields.named,
+        Fields::Unnamed(fields) => &fields.unnamed,
+        fields => {
+            let msg = lang_tr!(
+                cn = "字段类型不支持，仅支持具有命名字段的结构体和元组结构体",
+                en = "Only structs with named fields and tuple structs are supported"
+            );
+            return Err(diagnostics::error_spanned("PT0109", fields, msg));
+        }
+    };
+    if let Some(verify_layout) = &struct_options.verify_layout {
+        if !is_repr_c_packed(&input.attrs) {
+            let msg = lang_tr!(
+                cn = "`verify_layout` 需要结构体带有 `#[repr(C, packed)]`",
+                en = "`verify_layout` requires `#[repr(C, packed)]` on the struct"
+            );
+            return Err(diagnostics::error_spanned("PT0110", verify_layout, msg));
+        }
+        if let Some(version) = &struct_options.version {
+            let msg = lang_tr!(cn = "`verify_layout` 不能与 `version` 一起使用", en = "`verify_layout` cannot be combined with `version`");
+            return Err(diagnostics::error_spanned("PT0111", version, msg));
+        }
+    }
+
+    // 在编译时计算结构体总大小，嵌套结构体的大小为其 `SIZE` 常量
+    let mut field_sizes = Vec::new();
+    let mut field_ser = Vec::new();
+    let mut field_deser = Vec::new();
+    let mut accessors = Vec::new();
+    let mut layout = Vec::new();
+    let mut field_layout = Vec::new();
+    let mut fallible = struct_options.version.is_some();
+    // 带版本号的结构体在开头写入版本号，`version_sizes` 为按解码出的 `version` 计算的各部分字节数
+    let mut version_sizes = Vec::new();
+    if let Some(version) = &struct_options.version {
+        field_sizes.push(quote! { 1 });
+        field_ser.push(quote! {
+            buffer[pos] = #version;
+            pos += 1;
+        });
+        version_sizes.push(quote! { 1 });
+    }
+    let mut fields_iter = fields.iter().enumerate().peekable();
+    while let Some((index, field)) = fields_iter.next() {
+        let options = FieldOptions::parse(&field.attrs)?;
+        // 连续的位字段组成一组，共同占用整数个字节
+        if let Some(first) = BitField::new(index, field, &options, struct_options, true)? {
+            let mut group = vec![first];
+            while let Some(&(index, field)) = fields_iter.peek() {
+                match BitField::new(index, field, &FieldOptions::parse(&field.attrs)?, struct_options, false)? {
+                    Some(bit_field) => group.push(bit_field),
+                    None => break,
+                }
+                fields_iter.next();
+            }
+            let skip_pad = group[0].pad.as_ref().map(|pad| {
+                field_sizes.push(quote! { (#pad) });
+                version_sizes.push(quote! { (#pad) });
+                field_ser.push(quote! {
+                    buffer[pos..pos + (#pad)].fill(0);
+                    pos += #pad;
+                });
+                quote! { pos += #pad; }
+            });
+            let BitGroup { size, ser, fields } = bit_group(&group, struct_options.endian)?;
+            for (i, (bit_field, deser, codec)) in fields.into_iter().enumerate() {
+                accessors.push(accessor(vis, &bit_field.member, &bit_field.ty, &field_sizes, &codec, false));
+                // 同一组的位字段共用所在的字节
+                field_layout.push(layout_entry(&bit_field.member, &bit_field.ty, &field_sizes, &size));
+                let member = &bit_field.member;
+                let skip_pad = skip_pad.as_ref().filter(|_| i == 0);
+                let deser = match &bit_field.assert {
+                    Some(assert) => {
+                        fallible = true;
+                        assert_check(member, assert, deser)
+                    }
+                    None => deser,
+                };
+                field_deser.push((member.clone(), quote! {{ #skip_pad #deser }}));
+            }
+            field_sizes.push(size.clone());
+            version_sizes.push(size);
+            field_ser.push(ser);
+            continue;
+        }
+        let since = match (&options.since, &struct_options.version) {
+            (Some(since), Some(version)) if since.base10_parse::<u8>()? <= version.base10_parse::<u8>()? => Some(since),
+            (Some(since), Some(_)) => {
+                let msg = lang_tr!(cn = "`since` 不能大于结构体的 `version`", en = "`since` must not exceed the struct's `version`");
+                return Err(diagnostics::error_spanned("PT0112", since, msg));
+            }
+            (Some(since), None) => {
+                let msg = lang_tr!(
+                    cn = "`since` 需要结构体上的 `#[byte_encode(version = N)]`",
+                    en = "`since` requires `#[byte_encode(version = N)]` on the struct"
+                );
+                return Err(diagnostics::error_spanned("PT0113", since, msg));
+            }
+            (None, _) => None,
+        };
+        if let (Some(default), None) = (&options.default, since) {
+            let msg = lang_tr!(cn = "`default` 需要与 `since` 一起使用", en = "`default` requires `since`");
+            return Err(diagnostics::error_spanned("PT0114", default, msg));
+        }
+        if struct_options.verify_layout.is_some() && (options.skip || options.pad.is_some() || options.checksum.is_some()) {
+            let msg = lang_tr!(
+                cn = "`verify_layout` 的结构体字段不能使用 `skip`、`pad`、`checksum`，它们使内存布局与编码结果不同",
+                en = "Fields of a `verify_layout` struct cannot use `skip`, `pad` or `checksum`, which make the memory layout differ from the encoding"
+            );
+            return Err(diagnostics::error_spanned("PT0115", field, msg));
+        }
+        let start = field_sizes.len();
+        let field_name = member(index, field);
+        // 保留区域写入 0，解码时跳过，不检查其内容
+        let skip_pad = options.pad.as_ref().map(|pad| {
+            field_sizes.push(quote! { (#pad) });
+            field_ser.push(quote! {
+                buffer[pos..pos + (#pad)].fill(0);
+                pos += #pad;
+            });
+            quote! { pos += #pad; }
+        });
+        let ctx = FieldCtx {
+            label: match &field_name {
+                syn::Member::Named(ident) => ident.to_string(),
+                syn::Member::Unnamed(index) => index.index.to_string(),
+            },
+            endian: options.endian.unwrap_or(struct_options.endian),
+            endian_span: options.endian_span,
+            as_ty: options.as_ty.clone(),
+        };
+        if let Some(as_ty) = &options.as_ty {
+            if !is_as_target(&field.ty) {
+                let msg = lang_tr!(
+                    cn = "`as` 只能用于 `usize`、`isize`、`f32` 及其数组字段",
+                    en = "`as` can only be used on `usize`, `isize`, `f32` fields or arrays of them"
+                );
+                return Err(diagnostics::error_spanned("PT0116", as_ty, msg));
+            }
+            if options.skip || options.with.is_some() || options.width.is_some() {
+                let msg = lang_tr!(cn = "`as` 不能与 `skip`、`with`、`width` 一起使用", en = "`as` cannot be combined with `skip`, `with` or `width`");
+                return Err(diagnostics::error_spanned("PT0117", as_ty, msg));
+            }
+        }
+        if let (Some(width), true) = (&options.width, options.skip || options.checksum.is_some()) {
+            let msg = lang_tr!(cn = "`width` 不能与 `skip`、`checksum` 一起使用", en = "`width` cannot be combined with `skip` or `checksum`");
+            return Err(diagnostics::error_spanned("PT0118", width, msg));
+        }
+        if let (Some(with), true) = (&options.with, options.skip || options.checksum.is_some()) {
+            let msg = lang_tr!(cn = "`with` 不能与 `skip`、`checksum` 一起使用", en = "`with` cannot be combined with `skip` or `checksum`");
+            return Err(diagnostics::error_spanned("PT0119", with, msg));
+        }
+        if let (Some(len), true) = (&options.fixed_str, options.skip || options.checksum.is_some() || struct_options.verify_layout.is_some()) {
+            let msg = lang_tr!(
+                cn = "`fixed_str` 不能与 `skip`、`checksum`、`verify_layout` 一起使用",
+                en = "`fixed_str` cannot be combined with `skip`, `checksum` or `verify_layout`"
+            );
+            return Err(diagnostics::error_spanned("PT0120", len, msg));
+        }
+        if let (Some((_, assert)), true) = (&options.assert, options.skip) {
+            let msg = lang_tr!(cn = "`assert` 不能与 `skip` 一起使用", en = "`assert` cannot be combined with `skip`");
+            return Err(diagnostics::error_spanned("PT0121", assert, msg));
+        }
+        let field_value = if options.skip {
+            quote! {{ #skip_pad ::core::default::Default::default() }}
+        } else if let Some((checksum, checksum_lit)) = &options.checksum {
+            // 校验和字段编码时忽略字段的值，改为写入之前所有字节的校验和，解码时校验
+            let (func, expected_ty) = checksum.func();
+            if !matches!(&field.ty, Type::Path(type_path) if type_path.path.is_ident(expected_ty)) {
+                let msg = lang_tr!(
+                    cn = format!("校验和 `{}` 的字段类型必须为 `{}`", checksum_lit.value(), expected_ty),
+                    en = format!("The field type of checksum `{}` must be `{}`", checksum_lit.value(), expected_ty)
+                );
+                return Err(diagnostics::error_spanned("PT0122", &field.ty, msg));
+            }
+            let codec = ctx.codec(&field.ty, quote! { checksum })?;
+            fallible = true;
+            accessors.push(accessor(vis, &field_name, &field.ty, &field_sizes, &codec, true));
+            field_layout.push(layout_entry(&field_name, &field.ty, &field_sizes, &codec.size));
+            let (ser, deser) = (codec.ser, codec.deser);
+            let label = &ctx.label;
+            field_sizes.push(codec.size);
+            field_ser.push(quote! {{
+                let checksum = proc_tools_core::utils_core::checksum::#func(&buffer[..pos]);
+                #ser
+            }});
+            quote! {{
+                #skip_pad
+                let checksum = proc_tools_core::utils_core::checksum::#func(&bytes[..pos]);
+                let value = #deser;
+                if value != checksum {
+                    return ::core::result::Result::Err(proc_tools_core::DecodeError::ChecksumMismatch {
+                        field: #label,
+                        stored: value as u32,
+                        computed: checksum as u32,
+                    });
+                }
+                value
+            }}
+        } else {
+            let codec = match (&options.with, &options.width, &options.fixed_str) {
+                (Some(with), None, None) => ctx.with_codec(&field_name, with, struct_options)?,
+                (None, Some(width), None) => ctx.width_codec(&field.ty, quote! { self.#field_name }, width)?,
+                (None, None, Some(len)) => ctx.fixed_str_codec(&field.ty, quote! { self.#field_name }, len)?,
+                (None, None, None) => ctx.codec(&field.ty, quote! { self.#field_name })?,
                 _ => {
                     let msg = lang_tr!(
-                        cn = format!("不支持的类型: {}", seg.ident),
-                        en = format!("Unsupported type: {}", seg.ident)
+                        cn = "`with`、`width`、`fixed_str` 只能使用其中一个",
+                        en = "Only one of `with`, `width` and `fixed_str` can be used"
                     );
-                    panic!("{}", msg)
+                    return Err(diagnostics::error_spanned("PT0123", field, msg));
                 }
+            };
+            fallible |= codec.fallible;
+            accessors.push(accessor(vis, &field_name, &field.ty, &field_sizes, &codec, false));
+            field_layout.push(layout_entry(&field_name, &field.ty, &field_sizes, &codec.size));
+            if struct_options.verify_layout.is_some() {
+                layout.push(layout_check(name, &field_name, &field.ty, &field_sizes, &codec, &ctx));
+            }
+            let deser = codec.deser;
+            field_sizes.push(codec.size);
+            field_ser.push(codec.ser);
+            quote! {{ #skip_pad #deser }}
+        };
+        let field_value = match &options.assert {
+            Some(assert) => {
+                fallible = true;
+                assert_check(&field_name, assert, field_value)
             }
+            None => field_value,
+        };
+        // 较新版本加入的字段在旧版本的数据中不存在，解码旧版本时使用默认值
+        match since {
+            Some(since) => {
+                let default = match &options.default {
+                    Some(default) => quote! { #default },
+                    None => quote! { ::core::default::Default::default() },
+                };
+                version_sizes.extend(field_sizes[start..].iter().map(|size| quote! { (if version >= #since { #size } else { 0 }) }));
+                field_deser.push((field_name, quote! { if version >= #since #field_value else { #default } }));
+            }
+            None => {
+                version_sizes.extend(field_sizes[start..].iter().cloned());
+                field_deser.push((field_name, field_value));
+            }
+        }
+    }
+    let (members, exprs): (Vec<_>, Vec<_>) = field_deser.into_iter().unzip();
+    let skip_version = struct_options.version.as_ref().map(|_| quote! { pos += 1; });
+    let value = quote! {{
+        #skip_version
+        Self { #(#members: #exprs),* }
+    }};
+    // 逐个字段写入 `out` 指向的位置，不在栈上构造完整的结构体
+    let in_place = struct_options.in_place.then(|| {
+        let write = match is_repr_packed(&input.attrs) {
+            true => quote! { write_unaligned },
+            false => quote! { write },
+        };
+        quote! {
+            #skip_version
+            #(
+                let value = #exprs;
+                // SAFETY: `out` 指向有效的 `Self` 存储，`addr_of_mut!` 不创建对未初始化内存的引用，
+                // packed 结构体的字段用非对齐写入
+                unsafe { ::core::ptr::addr_of_mut!((*out).#members).#write(value) };
+            )*
+        }
+    });
+    let version = struct_options.version.as_ref().map(|version| (version.clone(), quote! { 0 #(+ #version_sizes)* }));
+    Ok(Body { sizes: field_sizes, ser: field_ser, value, accessors, version, layout, field_layout, in_place, fallible })
+}
+
+/// 解码出字段的值后检查 `#[byte_encode(assert = "...")]` 的条件，不满足时返回 `DecodeError::InvalidValue`
+/// - 条件中以字段名（元组结构体为 `value`）引用字段的值
+fn assert_check(member: &syn::Member, (cond, lit): &(syn::Expr, syn::LitStr), deser: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let (binding, label) = match member {
+        syn::Member::Named(ident) => (ident.clone(), ident.unraw().to_string()),
+        syn::Member::Unnamed(index) => (format_ident!("value"), index.index.to_string()),
+    };
+    let reason = format!("assertion failed: {}", lit.value());
+    quote! {{
+        let #binding = #deser;
+        if !(#cond) {
+            return ::core::result::Result::Err(proc_tools_core::DecodeError::InvalidValue { field: #label, reason: #reason });
+        }
+        #binding
+    }}
+}
+
+/// 字段的编码位置
+struct LayoutEntry {
+    /// 字段的访问方式
+    member: syn::Member,
+    /// 字段类型
+    ty: Type,
+    /// 字段名，元组结构体为下标
+    label: String,
+    /// 字段之前的字节数
+    offset: proc_macro2::TokenStream,
+    /// 字段编码后的字节数
+    size: proc_macro2::TokenStream,
+}
+
+/// `preceding` 为字段之前各部分的字节数
+fn layout_entry(member: &syn::Member, ty: &Type, preceding: &[proc_macro2::TokenStream], size: &proc_macro2::TokenStream) -> LayoutEntry {
+    let label = match member {
+        syn::Member::Named(ident) => ident.unraw().to_string(),
+        syn::Member::Unnamed(index) => index.index.to_string(),
+    };
+    LayoutEntry { member: member.clone(), ty: ty.clone(), label, offset: quote! { 0 #(+ #preceding)* }, size: size.clone() }
+}
+
+/// 校验字段在内存中的偏移、大小、字节序与编码结果一致的常量断言，`preceding` 为字段之前各部分的字节数
+/// - 嵌套类型的字段要求其自身也带有 `verify_layout`，即有生成的 `as_bytes` 方法
+fn layout_check(
+    name: &syn::Ident,
+    member: &syn::Member,
+    ty: &Type,
+    preceding: &[proc_macro2::TokenStream],
+    codec: &Codec,
+    ctx: &FieldCtx,
+) -> proc_macro2::TokenStream {
+    let size = &codec.size;
+    let offset_msg = lang_tr!(
+        cn = format!("字段 `{}` 在内存中的偏移与编码位置不一致", ctx.label),
+        en = format!("the memory offset of field `{}` differs from its encoded position", ctx.label)
+    );
+    let size_msg = lang_tr!(
+        cn = format!("字段 `{}` 在内存中的大小与编码后的字节数不一致", ctx.label),
+        en = format!("the memory size of field `{}` differs from its encoded size", ctx.label)
+    );
+    let endian_check = codec.endian_dependent.then(|| {
+        let target = match ctx.endian {
+            Endian::Little => "little",
+            Endian::Big => "big",
+        };
+        let endian_msg = lang_tr!(
+            cn = format!("字段 `{}` 的字节序与目标平台不一致", ctx.label),
+            en = format!("the endianness of field `{}` differs from the target", ctx.label)
+        );
+        quote! { assert!(cfg!(target_endian = #target), #endian_msg); }
+    });
+    // 只有嵌套类型没有零值
+    let nested_check = codec.zero.is_none().then(|| quote! { let _ = <#ty>::as_bytes; });
+    quote! {
+        assert!(::core::mem::offset_of!(#name, #member) == 0 #(+ #preceding)*, #offset_msg);
+        assert!(::core::mem::size_of::<#ty>() == #size, #size_msg);
+        #endian_check
+        #nested_check
+    }
+}
+
+/// 结构体是否带有 `#[repr(C, packed)]`，`packed(1)` 与 `packed` 相同
+fn is_repr_c_packed(attrs: &[syn::Attribute]) -> bool {
+    let (mut c, mut packed) = (false, false);
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("repr")) {
+        let _ = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("C") {
+                c = true;
+            } else if meta.path.is_ident("packed") {
+                packed = match meta.input.peek(syn::token::Paren) {
+                    true => {
+                        let content;
+                        syn::parenthesized!(content in meta.input);
+                        content.parse::<LitInt>()?.base10_parse::<u32>()? == 1
+                    }
+                    false => true,
+                };
+            }
+            Ok(())
+        });
+    }
+    c && packed
+}
+
+/// 结构体是否带有 `#[repr(packed)]` 或 `#[repr(packed(N))]`，此时字段可能未对齐
+fn is_repr_packed(attrs: &[syn::Attribute]) -> bool {
+    let mut packed = false;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("repr")) {
+        let _ = attr.parse_nested_meta(|meta| {
+            packed |= meta.path.is_ident("packed");
+            if meta.input.peek(syn::token::Paren) {
+                let _content;
+                syn::parenthesized!(_content in meta.input);
+            }
+            Ok(())
+        });
+    }
+    packed
+}
+
+/// 字段的访问方式，元组结构体的字段以下标访问，结构体字面量 `Self { 0: value }` 同样适用
+fn member(index: usize, field: &syn::Field) -> syn::Member {
+    match &field.ident {
+        Some(ident) => syn::Member::Named(ident.clone()),
+        None => syn::Member::Unnamed(syn::Index { index: index as u32, span: field.ty.span() }),
+    }
+}
+
+/// 带 `#[byte_encode(bits = N)]` 的位字段
+struct BitField {
+    member: syn::Member,
+    ty: Type,
+    /// 占用的位数
+    bits: u32,
+    /// 一组中第一个字段之前的保留字节数
+    pad: Option<syn::Expr>,
+    /// 解码时检查的条件
+    assert: Option<(syn::Expr, syn::LitStr)>,
+}
+
+impl BitField {
+    /// 检查位字段的类型和选项，不是位字段时返回 `None`
+    /// - `first` 为是否为一组中的第一个字段，只有第一个字段可以带 `pad`
+    fn new(index: usize, field: &syn::Field, options: &FieldOptions, struct_options: &StructOptions, first: bool) -> syn::Result<Option<Self>> {
+        let Some(bits_lit) = &options.bits else {
+            return Ok(None);
+        };
+        let bits = bits_lit.base10_parse::<u32>()?;
+        let width = match &field.ty {
+            Type::Path(type_path) => match type_path.path.get_ident().map(|ident| ident.to_string()).as_deref() {
+                Some("bool") => Some(1),
+                Some(ident @ ("u8" | "u16" | "u32" | "u64" | "u128")) => get_number_size(ident).map(|size| size as u32 * 8),
+                _ => None,
+            },
+            _ => None,
+        };
+        let Some(width) = width else {
+            let msg = lang_tr!(cn = "位字段的类型只能为无符号整数或 `bool`", en = "Bit fields must be unsigned integers or `bool`");
+            return Err(diagnostics::error_spanned("PT0124", &field.ty, msg));
+        };
+        if bits == 0 || bits > width {
+            let msg = lang_tr!(
+                cn = format!("位数必须在 1 到 {} 之间", width),
+                en = format!("The number of bits must be between 1 and {}", width)
+            );
+            return Err(diagnostics::error_spanned("PT0125", bits_lit, msg));
+        }
+        let conflict = if options.skip {
+            Some("skip")
+        } else if options.checksum.is_some() {
+            Some("checksum")
+        } else if options.with.is_some() {
+            Some("with")
+        } else if options.width.is_some() {
+            Some("width")
+        } else if options.since.is_some() {
+            Some("since")
+        } else if options.endian.is_some() {
+            Some("big_endian/little_endian")
+        } else if options.pad.is_some() && !first {
+            Some("pad")
+        } else if struct_options.verify_layout.is_some() {
+            Some("verify_layout")
+        } else {
+            None
+        };
+        if let Some(_conflict) = conflict {
+            let msg = lang_tr!(cn = format!("位字段不能使用 `{}`", _conflict), en = format!("Bit fields cannot use `{}`", _conflict));
+            return Err(diagnostics::error_spanned("PT0126", field, msg));
+        }
+        Ok(Some(BitField { member: member(index, field), ty: field.ty.clone(), bits, pad: options.pad.clone(), assert: options.assert.clone() }))
+    }
+}
+
+/// 一组位字段的编码代码
+struct BitGroup<'a> {
+    /// 整组的字节数
+    size: proc_macro2::TokenStream,
+    /// 写入 `buffer` 的语句
+    ser: proc_macro2::TokenStream,
+    /// 各字段、在结构体字面量中的解码表达式、视图方法使用的编码代码
+    fields: Vec<(&'a BitField, proc_macro2::TokenStream, Codec)>,
+}
+
+/// 一组位字段的编码代码
+/// - 第一个字段占用最高位，依次向低位排列，整组按结构体的字节序写入
+/// - 编码时只保留每个字段的低 N 位，与 C 语言的位域相同
+fn bit_group(group: &[BitField], endian: Endian) -> syn::Result<BitGroup<'_>> {
+    let total: u32 = group.iter().map(|bit_field| bit_field.bits).sum();
+    if total % 8 != 0 || total > 128 {
+        let last = &group[group.len() - 1];
+        let msg = lang_tr!(
+            cn = format!("连续位字段的总位数为 {}，必须是 8 的倍数且不超过 128", total),
+            en = format!("Consecutive bit fields total {} bits, which must be a multiple of 8 and at most 128", total)
+        );
+        return Err(diagnostics::error_spanned("PT0127", &last.ty, msg));
+    }
+    let len = proc_macro2::Literal::usize_unsuffixed((total / 8) as usize);
+    let (to_bytes, from_bytes) = endian.methods();
+    // 整组在 u128 中拼接，按字节序取出有效的字节
+    let (write, read) = match endian {
+        Endian::Big => (quote! { &acc.#to_bytes()[16 - #len..] }, quote! { arr[16 - #len..] }),
+        Endian::Little => (quote! { &acc.#to_bytes()[..#len] }, quote! { arr[..#len] }),
+    };
+    let mut shift = total;
+    let mut parts = Vec::new();
+    let mut fields = Vec::new();
+    for (i, bit_field) in group.iter().enumerate() {
+        shift -= bit_field.bits;
+        let member = &bit_field.member;
+        let ty = &bit_field.ty;
+        let mask = proc_macro2::Literal::u128_suffixed(u128::MAX >> (128 - bit_field.bits));
+        let shift_lit = proc_macro2::Literal::u32_unsuffixed(shift);
+        parts.push(quote! { acc |= ((self.#member as u128) & #mask) << #shift_lit; });
+        let value = match matches!(ty, Type::Path(type_path) if type_path.path.is_ident("bool")) {
+            true => quote! { (acc >> #shift_lit) & #mask != 0 },
+            false => quote! { ((acc >> #shift_lit) & #mask) as #ty },
+        };
+        let deser = |advance: bool| {
+            let advance = advance.then(|| quote! { pos += #len; });
+            quote! {{
+                let mut arr = [0u8; 16];
+                #read.copy_from_slice(&bytes[pos..pos + #len]);
+                let acc = u128::#from_bytes(arr);
+                #advance
+                #value
+            }}
+        };
+        // 视图方法单独解码每个字段，总是推进 `pos`
+        let codec = Codec {
+            size: quote! { #len },
+            ser: proc_macro2::TokenStream::new(),
+            deser: deser(true),
+            zero: None,
+            fallible: false,
+            endian_dependent: total > 8,
+        };
+        // 最后一个字段解码后推进 `pos`
+        fields.push((bit_field, deser(i == group.len() - 1), codec));
+    }
+    let ser = quote! {{
+        let mut acc: u128 = 0;
+        #(#parts)*
+        buffer[pos..pos + #len].copy_from_slice(#write);
+        pos += #len;
+    }};
+    Ok(BitGroup { size: quote! { #len }, ser, fields })
+}
+
+/// 视图类型中解码单个字段的方法，`preceding` 为字段之前各部分的字节数
+/// - 方法与字段同名，元组结构体的字段为 `field_下标`
+/// - 解码不会失败的字段直接返回值，否则返回 `Result`
+fn accessor(
+    vis: &syn::Visibility,
+    member: &syn::Member,
+    ty: &Type,
+    preceding: &[proc_macro2::TokenStream],
+    codec: &Codec,
+    checksum: bool,
+) -> proc_macro2::TokenStream {
+    let deser = &codec.deser;
+    let (field_name, label) = match member {
+        syn::Member::Named(ident) => (ident.clone(), ident.to_string()),
+        syn::Member::Unnamed(index) => (format_ident!("field_{}", index.index), index.index.to_string()),
+    };
+    let doc = if checksum {
+        format!("解码 `{}` 字段，只读出存储的校验和，不校验", label)
+    } else {
+        format!("解码 `{}` 字段", label)
+    };
+    let (output, value) = if codec.fallible {
+        (quote! { ::core::result::Result<#ty, proc_tools_core::DecodeError> }, quote! { ::core::result::Result::Ok(value) })
+    } else {
+        (quote! { #ty }, quote! { value })
+    };
+    quote! {
+        #[doc = #doc]
+        #[inline]
+        #vis fn #field_name(&self) -> #output {
+            let bytes: &[u8] = self.bytes;
+            let mut pos = 0 #(+ #preceding)*;
+            let value = #deser;
+            let _ = pos;
+            #value
+        }
+    }
+}
+
+/// 无字段枚举的编码代码，编码为 `#[repr(u8)]` 等指定的整数类型的判别值，解码时未知的判别值返回错误
+fn enum_body(input: &DeriveInput, data: &syn::DataEnum, struct_options: &StructOptions) -> syn::Result<Body> {
+    let name = &input.ident;
+    let repr = enum_repr(&input.attrs).ok_or_else(|| {
+        let msg = lang_tr!(
+            cn = "枚举需要用 `#[repr(u8)]`、`#[repr(u16)]` 等指定判别值的整数类型",
+            en = "Enums require an integer representation such as `#[repr(u8)]` or `#[repr(u16)]`"
+        );
+        diagnostics::error_spanned("PT0128", name, msg)
+    })?;
+    if let Some(variant) = data.variants.iter().find(|variant| !matches!(variant.fields, Fields::Unit)) {
+        let msg = lang_tr!(cn = "仅支持没有字段的枚举变体", en = "Only fieldless enum variants are supported");
+        return Err(diagnostics::error_spanned("PT0129", variant, msg));
+    }
+    let variants: Vec<_> = data.variants.iter().map(|variant| &variant.ident).collect();
+    let ctx = FieldCtx { label: name.to_string(), endian: struct_options.endian, endian_span: None, as_ty: None };
+    let codec = ctx.codec(&syn::parse_quote! { #repr }, quote! { discriminant })?;
+    let (ser, deser) = (codec.ser, codec.deser);
+    let ty = name.to_string();
+    Ok(Body {
+        sizes: vec![codec.size],
+        ser: vec![quote! {
+            let discriminant: #repr = match self {
+                #(Self::#variants => #name::#variants as #repr,)*
+            };
+            #ser
+        }],
+        value: quote! {{
+            let discriminant: #repr = #deser;
+            #(if discriminant == #name::#variants as #repr {
+                #name::#variants
+            } else)* {
+                return ::core::result::Result::Err(proc_tools_core::DecodeError::InvalidEnumValue { ty: #ty, value: discriminant as i128 });
+            }
+        }},
+        accessors: Vec::new(),
+        version: None,
+        layout: Vec::new(),
+        field_layout: Vec::new(),
+        in_place: None,
+        fallible: true,
+    })
+}
+
+/// 变体带有数据的枚举的编码代码，每个变体最多一个字段，如 `Request(Request)`，字段类型与结构体字段相同
+/// - 编码为一个字节的标签和变体的内容，内容补零到最大变体的大小，因此 `SIZE` 固定
+/// - 标签为变体的判别值，未显式指定时与 Rust 的规则相同，从 0 开始依次加 1
+fn union_body(input: &DeriveInput, data: &syn::DataEnum, struct_options: &StructOptions) -> syn::Result<Body> {
+    let name = &input.ident;
+    let ty = name.to_string();
+    // 内容区域的大小，即 `SIZE` 减去标签
+    let payload_size = quote! { (<Self as proc_tools_core::ByteEncode>::SIZE - 1) };
+    let mut payload_sizes = Vec::new();
+    let (mut ser_arms, mut deser_arms) = (Vec::new(), Vec::new());
+    let mut tag = quote! { 0 };
+    for variant in &data.variants {
+        if let Some((_, discriminant)) = &variant.discriminant {
+            tag = quote! { #discriminant };
+        }
+        let ident = &variant.ident;
+        match &variant.fields {
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let ctx = FieldCtx { label: ident.to_string(), endian: struct_options.endian, endian_span: None, as_ty: None };
+                let codec = ctx.codec(&fields.unnamed[0].ty, quote! { *payload })?;
+                let (size, ser, deser) = (codec.size, codec.ser, codec.deser);
+                ser_arms.push(quote! {
+                    Self::#ident(payload) => {
+                        buffer[pos] = #tag;
+                        pos += 1;
+                        let start = pos;
+                        #ser
+                        buffer[pos..start + #payload_size].fill(0);
+                        pos = start + #payload_size;
+                    }
+                });
+                deser_arms.push(quote! {
+                    if tag == (#tag) {
+                        let start = pos;
+                        let value = Self::#ident(#deser);
+                        pos = start + #payload_size;
+                        value
+                    }
+                });
+                payload_sizes.push(size);
+            }
+            Fields::Unit => {
+                ser_arms.push(quote! {
+                    Self::#ident => {
+                        buffer[pos] = #tag;
+                        pos += 1;
+                        buffer[pos..pos + #payload_size].fill(0);
+                        pos += #payload_size;
+                    }
+                });
+                deser_arms.push(quote! {
+                    if tag == (#tag) {
+                        pos += #payload_size;
+                        Self::#ident
+                    }
+                });
+            }
+            fields => {
+                let msg = lang_tr!(
+                    cn = "枚举变体只能没有字段，或只有一个元组字段",
+                    en = "Enum variants must be fieldless or have a single tuple field"
+                );
+                return Err(diagnostics::error_spanned("PT0130", fields, msg));
+            }
+        }
+        tag = quote! { (#tag) + 1 };
+    }
+    Ok(Body {
+        // 常量表达式中取各变体大小的最大值
+        sizes: vec![quote! { 1 }, quote! {{
+            let mut max = 0;
+            #(if #payload_sizes > max {
+                max = #payload_sizes;
+            })*
+            max
+        }}],
+        ser: vec![quote! {
+            match self {
+                #(#ser_arms)*
+            }
+        }],
+        value: quote! {{
+            let tag: u8 = bytes[pos];
+            pos += 1;
+            #(#deser_arms else)* {
+                return ::core::result::Result::Err(proc_tools_core::DecodeError::InvalidEnumValue { ty: #ty, value: tag as i128 });
+            }
+        }},
+        accessors: Vec::new(),
+        version: None,
+        layout: Vec::new(),
+        field_layout: Vec::new(),
+        in_place: None,
+        fallible: true,
+    })
+}
+
+/// 取出 `#[repr(...)]` 中的整数类型
+fn enum_repr(attrs: &[syn::Attribute]) -> Option<syn::Ident> {
+    let mut repr = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("repr")) {
+        let _ = attr.parse_nested_meta(|meta| {
+            let integer = |ident: &&syn::Ident| {
+                matches!(ident.to_string().as_str(), "u8" | "u16" | "u32" | "u64" | "u128" | "i8" | "i16" | "i32" | "i64" | "i128")
+            };
+            if let Some(ident) = meta.path.get_ident().filter(integer) {
+                repr = Some(ident.clone());
+            }
+            Ok(())
+        });
+    }
+    repr
+}
+
+/// 一个字段或数组元素的编码代码
+struct Codec {
+    /// 编码后的字节数，为常量表达式
+    size: proc_macro2::TokenStream,
+    /// 将值写入 `buffer[pos..]` 并推进 `pos` 的语句
+    ser: proc_macro2::TokenStream,
+    /// 从 `bytes[pos..]` 读出值并推进 `pos` 的表达式，失败时从 `from_bytes` 返回错误
+    deser: proc_macro2::TokenStream,
+    /// 解码数组时用于初始化元素的零值，不支持作为数组元素时为 `None`
+    zero: Option<proc_macro2::TokenStream>,
+    /// 解码是否可能失败，`deser` 中是否有 `return Err` 或 `?`
+    fallible: bool,
+    /// 编码结果是否受字节序影响，即是否包含多字节数值
+    endian_dependent: bool,
+}
+
+/// 生成编码代码时字段级的上下文
+struct FieldCtx {
+    /// 字段名，用于错误信息
+    label: String,
+    /// 多字节数值的字节序
+    endian: Endian,
+    /// 字段上字节序选项的位置，用于报错
+    endian_span: Option<proc_macro2::Span>,
+    /// `usize`/`isize` 编码使用的定长整数类型，或 `f32` 的 `f16`
+    as_ty: Option<syn::LitStr>,
+}
+
+impl FieldCtx {
+    /// 带 `#[byte_encode(width = N)]` 的整数字段只编码低 N 个字节，解码时无符号数补零、有符号数按符号位扩展
+    fn width_codec(&self, ty: &Type, value: proc_macro2::TokenStream, width_lit: &LitInt) -> syn::Result<Codec> {
+        let int = match ty {
+            Type::Path(type_path) => type_path.path.get_ident().map(|ident| ident.to_string()),
+            _ => None,
+        };
+        let Some((int, size)) = int.and_then(|int| get_number_size(&int).filter(|_| !int.starts_with('f')).map(|size| (int, size))) else {
+            let msg = lang_tr!(cn = "`width` 只能用于整数字段", en = "`width` can only be used on integer fields");
+            return Err(diagnostics::error_spanned("PT0131", ty, msg));
+        };
+        let width = width_lit.base10_parse::<usize>()?;
+        if width == 0 || width >= size {
+            let msg = lang_tr!(
+                cn = format!("`{}` 的 `width` 必须在 1 到 {} 之间", int, size - 1),
+                en = format!("`width` of `{}` must be between 1 and {}", int, size - 1)
+            );
+            return Err(diagnostics::error_spanned("PT0132", width_lit, msg));
+        }
+        let value = quote! { (#value) };
+        let (to_bytes, from_bytes) = self.endian.methods();
+        let (size_lit, width_lit) = (proc_macro2::Literal::usize_unsuffixed(size), proc_macro2::Literal::usize_unsuffixed(width));
+        // 小端的有效字节在开头，大端的在末尾
+        let range = match self.endian {
+            Endian::Little => quote! { ..#width_lit },
+            Endian::Big => quote! { #size_lit - #width_lit.. },
+        };
+        let extend = int.starts_with('i').then(|| {
+            let shift = proc_macro2::Literal::usize_unsuffixed((size - width) * 8);
+            quote! { let value = (value << #shift) >> #shift; }
+        });
+        Ok(Codec {
+            size: quote! { #width_lit },
+            ser: quote! {
+                buffer[pos..pos + #width_lit].copy_from_slice(&#value.#to_bytes()[#range]);
+                pos += #width_lit;
+            },
+            deser: quote! {{
+                let mut arr = [0u8; #size_lit];
+                arr[#range].copy_from_slice(&bytes[pos..pos + #width_lit]);
+                let value = <#ty>::#from_bytes(arr);
+                #extend
+                pos += #width_lit;
+                value
+            }},
+            zero: None,
+            fallible: false,
+            endian_dependent: true,
+        })
+    }
+
+    /// 带 `#[byte_encode(fixed_str = N)]` 的字符串字段编码为 N 个字节的 UTF-8，不足的部分补 NUL
+    /// - `String` 等实现了 `AsRef<str>` 和 `From<&str>` 的类型超出 N 个字节时在字符边界截断
+    /// - `[u8; M]` 按原样复制前 N 个字节，解码时同样检查 UTF-8
+    /// - 解码时去掉末尾的 NUL，内容不是有效的 UTF-8 时返回 `DecodeError::InvalidValue`
+    fn fixed_str_codec(&self, ty: &Type, value: proc_macro2::TokenStream, len: &syn::Expr) -> syn::Result<Codec> {
+        let value = quote! { (#value) };
+        let len = quote! { (#len) };
+        let label = &self.label;
+        let text = quote! {
+            let raw = &bytes[pos..pos + #len];
+            let end = raw.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+            let text = match ::core::str::from_utf8(&raw[..end]) {
+                ::core::result::Result::Ok(text) => text,
+                ::core::result::Result::Err(_) => {
+                    return ::core::result::Result::Err(proc_tools_core::DecodeError::InvalidValue { field: #label, reason: "invalid UTF-8" });
+                }
+            };
+            pos += #len;
+        };
+        let (ser, deser) = match ty {
+            Type::Array(array) if matches!(&*array.elem, Type::Path(type_path) if type_path.path.is_ident("u8")) => {
+                let array_len = &array.len;
+                let ser = quote! {{
+                    let n = ::core::cmp::min(#len, #array_len);
+                    buffer[pos..pos + n].copy_from_slice(&#value[..n]);
+                    buffer[pos + n..pos + #len].fill(0);
+                    pos += #len;
+                }};
+                let deser = quote! {{
+                    #text
+                    let mut arr = [0u8; #array_len];
+                    let n = ::core::cmp::min(text.len(), #array_len);
+                    arr[..n].copy_from_slice(&text.as_bytes()[..n]);
+                    arr
+                }};
+                (ser, deser)
+            }
+            Type::Array(_) | Type::Tuple(_) | Type::Reference(_) => {
+                let msg = lang_tr!(
+                    cn = "`fixed_str` 只能用于 `String` 等字符串类型或 `[u8; N]`",
+                    en = "`fixed_str` can only be used on string types such as `String`, or `[u8; N]`"
+                );
+                return Err(diagnostics::error_spanned("PT0133", ty, msg));
+            }
+            _ => {
+                let ser = quote! {{
+                    let text: &str = ::core::convert::AsRef::<str>::as_ref(&#value);
+                    let mut n = ::core::cmp::min(text.len(), #len);
+                    while !text.is_char_boundary(n) {
+                        n -= 1;
+                    }
+                    buffer[pos..pos + n].copy_from_slice(&text.as_bytes()[..n]);
+                    buffer[pos + n..pos + #len].fill(0);
+                    pos += #len;
+                }};
+                let deser = quote! {{
+                    #text
+                    <#ty as ::core::convert::From<&str>>::from(text)
+                }};
+                (ser, deser)
+            }
+        };
+        Ok(Codec { size: len, ser, deser, zero: None, fallible: true, endian_dependent: false })
+    }
+
+    /// 带 `#[byte_encode(with = "module")]` 的字段调用模块中的 `SIZE`、`encode`、`decode` 编码
+    fn with_codec(&self, member: &syn::Member, with: &syn::Path, struct_options: &StructOptions) -> syn::Result<Codec> {
+        let conflict = match (self.endian_span, &struct_options.verify_layout) {
+            (Some(span), _) => Some(("big_endian/little_endian", span)),
+            (None, Some(verify_layout)) => Some(("verify_layout", verify_layout.span())),
+            (None, None) => None,
+        };
+        if let Some((_conflict, span)) = conflict {
+            let msg = lang_tr!(
+                cn = format!("`with` 不能与 `{}` 一起使用，字段的编码完全由模块中的函数决定", _conflict),
+                en = format!("`with` cannot be combined with `{}`, the encoding is entirely up to the module's functions", _conflict)
+            );
+            return Err(diagnostics::error("PT0134", span, msg));
+        }
+        let (size, label) = (quote! { (#with::SIZE) }, &self.label);
+        Ok(Codec {
+            size: size.clone(),
+            ser: quote! {
+                #with::encode(&self.#member, &mut buffer[pos..pos + #size]);
+                pos += #size;
+            },
+            deser: quote! {{
+                let value = match #with::decode(&bytes[pos..pos + #size]) {
+                    ::core::result::Result::Ok(value) => value,
+                    ::core::result::Result::Err(reason) => {
+                        return ::core::result::Result::Err(proc_tools_core::DecodeError::InvalidValue { field: #label, reason });
+                    }
+                };
+                pos += #size;
+                value
+            }},
+            zero: None,
+            fallible: true,
+            endian_dependent: false,
+        })
+    }
+
+    /// 为类型 `ty` 生成编码代码，`value` 为要编码的位置表达式，如 `self.field`、`item`
+    fn codec(&self, ty: &Type, value: proc_macro2::TokenStream) -> syn::Result<Codec> {
+        let value = quote! { (#value) };
+        let (to_bytes, from_bytes) = self.endian.methods();
+        match ty {
+            // [u8; N] 按原样复制，N 可以是常量表达式
+            Type::Array(array) if matches!(&*array.elem, Type::Path(type_path) if type_path.path.is_ident("u8")) => {
+                let len = &array.len;
+                Ok(Codec {
+                    size: quote! { (#len) },
+                    ser: quote! {
+                        buffer[pos..pos + (#len)].copy_from_slice(&#value);
+                        pos += #len;
+                    },
+                    deser: quote! {{
+                        let mut arr = [0u8; #len];
+                        arr.copy_from_slice(&bytes[pos..pos + (#len)]);
+                        pos += #len;
+                        arr
+                    }},
+                    zero: Some(quote! { [0u8; #len] }),
+                    fallible: false,
+                    endian_dependent: false,
+                })
+            }
+            // 其他数组逐个编码元素，元素的字节序与字段相同
+            // 按值遍历数组的副本，`#[repr(packed)]` 结构体的字段不能直接借用
+            Type::Array(array) => {
+                let len = &array.len;
+                let elem = self.codec(&array.elem, quote! { item })?;
+                let Some(zero) = elem.zero else {
+                    let msg = lang_tr!(
+                        cn = "数组元素只支持数值、`bool`、`char` 和数组",
+                        en = "Array elements must be numbers, `bool`, `char` or arrays"
+                    );
+                    return Err(diagnostics::error_spanned("PT0135", &array.elem, msg));
+                };
+                let (elem_size, elem_ser, elem_deser, fallible, endian_dependent) =
+                    (elem.size, elem.ser, elem.deser, elem.fallible, elem.endian_dependent);
+                Ok(Codec {
+                    size: quote! { (#elem_size * (#len)) },
+                    ser: quote! {
+                        for item in #value {
+                            #elem_ser
+                        }
+                    },
+                    deser: quote! {{
+                        let mut arr = [#zero; #len];
+                        for item in arr.iter_mut() {
+                            *item = #elem_deser;
+                        }
+                        arr
+                    }},
+                    zero: Some(quote! { [#zero; #len] }),
+                    fallible,
+                    endian_dependent,
+                })
+            }
+            // bool 编码为 0/1，解码时拒绝其他值
+            Type::Path(type_path) if type_path.path.is_ident("bool") => {
+                let label = &self.label;
+                Ok(Codec {
+                    size: quote! { 1 },
+                    ser: quote! {
+                        buffer[pos] = #value as u8;
+                        pos += 1;
+                    },
+                    deser: quote! {{
+                        let value = match bytes[pos] {
+                            0 => false,
+                            1 => true,
+                            value => return ::core::result::Result::Err(proc_tools_core::DecodeError::InvalidBool { field: #label, value }),
+                        };
+                        pos += 1;
+                        value
+                    }},
+                    zero: Some(quote! { false }),
+                    fallible: true,
+                    endian_dependent: false,
+                })
+            }
+            // char 按字节序编码为 u32 标量值，解码时拒绝代理项和超出范围的值
+            Type::Path(type_path) if type_path.path.is_ident("char") => {
+                let label = &self.label;
+                Ok(Codec {
+                    size: quote! { 4 },
+                    ser: quote! {
+                        buffer[pos..pos + 4].copy_from_slice(&(#value as u32).#to_bytes());
+                        pos += 4;
+                    },
+                    deser: quote! {{
+                        let mut arr = [0u8; 4];
+                        arr.copy_from_slice(&bytes[pos..pos + 4]);
+                        let scalar = u32::#from_bytes(arr);
+                        let value = match ::core::char::from_u32(scalar) {
+                            ::core::option::Option::Some(value) => value,
+                            ::core::option::Option::None => {
+                                return ::core::result::Result::Err(proc_tools_core::DecodeError::InvalidChar { field: #label, value: scalar });
+                            }
+                        };
+                        pos += 4;
+                        value
+                    }},
+                    zero: Some(quote! { '\0' }),
+                    fallible: true,
+                    endian_dependent: true,
+                })
+            }
+            // 带 `as = "f16"` 的 `f32` 编码为半精度浮点数，按最近偶数舍入
+            Type::Path(type_path) if type_path.path.is_ident("f32") && self.as_ty.is_some() => {
+                if let Some(as_lit) = self.as_ty.as_ref().filter(|as_lit| as_lit.value() != "f16") {
+                    let msg = lang_tr!(cn = "`f32` 的 `as` 只能为 `f16`", en = "`as` for `f32` must be `f16`");
+                    return Err(diagnostics::error_spanned("PT0136", as_lit, msg));
+                }
+                Ok(Codec {
+                    size: quote! { 2 },
+                    ser: quote! {
+                        buffer[pos..pos + 2].copy_from_slice(&proc_tools_core::utils_core::half::f32_to_f16_bits(#value).#to_bytes());
+                        pos += 2;
+                    },
+                    deser: quote! {{
+                        let mut arr = [0u8; 2];
+                        arr.copy_from_slice(&bytes[pos..pos + 2]);
+                        pos += 2;
+                        proc_tools_core::utils_core::half::f16_bits_to_f32(u16::#from_bytes(arr))
+                    }},
+                    zero: Some(quote! { 0.0f32 }),
+                    fallible: false,
+                    endian_dependent: true,
+                })
+            }
+            // 数值类型按字节序使用 to_le_bytes/to_be_bytes 和 from_le_bytes/from_be_bytes 方法
+            Type::Path(type_path) if type_path.path.get_ident().and_then(|ident| get_number_size(&ident.to_string())).is_some() => {
+                let size = get_number_size(&type_path.path.segments[0].ident.to_string()).unwrap_or_default();
+                let size_lit = LitInt::new(&size.to_string(), ty.span());
+                Ok(Codec {
+                    size: quote! { #size_lit },
+                    ser: quote! {
+                        buffer[pos..pos + #size_lit].copy_from_slice(&#value.#to_bytes());
+                        pos += #size_lit;
+                    },
+                    deser: quote! {{
+                        let mut arr = [0u8; #size_lit];
+                        arr.copy_from_slice(&bytes[pos..pos + #size_lit]);
+                        let value = <#ty>::#from_bytes(arr);
+                        pos += #size_lit;
+                        value
+                    }},
+                    zero: Some(quote! { 0 as #ty }),
+                    fallible: false,
+                    endian_dependent: size > 1,
+                })
+            }
+            // 平台相关的 `usize`/`isize` 必须指定定长的编码类型，编码时按 `as` 截断，解码时检查范围
+            Type::Path(type_path) if type_path.path.is_ident("usize") || type_path.path.is_ident("isize") => {
+                let signed = type_path.path.is_ident("isize");
+                let Some(as_lit) = &self.as_ty else {
+                    let msg = lang_tr!(
+                        cn = format!("`{0}` 的大小依赖平台，需要用 `#[byte_encode(as = \"{1}64\")]` 等指定编码类型", quote!(#ty), if signed { "i" } else { "u" }),
+                        en = format!(
+                            "the size of `{0}` depends on the platform, specify the encoded type with e.g. `#[byte_encode(as = \"{1}64\")]`",
+                            quote!(#ty),
+                            if signed { "i" } else { "u" }
+                        )
+                    );
+                    return Err(diagnostics::error_spanned("PT0137", ty, msg));
+                };
+                let as_name = as_lit.value();
+                let valid = match signed {
+                    true => matches!(as_name.as_str(), "i8" | "i16" | "i32" | "i64" | "i128"),
+                    false => matches!(as_name.as_str(), "u8" | "u16" | "u32" | "u64" | "u128"),
+                };
+                if !valid {
+                    let msg = lang_tr!(
+                        cn = format!("`{}` 的 `as` 必须为同样有无符号的整数类型", quote!(#ty)),
+                        en = format!("`as` for `{}` must be an integer type of the same signedness", quote!(#ty))
+                    );
+                    return Err(diagnostics::error_spanned("PT0138", as_lit, msg));
+                }
+                let as_ty: Type = syn::parse_str(&as_name)?;
+                let int_codec = self.codec(&as_ty, quote! { #value as #as_ty })?;
+                let (size, ser, int_deser, endian_dependent) = (int_codec.size, int_codec.ser, int_codec.deser, int_codec.endian_dependent);
+                // `usize` 至少为 16 位，8 位和 16 位的值总能转换
+                let (deser, fallible) = match as_name.as_str() {
+                    "u8" | "u16" | "i8" | "i16" => (quote! { <#ty as ::core::convert::From<#as_ty>>::from(#int_deser) }, false),
+                    _ => {
+                        let (label, reason) = (&self.label, format!("value out of range for {}", quote!(#ty)));
+                        let deser = quote! {{
+                            match <#ty as ::core::convert::TryFrom<#as_ty>>::try_from(#int_deser) {
+                                ::core::result::Result::Ok(value) => value,
+                                ::core::result::Result::Err(_) => {
+                                    return ::core::result::Result::Err(proc_tools_core::DecodeError::InvalidValue { field: #label, reason: #reason });
+                                }
+                            }
+                        }};
+                        (deser, true)
+                    }
+                };
+                Ok(Codec { size, ser, deser, zero: Some(quote! { 0 as #ty }), fallible, endian_dependent })
+            }
+            // NonZero 整数按内部整数编码，解码时拒绝 0
+            Type::Path(type_path) if type_path.qself.is_none() && nonzero_int(&type_path.path).is_some() => {
+                let int = format_ident!("{}", nonzero_int(&type_path.path).unwrap_or_default());
+                let int_ty: Type = syn::parse_quote_spanned! { ty.span()=> #int };
+                let int_codec = self.codec(&int_ty, quote! { #value.get() })?;
+                let (size, ser, int_deser, endian_dependent) = (int_codec.size, int_codec.ser, int_codec.deser, int_codec.endian_dependent);
+                let label = &self.label;
+                Ok(Codec {
+                    size,
+                    ser,
+                    deser: quote! {{
+                        match <#ty>::new(#int_deser) {
+                            ::core::option::Option::Some(value) => value,
+                            ::core::option::Option::None => {
+                                return ::core::result::Result::Err(proc_tools_core::DecodeError::ZeroValue { field: #label });
+                            }
+                        }
+                    }},
+                    zero: Some(quote! { <#ty>::MIN }),
+                    fallible: true,
+                    endian_dependent,
+                })
+            }
+            // 其他路径类型视为实现了 `ByteEncode` 的类型，委托给其实现，字节序由其自身决定
+            Type::Path(type_path) if type_path.qself.is_none() => {
+                if let Some(endian) = self.endian_span {
+                    let msg = lang_tr!(
+                        cn = "嵌套结构体字段的字节序由其自身的 `#[byte_encode(endian = ...)]` 决定，不能在字段上设置",
+                        en = "The endianness of a nested struct field is set by its own `#[byte_encode(endian = ...)]`, not on the field"
+                    );
+                    return Err(diagnostics::error("PT0139", endian, msg));
+                }
+                let nested_size = quote! { <#ty as proc_tools_core::ByteEncode>::SIZE };
+                Ok(Codec {
+                    size: nested_size.clone(),
+                    ser: quote! {
+                        pos += proc_tools_core::ByteEncode::write_to(&#value, &mut buffer[pos..])?;
+                    },
+                    deser: quote! {{
+                        let value = <#ty as proc_tools_core::ByteEncode>::from_bytes(&bytes[pos..pos + #nested_size])?;
+                        pos += #nested_size;
+                        value
+                    }},
+                    zero: None,
+                    fallible: true,
+                    endian_dependent: false,
+                })
+            }
+            _ => {
+                let msg = lang_tr!(cn = "不支持的类型", en = "Unsupported type");
+                Err(diagnostics::error_spanned("PT0140", ty, msg))
+            }
+        }
+    }
+}
+
+/// 类型是否为可以使用 `as` 的 `usize`、`isize`、`f32` 或其（多维）数组
+fn is_as_target(ty: &Type) -> bool {
+    match ty {
+        Type::Array(array) => is_as_target(&array.elem),
+        Type::Path(type_path) => ["usize", "isize", "f32"].iter().any(|ident| type_path.path.is_ident(ident)),
+        _ => false,
+    }
+}
+
+/// 数值类型的字节数，不是数值类型时返回 `None`
+fn get_number_size(ident: &str) -> Option<usize> {
+    match ident {
+        "u8" | "i8" => Some(1),
+        "u16" | "i16" => Some(2),
+        "u32" | "i32" | "f32" => Some(4),
+        "u64" | "i64" | "f64" => Some(8),
+        "u128" | "i128" => Some(16),
+        _ => None,
+    }
+}
+
+/// `NonZeroU16`、`core::num::NonZeroU16`、`NonZero<u16>` 等 NonZero 整数类型的内部整数类型名，不是时返回 `None`
+fn nonzero_int(path: &syn::Path) -> Option<String> {
+    let segment = path.segments.last()?;
+    let name = segment.ident.to_string();
+    let int = match &segment.arguments {
+        syn::PathArguments::None => name.strip_prefix("NonZero")?.to_lowercase(),
+        syn::PathArguments::AngleBracketed(args) if name == "NonZero" && args.args.len() == 1 => match &args.args[0] {
+            syn::GenericArgument::Type(Type::Path(inner)) => inner.path.get_ident()?.to_string(),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    (get_number_size(&int).is_some() && !int.starts_with('f')).then_some(int)
+}
+
+/// 多字节数值的字节序
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+enum Endian {
+    #[default]
+    Little,
+    Big,
+}
+
+impl Endian {
+    /// 编码和解码使用的方法名，如 `(to_le_bytes, from_le_bytes)`
+    fn methods(self) -> (syn::Ident, syn::Ident) {
+        let suffix = match self {
+            Endian::Little => "le",
+            Endian::Big => "be",
+        };
+        (format_ident!("to_{}_bytes", suffix), format_ident!("from_{}_bytes", suffix))
+    }
+}
+
+/// 校验和算法
+#[derive(Clone, Copy)]
+enum Checksum {
+    /// CRC-32，字段类型为 `u32`
+    Crc32,
+    /// 字节回绕相加，字段类型为 `u8`
+    Sum8,
+    /// 字节异或，字段类型为 `u8`
+    Xor,
+}
+
+impl Checksum {
+    /// `proc_tools_core::utils_core::checksum` 中的函数名及字段类型
+    fn func(self) -> (syn::Ident, &'static str) {
+        match self {
+            Checksum::Crc32 => (format_ident!("crc32"), "u32"),
+            Checksum::Sum8 => (format_ident!("sum8"), "u8"),
+            Checksum::Xor => (format_ident!("xor8"), "u8"),
+        }
+    }
+}
+
+/// 结构体上的 `#[byte_encode(...)]` 选项
+#[derive(Default)]
+struct StructOptions {
+    /// 所有字段默认的字节序，`endian = "big"` 或 `endian = "little"`
+    endian: Endian,
+    /// 生成按需解码字段的视图类型 `类型名View<'a>`
+    view: bool,
+    /// 当前版本号，编码时写在开头的一个字节
+    version: Option<LitInt>,
+    /// 在编译时断言内存布局与编码结果一致，并生成 `as_bytes` 方法，值为选项的路径，用于报错
+    verify_layout: Option<syn::Path>,
+    /// 额外生成经过字节表示的 `serde` 实现，`serde = "bytes"` 或 `serde = "hex"`
+    serde: Option<syn::LitStr>,
+    /// 生成逐个字段写入的 `decode_into`，含 `unsafe` 代码
+    in_place: bool,
+    /// 额外生成标注各字段偏移和编码后字节的 `Debug` 实现
+    debug_hex: bool,
+    /// 生成带帧头的编码和解码方法
+    frame: Option<FrameOptions>,
+}
+
+/// `#[byte_encode(frame(magic = b"...", len = "u16"))]` 的选项
+struct FrameOptions {
+    /// 帧开头的魔数
+    magic: Option<syn::LitByteStr>,
+    /// 长度字段的类型，`u16` 或 `u32`，默认为 `u32`
+    len: syn::Ident,
+}
+
+impl StructOptions {
+    fn parse(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut options = StructOptions::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("byte_encode")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("endian") {
+                    let endian = meta.value()?.parse::<syn::LitStr>()?;
+                    options.endian = match endian.value().as_str() {
+                        "little" => Endian::Little,
+                        "big" => Endian::Big,
+                        _ => {
+                            let msg = lang_tr!(cn = "字节序只能为 `little` 或 `big`", en = "Endianness must be `little` or `big`");
+                            return Err(diagnostics::error_spanned("PT0141", endian, msg));
+                        }
+                    };
+                    return Ok(());
+                }
+                if meta.path.is_ident("view") {
+                    options.view = true;
+                    return Ok(());
+                }
+                if meta.path.is_ident("version") {
+                    let version = meta.value()?.parse::<LitInt>()?;
+                    version.base10_parse::<u8>()?;
+                    options.version = Some(version);
+                    return Ok(());
+                }
+                if meta.path.is_ident("verify_layout") {
+                    options.verify_layout = Some(meta.path.clone());
+                    return Ok(());
+                }
+                if meta.path.is_ident("frame") {
+                    let mut frame = FrameOptions { magic: None, len: format_ident!("u32") };
+                    // 不带括号的 `frame` 使用默认选项
+                    if !meta.input.peek(syn::token::Paren) {
+                        options.frame = Some(frame);
+                        return Ok(());
+                    }
+                    meta.parse_nested_meta(|meta| {
+                        if meta.path.is_ident("magic") {
+                            frame.magic = Some(meta.value()?.parse::<syn::LitByteStr>()?);
+                            return Ok(());
+                        }
+                        if meta.path.is_ident("len") {
+                            let len = meta.value()?.parse::<syn::LitStr>()?;
+                            if !matches!(len.value().as_str(), "u16" | "u32") {
+                                let msg = lang_tr!(cn = "帧头长度只能为 `u16` 或 `u32`", en = "The frame length must be `u16` or `u32`");
+                                return Err(diagnostics::error_spanned("PT0142", len, msg));
+                            }
+                            frame.len = syn::Ident::new(&len.value(), len.span());
+                            return Ok(());
+                        }
+                        Err(diagnostics::unknown_option("PT0145", &meta, "`magic`、`len`", "`magic` or `len`"))
+                    })?;
+                    options.frame = Some(frame);
+                    return Ok(());
+                }
+                if meta.path.is_ident("debug_hex") {
+                    options.debug_hex = true;
+                    return Ok(());
+                }
+                if meta.path.is_ident("in_place") {
+                    options.in_place = true;
+                    return Ok(());
+                }
+                if meta.path.is_ident("serde") {
+                    let repr = meta.value()?.parse::<syn::LitStr>()?;
+                    if !matches!(repr.value().as_str(), "bytes" | "hex") {
+                        let msg = lang_tr!(cn = "`serde` 只能为 `bytes` 或 `hex`", en = "`serde` must be `bytes` or `hex`");
+                        return Err(diagnostics::error_spanned("PT0143", repr, msg));
+                    }
+                    options.serde = Some(repr);
+                    return Ok(());
+                }
+                Err(diagnostics::unknown_option(
+                    "PT0145",
+                    &meta,
+                    "`endian`、`view`、`version`、`verify_layout`、`serde`、`in_place`、`debug_hex`、`frame`",
+                    "`endian`, `view`, `version`, `verify_layout`, `serde`, `in_place`, `debug_hex` or `frame`",
+                ))
+            })?;
+        }
+        Ok(options)
+    }
+}
+
+/// 字段上的 `#[byte_encode(...)]` 选项
+#[derive(Default)]
+struct FieldOptions {
+    /// 该字段的字节序，优先于结构体上的设置
+    endian: Option<Endian>,
+    /// 字节序选项的位置，用于报错
+    endian_span: Option<proc_macro2::Span>,
+    /// 不编码该字段，解码时以 `Default::default()` 初始化
+    skip: bool,
+    /// 在该字段之前插入的保留字节数
+    pad: Option<syn::Expr>,
+    /// 该字段为之前所有字节的校验和，及选项的原文
+    checksum: Option<(Checksum, syn::LitStr)>,
+    /// 加入该字段的版本号，更早版本的数据中没有该字段
+    since: Option<LitInt>,
+    /// 解码更早版本的数据时该字段的值，默认为 `Default::default()`
+    default: Option<syn::Expr>,
+    /// 位字段占用的位数，连续的位字段共同占用整数个字节
+    bits: Option<LitInt>,
+    /// 自定义编码的模块，提供 `SIZE`、`encode`、`decode`
+    with: Option<syn::Path>,
+    /// 整数字段只编码低位的字节数
+    width: Option<LitInt>,
+    /// `usize`/`isize` 字段编码使用的定长整数类型，或 `f32` 字段的 `f16`
+    as_ty: Option<syn::LitStr>,
+    /// 解码时检查的条件，及选项的原文
+    assert: Option<(syn::Expr, syn::LitStr)>,
+    /// 字符串字段编码后的固定字节数，不足的部分补 NUL
+    fixed_str: Option<syn::Expr>,
+}
+
+impl FieldOptions {
+    fn parse(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut options = FieldOptions::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("byte_encode")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("big_endian") {
+                    options.endian = Some(Endian::Big);
+                    options.endian_span = Some(meta.path.span());
+                    return Ok(());
+                }
+                if meta.path.is_ident("little_endian") {
+                    options.endian = Some(Endian::Little);
+                    options.endian_span = Some(meta.path.span());
+                    return Ok(());
+                }
+                if meta.path.is_ident("skip") {
+                    options.skip = true;
+                    return Ok(());
+                }
+                if meta.path.is_ident("pad") {
+                    options.pad = Some(meta.value()?.parse::<syn::Expr>()?);
+                    return Ok(());
+                }
+                if meta.path.is_ident("checksum") {
+                    let lit = meta.value()?.parse::<syn::LitStr>()?;
+                    let checksum = match lit.value().as_str() {
+                        "crc32" => Checksum::Crc32,
+                        "sum8" => Checksum::Sum8,
+                        "xor" => Checksum::Xor,
+                        _ => {
+                            let msg = lang_tr!(
+                                cn = "校验和算法只能为 `crc32`、`sum8` 或 `xor`",
+                                en = "Checksum algorithm must be `crc32`, `sum8` or `xor`"
+                            );
+                            return Err(diagnostics::error_spanned("PT0144", lit, msg));
+                        }
+                    };
+                    options.checksum = Some((checksum, lit));
+                    return Ok(());
+                }
+                if meta.path.is_ident("since") {
+                    let since = meta.value()?.parse::<LitInt>()?;
+                    since.base10_parse::<u8>()?;
+                    options.since = Some(since);
+                    return Ok(());
+                }
+                if meta.path.is_ident("default") {
+                    options.default = Some(meta.value()?.parse::<syn::LitStr>()?.parse::<syn::Expr>()?);
+                    return Ok(());
+                }
+                if meta.path.is_ident("bits") {
+                    options.bits = Some(meta.value()?.parse::<LitInt>()?);
+                    return Ok(());
+                }
+                if meta.path.is_ident("width") {
+                    options.width = Some(meta.value()?.parse::<LitInt>()?);
+                    return Ok(());
+                }
+                if meta.path.is_ident("with") {
+                    options.with = Some(meta.value()?.parse::<syn::LitStr>()?.parse::<syn::Path>()?);
+                    return Ok(());
+                }
+                if meta.path.is_ident("fixed_str") {
+                    options.fixed_str = Some(meta.value()?.parse::<syn::Expr>()?);
+                    return Ok(());
+                }
+                if meta.path.is_ident("assert") {
+                    let lit = meta.value()?.parse::<syn::LitStr>()?;
+                    options.assert = Some((lit.parse::<syn::Expr>()?, lit));
+                    return Ok(());
+                }
+                if meta.path.is_ident("as") {
+                    options.as_ty = Some(meta.value()?.parse::<syn::LitStr>()?);
+                    return Ok(());
+                }
+                Err(diagnostics::unknown_option(
+                    "PT0145",
+                    &meta,
+                    "`big_endian`、`little_endian`、`skip`、`pad`、`checksum`、`since`、`default`、`bits`、`width`、`with`、`as`、`assert`、`fixed_str`",
+                    "`big_endian`, `little_endian`, `skip`, `pad`, `checksum`, `since`, `default`, `bits`, `width`, `with`, `as`, `assert` or `fixed_str`",
+                ))
+            })?;
         }
-        _ => panic!(lang_tr!(cn = "不支持的类型", en = "Unsupported type")),
+        Ok(options)
     }
 }
@@ -0,0 +1,178 @@
+use crate::derive_nwe::doc_lines;
+use crate::diagnostics;
+use proc_macro::TokenStream;
+use proc_tools_helper::lang_tr;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::{Data, DeriveInput, Fields, parse_macro_input};
+
+/// 生成的访问方法种类
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Accessor {
+    /// `field()`
+    Getter,
+    /// `set_field()`
+    Setter,
+    /// `with_field()`
+    With,
+}
+
+pub(crate) fn derive_getters_implement(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match getset_code(&input, Accessor::Getter) {
+        Ok(code) => TokenStream::from(code),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+pub(crate) fn derive_setters_implement(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match getset_code(&input, Accessor::Setter) {
+        Ok(code) => TokenStream::from(code),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+pub(crate) fn derive_with_methods_implement(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match getset_code(&input, Accessor::With) {
+        Ok(code) => TokenStream::from(code),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// 为命名字段结构体的每个字段生成访问方法
+/// - 读取方法与字段同名，返回 `&字段类型`，带 `#[getset(copy)]` 的字段按值返回
+/// - 修改方法名为 `set_字段名`，返回 `&mut Self` 以便链式调用
+/// - 链式修改方法名为 `with_字段名`，按值接收并返回 `Self`
+/// - 可见性依次取字段上的 `#[getset(vis = "...")]`、结构体上的 `#[getset(vis = "...")]`，默认为 `pub`
+fn getset_code(input: &DeriveInput, accessor: Accessor) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            fields => {
+                let msg = lang_tr!(cn = "仅支持具有命名字段的结构体", en = "Only structs with named fields are supported");
+                return Err(diagnostics::error_spanned("PT0401", fields, msg));
+            }
+        },
+        _ => {
+            let msg = lang_tr!(cn = "仅支持结构体", en = "Only structs are supported");
+            return Err(diagnostics::error_spanned("PT0402", name, msg));
+        }
+    };
+    let struct_options = GetSetOptions::parse(&input.attrs, false)?;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut methods = Vec::new();
+    for field in fields {
+        let options = GetSetOptions::parse(&field.attrs, true)?;
+        if options.skip {
+            continue;
+        }
+        let (Some(ident), ty) = (&field.ident, &field.ty) else {
+            continue;
+        };
+        let vis = match (&options.vis, &struct_options.vis) {
+            (Some(vis), _) | (None, Some(vis)) => vis,
+            (None, None) => &syn::parse_quote! { pub },
+        };
+        let field_name = ident.unraw().to_string();
+        methods.push(match accessor {
+            Accessor::Getter => {
+                let mut docs = doc_lines(&field.attrs);
+                if docs.is_empty() {
+                    docs.push(format!("返回 `{}` 字段", field_name));
+                }
+                if options.copy {
+                    quote! {
+                        #(#[doc = #docs])*
+                        #[inline]
+                        #vis fn #ident(&self) -> #ty {
+                            self.#ident
+                        }
+                    }
+                } else {
+                    quote! {
+                        #(#[doc = #docs])*
+                        #[inline]
+                        #vis fn #ident(&self) -> &#ty {
+                            &self.#ident
+                        }
+                    }
+                }
+            }
+            Accessor::Setter => {
+                let setter = format_ident!("set_{}", field_name);
+                let doc = format!("设置 `{}` 字段", field_name);
+                quote! {
+                    #[doc = #doc]
+                    #[inline]
+                    #vis fn #setter(&mut self, value: #ty) -> &mut Self {
+                        self.#ident = value;
+                        self
+                    }
+                }
+            }
+            Accessor::With => {
+                let with = format_ident!("with_{}", field_name);
+                let doc = format!("设置 `{}` 字段并返回修改后的值", field_name);
+                quote! {
+                    #[doc = #doc]
+                    #[inline]
+                    #[must_use]
+                    #vis fn #with(mut self, value: #ty) -> Self {
+                        self.#ident = value;
+                        self
+                    }
+                }
+            }
+        });
+    }
+
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #(#methods)*
+        }
+    })
+}
+
+/// 结构体或字段上的 `#[getset(...)]` 选项
+#[derive(Default)]
+struct GetSetOptions {
+    /// 不为该字段生成方法
+    skip: bool,
+    /// 读取方法按值返回，字段类型需实现 `Copy`
+    copy: bool,
+    /// 生成方法的可见性，`vis = ""` 为私有
+    vis: Option<syn::Visibility>,
+}
+
+impl GetSetOptions {
+    /// `is_field` 为 `false` 时只接受 `vis`
+    fn parse(attrs: &[syn::Attribute], is_field: bool) -> syn::Result<Self> {
+        let mut options = GetSetOptions::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("getset")) {
+            attr.parse_nested_meta(|meta| {
+                if is_field && meta.path.is_ident("skip") {
+                    options.skip = true;
+                    return Ok(());
+                }
+                if is_field && meta.path.is_ident("copy") {
+                    options.copy = true;
+                    return Ok(());
+                }
+                if meta.path.is_ident("vis") {
+                    options.vis = Some(meta.value()?.parse::<syn::LitStr>()?.parse::<syn::Visibility>()?);
+                    return Ok(());
+                }
+                if is_field {
+                    Err(diagnostics::unknown_option("PT0403", &meta, "`skip`、`copy`、`vis`", "`skip`, `copy` or `vis`"))
+                } else {
+                    Err(diagnostics::unknown_option("PT0403", &meta, "`vis`", "`vis`"))
+                }
+            })?;
+        }
+        Ok(options)
+    }
+}
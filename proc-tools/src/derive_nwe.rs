@@ -1,82 +1,430 @@
-use proc_macro::{Delimiter, TokenStream, TokenTree};
+use crate::diagnostics;
+use proc_macro::TokenStream;
 use proc_tools_helper::lang_tr;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::{Data, DeriveInput, Expr, parse_macro_input};
 
 pub(crate) fn derive_new_implement(input: TokenStream) -> TokenStream {
-    let mut struct_name = None;
-    let mut fields = Vec::new();
-
-    // 解析结构体定义
-    let mut tokens = input.into_iter();
-    while let Some(token) = tokens.next() {
-        if let TokenTree::Ident(ident) = &token {
-            if ident.to_string() == "struct" {
-                if let Some(TokenTree::Ident(name)) = tokens.next() {
-                    struct_name = Some(name.to_string());
-                }
+    let input = parse_macro_input!(input as DeriveInput);
+    match new_code(&input) {
+        Ok(code) => TokenStream::from(code),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// 生成 `new` 构造函数
+/// - 命名字段结构体的参数与字段同名，元组结构体的参数依次命名为 `arg0`、`arg1`……，单元结构体生成无参数的 `new()`
+/// - 枚举为每个变体生成一个构造函数，名称为 `new_` 加变体名的蛇形命名，如 `KeyPressed` 生成 `new_key_pressed`
+/// - 结构体的泛型参数（类型、生命周期、常量）及其约束、`where` 子句原样带入生成的 `impl` 块，
+///   如 `impl<'a, T: Clone, const N: usize> Wrapper<'a, T, N>`
+/// - 参数默认按字段声明顺序排列，字段指定 `#[new(order = N)]` 时按 `N` 从小到大排列
+/// - 结构体的文档注释复制到 `new` 上，并追加列出各参数及对应字段文档注释的 `# 参数` 一节
+/// - 指定 `#[new(validate = "函数", error = "错误类型")]` 时构造函数返回 `Result<Self, 错误类型>`
+/// - 构造函数默认名为 `new`，枚举变体的构造函数以 `new_` 为前缀，可用 `#[new(name = "create")]` 改为其他名称
+/// - 指定 `#[new(derive_default)]` 时另外生成以各参数类型的默认值调用 `new` 的 `Default` 实现
+fn new_code(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let struct_options = StructOptions::parse(&input.attrs)?;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let constructors = match &input.data {
+        Data::Struct(data) => {
+            let docs = doc_lines(&input.attrs);
+            vec![constructor(&struct_options, &input.vis, struct_options.name(), quote! { Self }, &data.fields, docs, name.to_string())?]
+        }
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .map(|variant| {
+                let ident = &variant.ident;
+                let fn_name = format_ident!("{}_{}", struct_options.name(), snake_case(&ident.unraw().to_string()));
+                let docs = doc_lines(&variant.attrs);
+                constructor(&struct_options, &input.vis, fn_name, quote! { Self::#ident }, &variant.fields, docs, format!("{}::{}", name, ident))
+            })
+            .collect::<syn::Result<_>>()?,
+        Data::Union(_) => {
+            let msg = lang_tr!(cn = "仅支持结构体和枚举", en = "Only structs and enums are supported");
+            return Err(diagnostics::error_spanned("PT0201", name, msg));
+        }
+    };
+
+    let default_impl = match (&input.data, struct_options.derive_default) {
+        (Data::Struct(data), true) => Some(default_code(input, &struct_options, &data.fields)?),
+        (_, true) => {
+            let msg = lang_tr!(cn = "`derive_default` 仅支持结构体", en = "`derive_default` is only supported on structs");
+            return Err(diagnostics::error_spanned("PT0202", name, msg));
+        }
+        _ => None,
+    };
+
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #(#constructors)*
+        }
+
+        #default_impl
+    })
+}
+
+/// 生成委托给 `new` 的 `Default` 实现，每个参数传入其字段类型的 `Default::default()`
+/// - 作为参数的字段类型均需实现 `Default`，泛型结构体为这些类型追加 `where` 约束
+/// - 以 `<字段类型 as Default>::default()` 传参，`#[new(into)]` 的参数同样适用，`#[new(clone)]` 的参数传入其引用
+fn default_code(input: &DeriveInput, struct_options: &StructOptions, fields: &syn::Fields) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    if let Some((validate, _)) = &struct_options.validate {
+        let msg = lang_tr!(
+            cn = "`derive_default` 不能与 `validate` 一起使用，`Default::default()` 无法返回错误",
+            en = "`derive_default` cannot be combined with `validate`, `Default::default()` cannot return an error"
+        );
+        return Err(diagnostics::error_spanned("PT0203", validate, msg));
+    }
+    let mut param_tys = Vec::new();
+    for field in fields {
+        let options = FieldOptions::parse(&field.attrs)?;
+        if options.default.is_none() && !is_phantom_data(&field.ty) {
+            param_tys.push((options.order()?, &field.ty, options.clone));
+        }
+    }
+    param_tys.sort_by_key(|(order, ..)| *order);
+    let args = param_tys.iter().map(|(_, ty, clone)| {
+        let arg = quote! { <#ty as ::core::default::Default>::default() };
+        if *clone { quote! { &#arg } } else { arg }
+    });
+    let param_tys: Vec<_> = param_tys.iter().map(|(_, ty, _)| ty).collect();
+    let mut generics = input.generics.clone();
+    if !generics.params.is_empty() {
+        let where_clause = generics.make_where_clause();
+        for ty in &param_tys {
+            where_clause.predicates.push(syn::parse_quote! { #ty: ::core::default::Default });
+        }
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let fn_name = struct_options.name();
+
+    Ok(quote! {
+        impl #impl_generics ::core::default::Default for #name #ty_generics #where_clause {
+            fn default() -> Self {
+                Self::#fn_name(#(#args),*)
+            }
+        }
+    })
+}
+
+/// 生成一个构造函数，`path` 为构造的结构体或变体（`Self`、`Self::变体`），`docs` 为复制到构造函数上的文档注释，
+/// 为空时使用“创建 `target`”
+fn constructor(
+    struct_options: &StructOptions,
+    input_vis: &syn::Visibility,
+    fn_name: syn::Ident,
+    path: proc_macro2::TokenStream,
+    fields: &syn::Fields,
+    mut docs: Vec<String>,
+    target: String,
+) -> syn::Result<proc_macro2::TokenStream> {
+    // (order, 字段, 参数, 参数文档)
+    let mut params = Vec::new();
+    let mut inits = Vec::new();
+    for (idx, field) in fields.iter().enumerate() {
+        let mut options = FieldOptions::parse(&field.attrs)?;
+        let ty = &field.ty;
+        // `PhantomData` 字段不作为参数
+        if options.default.is_none() && is_phantom_data(ty) {
+            options.default = Some(quote! { ::core::marker::PhantomData });
+        }
+        // 以 `Self { 0: arg0 }` 的形式初始化元组结构体
+        let (member, param) = match &field.ident {
+            Some(ident) => (syn::Member::Named(ident.clone()), ident.clone()),
+            None => (syn::Member::Unnamed(syn::Index::from(idx)), format_ident!("arg{}", idx)),
+        };
+        if let (true, Some(option)) = (options.clone, options.clone_conflict()) {
+            let msg = lang_tr!(
+                cn = format!("`clone` 不能与 `{}` 选项一起使用", option),
+                en = format!("`clone` cannot be combined with the `{}` option", option)
+            );
+            return Err(diagnostics::error_spanned("PT0204", field, msg));
+        }
+        // `const fn` 中不能调用 `Default::default()`、`Into::into()` 和 `Clone::clone()`
+        if let (true, Some(option)) = (struct_options.constness, options.const_conflict(struct_options.into)) {
+            let msg = lang_tr!(
+                cn = format!("`#[new(const)]` 不能与 `{}` 选项一起使用，`const fn` 中不能调用 trait 方法", option),
+                en = format!("`#[new(const)]` cannot be combined with the `{}` option, trait methods cannot be called in a `const fn`", option)
+            );
+            return Err(diagnostics::error_spanned("PT0205", field, msg));
+        }
+        let order = options.order()?;
+        if let (Some(_), Some(order)) = (&options.default, &options.order) {
+            let msg = lang_tr!(cn = "`order` 只能用于作为参数的字段", en = "`order` can only be used on fields that are constructor parameters");
+            return Err(diagnostics::error_spanned("PT0206", order, msg));
+        }
+        let doc = doc_lines(&field.attrs).join(" ");
+        let param_doc = if doc.is_empty() { format!("- `{}`", param) } else { format!("- `{}`：{}", param, doc) };
+        match options.default {
+            Some(default) => inits.push(quote! { #member: #default }),
+            None if options.clone => {
+                params.push((order, field, quote! { #param: &#ty }, param_doc));
+                inits.push(quote! { #member: ::core::clone::Clone::clone(#param) });
             }
-        } else if let TokenTree::Group(group) = token {
-            if group.delimiter() == Delimiter::Brace {
-                // 解析字段
-                let mut field_tokens = group.stream().into_iter();
-                let mut current_field = None;
-
-                while let Some(token) = field_tokens.next() {
-                    if let TokenTree::Ident(ident) = token {
-                        current_field = Some(ident.to_string());
-                    } else if let TokenTree::Punct(punct) = &token {
-                        if punct.as_char() == ':' {
-                            // 开始解析类型
-                            let mut type_tokens = Vec::new();
-                            while let Some(token) = field_tokens.next() {
-                                if let TokenTree::Punct(punct) = &token {
-                                    if punct.as_char() == ',' {
-                                        break;
-                                    }
-                                }
-                                type_tokens.push(token.to_string());
-                            }
-
-                            if let Some(field) = current_field.take() {
-                                let field_type = type_tokens.join(" ");
-                                fields.push((field, field_type));
-                            }
-                        }
-                    }
+            None if options.into || struct_options.into => {
+                params.push((order, field, quote! { #param: impl ::core::convert::Into<#ty> }, param_doc));
+                inits.push(quote! { #member: ::core::convert::Into::into(#param) });
+            }
+            None => {
+                params.push((order, field, quote! { #param: #ty }, param_doc));
+                inits.push(quote! { #member: #param });
+            }
+        }
+    }
+    // 指定 `order` 时所有参数都需要指定，避免未指定的参数随字段增删改变位置
+    let ordered = params.iter().filter(|(order, ..)| order.is_some()).count();
+    if let Some((_, field, ..)) = params.iter().find(|(order, ..)| order.is_none()).filter(|_| ordered > 0) {
+        let msg = lang_tr!(
+            cn = "有字段指定了 `#[new(order = N)]` 时，所有作为参数的字段都需要指定 `order`",
+            en = "When any field specifies `#[new(order = N)]`, every field that is a constructor parameter must specify `order`"
+        );
+        return Err(diagnostics::error_spanned("PT0207", field, msg));
+    }
+    params.sort_by_key(|(order, ..)| *order);
+    if let Some(pair) = params.windows(2).find(|pair| pair[0].0 == pair[1].0 && pair[0].0.is_some()) {
+        let msg = lang_tr!(cn = "重复的参数顺序 `order`", en = "Duplicate parameter `order`");
+        return Err(diagnostics::error_spanned("PT0208", pair[1].1, msg));
+    }
+    let (params, param_docs): (Vec<_>, Vec<_>) = params.into_iter().map(|(_, _, param, doc)| (param, doc)).unzip();
+    let constness = struct_options.constness.then(|| quote! { const });
+    let (output, body) = match &struct_options.validate {
+        Some((validate, error)) => (
+            quote! { ::core::result::Result<Self, #error> },
+            quote! {
+                let value = #path { #(#inits),* };
+                match #validate(&value) {
+                    ::core::result::Result::Ok(()) => ::core::result::Result::Ok(value),
+                    ::core::result::Result::Err(err) => ::core::result::Result::Err(err),
                 }
-                break;
+            },
+        ),
+        None => (quote! { Self }, quote! { #path { #(#inits),* } }),
+    };
+    let vis = match &struct_options.vis {
+        Some(Some(vis)) => vis,
+        Some(None) => input_vis,
+        None => &syn::parse_quote! { pub },
+    };
+    if docs.is_empty() {
+        docs.push(format!("创建 [`{}`]", target));
+    }
+    if !param_docs.is_empty() {
+        docs.extend([String::new(), String::from("# 参数")]);
+        docs.extend(param_docs);
+    }
+    if let Some((validate, _)) = &struct_options.validate {
+        docs.extend([String::new(), String::from("# 错误"), format!("构造出的值未通过 `{}` 的检查时返回其错误", quote! { #validate })]);
+    }
+
+    Ok(quote! {
+        #(#[doc = #docs])*
+        #vis #constness fn #fn_name(#(#params),*) -> #output {
+            #body
+        }
+    })
+}
+
+/// 将大驼峰命名转换为蛇形命名，如 `KeyPressed` 转换为 `key_pressed`，`HTTPError` 转换为 `http_error`
+fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut snake = String::with_capacity(name.len() + 4);
+    for (idx, &ch) in chars.iter().enumerate() {
+        if ch.is_uppercase() && idx > 0 {
+            let prev = chars[idx - 1];
+            let next_lower = chars.get(idx + 1).is_some_and(|next| next.is_lowercase());
+            if prev != '_' && (prev.is_lowercase() || prev.is_ascii_digit() || (prev.is_uppercase() && next_lower)) {
+                snake.push('_');
             }
         }
+        snake.extend(ch.to_lowercase());
     }
+    snake
+}
 
-    if let Some(struct_name) = struct_name {
-        // 生成 new 函数
-        let mut code = format!("impl {} {{\n", struct_name);
-        code.push_str("    pub fn new(");
+/// 判断类型是否为 `PhantomData<T>`，包括 `core::marker::PhantomData<T>` 等完整路径
+pub(crate) fn is_phantom_data(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(path) => path.qself.is_none() && path.path.segments.last().is_some_and(|segment| segment.ident == "PhantomData"),
+        _ => false,
+    }
+}
 
-        // 添加参数
-        for (i, (name, ty)) in fields.iter().enumerate() {
-            if i > 0 {
-                code.push_str(", ");
+/// 取出 `///` 文档注释的各行，去掉 `///` 后的首个空格
+pub(crate) fn doc_lines(attrs: &[syn::Attribute]) -> Vec<String> {
+    attrs
+        .iter()
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(syn::MetaNameValue { path, value: Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(doc), .. }), .. }) if path.is_ident("doc") => {
+                let doc = doc.value();
+                Some(doc.strip_prefix(' ').map(str::to_string).unwrap_or(doc))
             }
-            code.push_str(&format!("{}: {}", name, ty));
+            _ => None,
+        })
+        .collect()
+}
+
+/// 结构体上的 `#[new(...)]` 选项
+#[derive(Default)]
+struct StructOptions {
+    /// 所有参数都接受 `impl Into<字段类型>`
+    into: bool,
+    /// 生成 `const fn`
+    constness: bool,
+    /// 构造函数的可见性，默认为 `pub`
+    /// - `#[new(vis = "pub(crate)")]` 为给定的可见性，`#[new(vis)]` 与结构体的可见性相同（记为 `Some(None)`）
+    vis: Option<Option<syn::Visibility>>,
+    /// 构造函数的名称，默认为 `new`
+    name: Option<syn::Ident>,
+    /// 另外生成委托给 `new` 的 `Default` 实现
+    derive_default: bool,
+    /// 构造后调用的检查函数 `fn(&Self) -> Result<(), 错误类型>` 及错误类型
+    validate: Option<(syn::Path, syn::Type)>,
+}
+
+impl StructOptions {
+    fn parse(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut options = StructOptions::default();
+        let (mut validate, mut error) = (None, None);
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("new")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("into") {
+                    options.into = true;
+                    return Ok(());
+                }
+                if meta.path.is_ident("const") {
+                    options.constness = true;
+                    return Ok(());
+                }
+                if meta.path.is_ident("vis") {
+                    options.vis = Some(if meta.input.peek(syn::Token![=]) {
+                        Some(meta.value()?.parse::<syn::LitStr>()?.parse::<syn::Visibility>()?)
+                    } else {
+                        None
+                    });
+                    return Ok(());
+                }
+                if meta.path.is_ident("name") {
+                    options.name = Some(meta.value()?.parse::<syn::LitStr>()?.parse::<syn::Ident>()?);
+                    return Ok(());
+                }
+                if meta.path.is_ident("derive_default") {
+                    options.derive_default = true;
+                    return Ok(());
+                }
+                if meta.path.is_ident("validate") {
+                    validate = Some(meta.value()?.parse::<syn::LitStr>()?.parse::<syn::Path>()?);
+                    return Ok(());
+                }
+                if meta.path.is_ident("error") {
+                    error = Some(meta.value()?.parse::<syn::LitStr>()?.parse::<syn::Type>()?);
+                    return Ok(());
+                }
+                Err(diagnostics::unknown_option("PT0211", &meta, "`into`、`const`、`vis`、`validate`、`error`、`derive_default`、`name`",
+                    "`into`, `const`, `vis`, `validate`, `error`, `derive_default` or `name`"))
+            })?;
         }
+        options.validate = match (validate, error) {
+            (Some(validate), Some(error)) => Some((validate, error)),
+            (None, None) => None,
+            (Some(validate), None) => {
+                let msg = lang_tr!(
+                    cn = "`validate` 需要同时用 `error = \"错误类型\"` 指定检查函数的错误类型",
+                    en = "`validate` requires `error = \"Type\"` naming the error type of the validation function"
+                );
+                return Err(diagnostics::error_spanned("PT0209", validate, msg));
+            }
+            (None, Some(error)) => {
+                let msg = lang_tr!(cn = "`error` 只能与 `validate` 一起使用", en = "`error` can only be used together with `validate`");
+                return Err(diagnostics::error_spanned("PT0210", error, msg));
+            }
+        };
+        Ok(options)
+    }
+
+    /// 构造函数的名称，枚举变体的构造函数以此为前缀
+    fn name(&self) -> syn::Ident {
+        self.name.clone().unwrap_or_else(|| format_ident!("new"))
+    }
+}
 
-        code.push_str(") -> Self {\n");
-        code.push_str("        Self {\n");
+/// 字段上的 `#[new(...)]` 选项
+#[derive(Default)]
+struct FieldOptions {
+    /// 字段不作为参数，以该表达式初始化
+    /// - `#[new(default)]` 为 `Default::default()`，`#[new(default = "Vec::new()")]` 为给定的表达式
+    default: Option<proc_macro2::TokenStream>,
+    /// 参数接受 `impl Into<字段类型>`
+    into: bool,
+    /// 是否为不带表达式的 `#[new(default)]`
+    default_trait: bool,
+    /// 参数在构造函数中的位置，从小到大排列
+    order: Option<syn::LitInt>,
+    /// 参数接受 `&字段类型`，在构造函数中克隆，优先于结构体上的 `#[new(into)]`
+    clone: bool,
+}
 
-        // 添加字段初始化
-        for (name, _) in &fields {
-            code.push_str(&format!("            {},\n", name));
+impl FieldOptions {
+    fn parse(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut options = FieldOptions::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("new")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("default") {
+                    options.default_trait = !meta.input.peek(syn::Token![=]);
+                    options.default = Some(if options.default_trait {
+                        quote! { ::core::default::Default::default() }
+                    } else {
+                        let expr = meta.value()?.parse::<syn::LitStr>()?.parse::<Expr>()?;
+                        quote! { #expr }
+                    });
+                    return Ok(());
+                }
+                if meta.path.is_ident("into") {
+                    options.into = true;
+                    return Ok(());
+                }
+                if meta.path.is_ident("order") {
+                    options.order = Some(meta.value()?.parse::<syn::LitInt>()?);
+                    return Ok(());
+                }
+                if meta.path.is_ident("clone") {
+                    options.clone = true;
+                    return Ok(());
+                }
+                Err(diagnostics::unknown_option("PT0211", &meta, "`default`、`into`、`order`、`clone`", "`default`, `into`, `order` or `clone`"))
+            })?;
         }
+        Ok(options)
+    }
 
-        code.push_str("        }\n");
-        code.push_str("    }\n");
-        code.push_str("}\n");
+    /// `#[new(order = N)]` 的值
+    fn order(&self) -> syn::Result<Option<usize>> {
+        self.order.as_ref().map(|order| order.base10_parse::<usize>()).transpose()
+    }
 
-        code.parse().unwrap_or_else(|_| {
-            panic!("{}", lang_tr!(cn = "解析生成的代码失败", en = "Failed to parse generated code"))
-        })
-    } else {
-        panic!("{}", lang_tr!(cn = "解析生成的代码失败", en = "Failed to parse generated code"))
+    /// 与 `#[new(clone)]` 冲突的字段选项
+    fn clone_conflict(&self) -> Option<&'static str> {
+        if self.default.is_some() {
+            Some("default")
+        } else if self.into {
+            Some("into")
+        } else {
+            None
+        }
+    }
+
+    /// 与 `#[new(const)]` 冲突的选项，`struct_into` 为结构体上是否有 `#[new(into)]`
+    fn const_conflict(&self, struct_into: bool) -> Option<&'static str> {
+        if self.default_trait {
+            Some("default")
+        } else if self.default.is_none() && self.clone {
+            Some("clone")
+        } else if self.default.is_none() && (self.into || struct_into) {
+            Some("into")
+        } else {
+            None
+        }
     }
-}
\ No newline at end of file
+}
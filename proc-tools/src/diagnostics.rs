@@ -0,0 +1,43 @@
+//! 所有宏共用的编译错误构造
+//! - 错误信息由调用方通过 `lang_tr!` 按语言特性选择，这里在信息前加上错误码，如 `[PT0101] 不支持的类型`，
+//!   便于在文档和问题报告中引用，不随语言变化
+//! - 错误码按宏分段：`PT00xx` 为 `concat_vars!` 系列，`PT01xx` 为 `ByteEncode`，`PT02xx` 为 `New`，
+//!   `PT03xx` 为 `Builder`，`PT04xx` 为 `Getters`、`Setters`、`WithMethods`
+//! - 错误总是指向相关的语法元素，没有合适的元素时指向宏调用处
+//! - 常量求值中的 panic（如 `concat_vars.rs` 中 `I_SIZE`、`U_SIZE` 不支持的指针宽度）不能调用 [`message`]，
+//!   需在 `lang_tr!` 的各语言信息中手写错误码前缀，这类错误统一使用 `PT0000`
+//! - 放在 `proc-tools` 而不是 `proc-tools-helper` 中：过程宏 crate 只能导出宏，不能导出供其他 crate 调用的函数
+
+use proc_tools_helper::lang_tr;
+use quote::{ToTokens, quote};
+use std::fmt::Display;
+
+/// 生成带错误码的信息
+#[inline]
+pub(crate) fn message(code: &str, msg: impl Display) -> String {
+    format!("[{}] {}", code, msg)
+}
+
+/// 生成指向 `tokens` 的带错误码的错误
+#[inline]
+pub(crate) fn error_spanned(code: &str, tokens: impl ToTokens, msg: impl Display) -> syn::Error {
+    syn::Error::new_spanned(tokens, message(code, msg))
+}
+
+/// 生成指向 `span` 的带错误码的错误
+#[inline]
+pub(crate) fn error(code: &str, span: proc_macro2::Span, msg: impl Display) -> syn::Error {
+    syn::Error::new(span, message(code, msg))
+}
+
+/// 生成指向选项名称的未知选项错误，错误码由各宏按自己的分段传入
+/// - `_expected_cn`、`_expected_en` 为可用选项的列表，只会用到当前语言的一个
+pub(crate) fn unknown_option(code: &str, meta: &syn::meta::ParseNestedMeta, _expected_cn: &str, _expected_en: &str) -> syn::Error {
+    let path = &meta.path;
+    let key = quote! { #path }.to_string();
+    let msg = lang_tr!(
+        cn = format!("未知的选项 `{}`，可用选项：{}", key, _expected_cn),
+        en = format!("Unknown option `{}`, expected {}", key, _expected_en)
+    );
+    error_spanned(code, &meta.path, msg)
+}
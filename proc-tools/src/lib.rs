@@ -1,9 +1,16 @@
 mod concat_vars;
+mod derive_builder;
 mod derive_byte_encode;
+mod derive_getset;
 mod derive_nwe;
+mod diagnostics;
 
-use crate::concat_vars::concat_vars_implement;
+use crate::concat_vars::{
+    concat_vars_bytes_implement, concat_vars_implement, concat_vars_into_implement, concat_vars_write_implement, try_concat_vars_implement,
+};
+use crate::derive_builder::derive_builder_implement;
 use crate::derive_byte_encode::byte_encode_implement;
+use crate::derive_getset::{derive_getters_implement, derive_setters_implement, derive_with_methods_implement};
 use crate::derive_nwe::derive_new_implement;
 use proc_macro::TokenStream;
 
@@ -12,7 +19,7 @@ use proc_macro::TokenStream;
 fn lang_verify() {}
 
 #[allow(dead_code)]
-#[cfg(any(feature = "lang-en"))]
+#[cfg(feature = "lang-en")]
 fn lang_verify() {}
 
 // 防止两个特性同时启用（编译时报错）
@@ -29,9 +36,79 @@ compile_error!("Cannot enable both 'lang_cn' and 'lang_en' features simultaneous
 ///
 /// # 参数
 /// - 支持的类型包括基本类型（整数、浮点数、布尔值等）和字符串
+/// - 支持直接传入字符串字面量，其长度在编译期确定，无需先绑定到变量
+/// - 整数、浮点数、字符、布尔值字面量可不指定类型与其他参数混用，如 `concat_vars!(base: String, 42, '/', suffix: &str)`
+///   - 字面量在编译期按其类型（带后缀时按后缀，否则整数为 `i32`、浮点数为 `f64`）转换为文本，长度直接计入预分配的容量
+/// - 参数可以是任意表达式，如字段访问 `user.name`、方法调用 `items.len()`，每个表达式只求值一次，且以引用方式使用，不会移动参数
+///   - 所有参数在宏生成的其他语句之前按从左到右的顺序求值，临时变量具有卫生性，不会与 `total_len` 等同名变量冲突
+///   - 有副作用的表达式（如 `counter.fetch_add(1, Ordering::Relaxed)`）出现几次就求值几次，相同的变量或字段访问只绑定一次
+/// - 其他实现了 `Display` 的类型可注解为 `display`，如 `addr: display`，格式化在栈上缓冲区中完成，结果过长时才使用堆内存
+///   - 格式化可能失败的参数可注解为 `try_display`，需使用 [`try_concat_vars!`]
+/// - 已编码为 UTF-8 的字节序列（`&[u8]`、`Vec<u8>` 等实现了 `AsRef<[u8]>` 的类型）可注解为 `raw_bytes`，原样复制，不做 `str` 转换检查
+///   - 由调用方保证内容是有效的 UTF-8，因此宏调用必须写在 `unsafe` 块中，如 `unsafe { concat_vars!("data=", cached: raw_bytes) }`
+///   - 调试构建中会校验 UTF-8，不合法时 panic
+/// - 时间戳可注解为 `timestamp`，值可以是 Unix 秒数 `u64`、自纪元起的 `Duration` 或 `SystemTime`，使用固定大小的栈上缓冲区格式化
+///   - 默认输出 RFC3339（UTC），如 `2023-11-14T22:13:20Z`，`Duration`/`SystemTime` 额外输出毫秒，如 `2023-11-14T22:13:20.123Z`
+///   - `ts: timestamp as millis` 输出毫秒时间戳，如 `1700000000123`
+/// - 时长可注解为 `Duration`，默认以秒为单位输出 3 位小数，如 `1.234s`，直接按整数运算格式化，不经过 `f64`
+///   - `as ms`、`as us` 以毫秒、微秒为单位输出 3 位小数，如 `1234.567ms`，`as ns` 输出整数纳秒，如 `1234567890ns`
+///   - `Instant` 的耗时可直接写为表达式，如 `start.elapsed(): Duration as ms`
+/// - 网络地址可注解为 `Ipv4Addr`、`Ipv6Addr`、`IpAddr`、`SocketAddrV4`、`SocketAddrV6`、`SocketAddr`，
+///   在固定大小的栈上缓冲区中格式化，结果与标准库的 `Display` 一致，如 `peer: SocketAddr`
+/// - `NonZeroU32`、`NonZero<u64>` 等非零整数类型，以及 `AtomicU64`、`AtomicBool` 等原子类型可直接作为类型注解，按对应的基本类型输出
+///   - 原子类型默认以 `Relaxed` 读取，可写为 `AtomicU64<Acquire>` 或 `AtomicU64<SeqCst>` 指定内存序
+/// - 类型注解支持引用和智能指针，如 `&i32`、`&&str`、`Box<str>`、`Rc<String>`、`Arc<f64>`、`Cow<str>`，生成的代码会自动解引用
+/// - 指定类型的参数可在类型后追加对齐说明：`>` 右对齐、`<` 左对齐、`^` 居中，随后是宽度和可选的填充字符
+///   - 例如 `age: i32 > 8`、`name: str < 20`、`id: u32 > 6 '0'`
+///   - 宽度按字符数计算，默认填充空格，值超出宽度时原样输出
+/// - 指定类型的参数可在类型后追加渲染模式 `as 模式`，位于对齐说明之前
+///   - `as hex`：整数以小写十六进制输出，如 `id: u32 as hex`
+///   - `as bin`：整数以二进制输出，如 `flags: u8 as bin`
+///   - 有符号整数按补码输出，与标准库 `format!("{:x}")` 一致
+///   - `as grouped`：整数每三位插入千位分隔符，默认为 `,`，可在模式后指定分隔字符，如 `count: u64 as grouped '_'`
+///   - `as locale(de)`：数值按地区格式输出，小数点替换为该地区的符号，如 `price: f64 as locale(de) . 2` 输出 `1234,50`
+///     - 追加 `grouped` 同时插入该地区的千位分隔符，如 `as locale(de, grouped)` 输出 `1.234,50`
+///     - 支持的地区：`en`、`zh`、`ch`、`de`、`es`、`it`、`nl`、`pt`、`fr`、`ru`，在编译期确定
+///   - `as json`：写入时按 JSON 字符串内容转义双引号、反斜杠和控制字符（不添加两侧引号），如 `msg: str as json`
+///   - `as csv`：写入时按 CSV 字段规则处理，包含 `,`、`"` 或换行时两侧加引号并将 `"` 写为 `""`
+///   - `as upper`/`as lower`：写入时将 ASCII 字母转为大写/小写，非 ASCII 字符不变，如 `header: str as lower`
+///   - `as hexdump`：将字节序列（`&[u8]`、`Vec<u8>`、`[u8; N]` 等实现了 `AsRef<[u8]>` 的类型）以小写十六进制输出，
+///     可追加 `sep " "` 在字节之间插入分隔符，如 `payload: &[u8] as hexdump sep " "`
+///   - `json`、`csv`、`upper`、`lower` 可用于任意类型，转义后的长度在预分配时精确计算，不需要先转义到临时字符串
+/// - 列表参数以 `[元素类型]` 注解，逐个连接元素，可追加 `sep 分隔符` 在元素之间插入分隔符
+///   - 例如 `ids: [u32] sep ","`、`names: [&str] sep ", "`、`flags: [u8] as hex sep " "`
+///   - 参数需提供返回元素引用的 `iter()` 方法，如切片、数组、`Vec`，元素直接写入结果，不会产生中间字符串
+///   - 元素类型为 `_` 时与未指定类型的参数相同，自动推断
+/// - 定长数组以 `[元素类型; 长度]` 注解，与列表参数相同，如 `rgba: [u8; 4] sep "."`
+/// - 元组以 `(类型, ...)` 注解，按顺序连接各元素，同样可追加 `sep 分隔符`，如 `(x, y, z): (f32, f32, f32) sep ","`
+///   - 参数为元组表达式时各元素分别绑定，不会移动其中的变量；模式、小数位数和对齐作用于每个元素
+/// - 条件参数组 `if 条件 { 参数, ... }` 只在条件成立时连接其中的参数，如 `if debug_on { " detail=", detail: &str }`
+///   - 条件只求值一次，组内的参数表达式只在条件成立时求值，不成立时既不计入容量也不写入
+///   - 指定了 `sep` 选项时，被跳过的参数不会留下多余的分隔符
+///   - `max` 选项按条件成立的最坏情况计算
+/// - `f32`/`f64` 参数可在类型后追加 `. 小数位数`，以固定小数位数输出，如 `score: f64 . 2`
+///   - 与 `format!("{:.2}")` 相同，按二进制的精确值舍入，如 `2.675` 输出为 `2.67`
+///
+/// # 选项
+/// - 可在参数列表前写入以 `;` 结尾的选项，多个选项以 `,` 分隔
+/// - `sep = ", "`：在每两个参数之间插入分隔符，分隔符的长度同样计入预分配的容量
+/// - `max = 256`：在编译期按最坏情况计算连接结果的字节数，超过上限时产生编译错误
+///   - 字符串字面量按实际长度计算，数值、字符、布尔值、时间戳、时长、网络地址按其最大输出长度计算，对齐按宽度计入填充
+///   - 数组按长度、元组按各元素计算，此时元素分隔符必须是字符串字面量
+///   - 长度没有上限的参数（字符串变量、`display`、`raw_bytes`、列表、未指定类型的变量）不能与 `max` 一起使用
+/// - `tight`：按精确长度预留容量，不保留额外的增长空间，适合长期保存的字符串
+///   - 所有参数（包括未指定类型的参数）都先渲染到栈上缓冲区并计算实际长度，再一次性分配，
+///     因此 `concat_vars!` 的结果容量总是等于长度，该选项主要影响 [`concat_vars_into!`]
+/// - `scratch`：所有参数共用一个栈上缓冲区，大小为单个参数所需的最大字节数，而不是每个参数各占一个缓冲区
+///   - 长度计算与写入时各渲染一次参数，渲染后立即写入结果，适合参数很多、对栈空间敏感的调用
+///   - 与默认方式相比多一次渲染开销，参数较少时不需要使用
 ///
 /// # 返回值
 /// - 返回一个 `String`，包含所有参数连接后的结果
+/// - 可在参数列表末尾以 `=> 类型` 指定结果类型：`String`、`Box<str>`、`Rc<str>`、`Arc<str>`
+///   - 直接按预先计算的长度分配目标类型的内存并写入，没有中间的 `String`，也没有多余的容量
+/// - 所有参数都是未指定类型的字面量（字符串、整数、浮点数、布尔值、字符）且分隔符也是字符串字面量时，
+///   在编译期直接折叠为 `&'static str`，不生成任何运行时代码，可用于 `const` 和 `static`
 ///
 /// # 性能说明
 /// 此宏通过以下方式优化性能：
@@ -43,6 +120,23 @@ compile_error!("Cannot enable both 'lang_cn' and 'lang_en' features simultaneous
 /// - 必须至少提供一个参数
 /// - 宏内部使用不安全代码，但对外提供安全接口
 /// - 需要依赖库：`proc_tools_core`
+/// - 生成的代码只依赖 `core` 和 `alloc`，可在 `#![no_std]` 环境中使用，此时需关闭 `proc_tools_core` 默认的 `std` 特性
+/// - 不支持的类型或渲染模式会产生指向对应参数的编译错误，而不是使宏展开 panic
+/// - 本库所有宏的错误信息以错误码开头，如 `[PT0001]`，`PT00xx` 为 `concat_vars!` 系列，`PT01xx` 为 `ByteEncode`，
+///   `PT02xx` 为 `New`，`PT03xx` 为 `Builder`，`PT04xx` 为 `Getters`、`Setters`、`WithMethods`，错误码不随语言变化
+///
+/// ```compile_fail
+/// use proc_tools::concat_vars;
+/// let bytes = vec![1u8, 2, 3];
+/// let result = concat_vars!("bytes: ", bytes: Vec<u8>);
+/// ```
+///
+/// ```compile_fail
+/// use proc_tools::concat_vars;
+/// // 最坏情况为 "id=" 3 字节 + u64 20 字节，超过了上限
+/// let id = 1u64;
+/// let result = concat_vars!(max = 16; "id=", id: u64);
+/// ```
 ///
 /// # 示例
 /// ```
@@ -62,27 +156,352 @@ compile_error!("Cannot enable both 'lang_cn' and 'lang_en' features simultaneous
 /// /// 内存够用情况，两种方式性能相差不大，不需要太纠结
 /// let result = concat_vars!(name: String, age: i32, score: f64);
 /// assert_eq!(result, "Alice3095.5");
+///
+/// /// 全部为字面量时在编译期折叠为 `&'static str`
+/// const VERSION: &str = concat_vars!(sep = "."; 1, 2, 0);
+/// assert_eq!(VERSION, "1.2.0");
+/// let banner: &'static str = concat_vars!("v", 2, "-", true, '!', -1.5);
+/// assert_eq!(banner, "v2-true!-1.5");
+///
+/// /// 直接生成 `Arc<str>` 或 `Box<str>`
+/// use std::sync::Arc;
+/// let key: Arc<str> = concat_vars!("user:", age: i32, ":", name => Arc<str>);
+/// assert_eq!(&*key, "user:30:Alice");
+/// let boxed = concat_vars!(sep = "/"; name, score: f64 => Box<str>);
+/// assert_eq!(&*boxed, "Alice/95.5");
+///
+/// /// 字符串字面量可以与其他参数混合使用
+/// let result = concat_vars!("name: ", name, ", score: ", score: f64);
+/// assert_eq!(result, "name: Alice, score: 95.5");
+///
+/// /// 使用分隔符连接所有参数
+/// let result = concat_vars!(sep = ", "; name, age: i32, score: f64);
+/// assert_eq!(result, "Alice, 30, 95.5");
+///
+/// /// 编译期校验最坏情况下的长度
+/// let result = concat_vars!(max = 32; "age=", age: i32, ", ok=", true: bool);
+/// assert_eq!(result, "age=30, ok=true");
+///
+/// /// 对齐填充，适合输出对齐的列
+/// let result = concat_vars!("|", name: str < 8, "|", age: i32 > 5, "|", 7: u8 > 3 '0', "|");
+/// assert_eq!(result, "|Alice   |   30|007|");
+///
+/// /// 十六进制与二进制输出
+/// let id = 48879u32;
+/// let flags = 5u8;
+/// let result = concat_vars!("0x", id: u32 as hex, " 0b", flags: u8 as bin > 8 '0');
+/// assert_eq!(result, "0xbeef 0b00000101");
+///
+/// /// JSON 与 CSV 转义
+/// let msg = "say \"hi\"\n";
+/// let result = concat_vars!("{\"msg\":\"", msg: str as json, "\"}");
+/// assert_eq!(result, r#"{"msg":"say \"hi\"\n"}"#);
+/// let result = concat_vars!(sep = ","; "a,b": str as csv, name: str as csv, msg: str as csv);
+/// assert_eq!(result, "\"a,b\",Alice,\"say \"\"hi\"\"\n\"");
+///
+/// /// 字节序列的十六进制输出
+/// let payload: &[u8] = &[0xca, 0xfe, 0x01];
+/// let result = concat_vars!("payload=", payload: &[u8] as hexdump, " [", payload: &[u8] as hexdump sep " ", "]");
+/// assert_eq!(result, "payload=cafe01 [ca fe 01]");
+///
+/// /// 大小写转换
+/// let header = "Content-Type";
+/// let result = concat_vars!(header: str as lower, ": ", "json": str as upper, " ", 255: u8 as upper);
+/// assert_eq!(result, "content-type: JSON 255");
+///
+/// /// 千位分隔符
+/// let count = 1234567u64;
+/// let result = concat_vars!(count: u64 as grouped, " ", -9876543: i32 as grouped '_', " ", 999: u16 as grouped);
+/// assert_eq!(result, "1,234,567 -9_876_543 999");
+///
+/// /// 固定小数位数
+/// let result = concat_vars!(sep = ","; score: f64 . 2, 7.0: f32 . 0, 1.0: f64 . 3 > 8);
+/// assert_eq!(result, "95.50,7,   1.000");
+/// let result = concat_vars!(sep = ","; 1.5: f64 . 2, 1.5: f32 . 1);
+/// assert_eq!(result, "1.50,1.5");
+/// let result = concat_vars!(sep = ","; 2.675: f64 . 2, 123456.785: f64 . 2, 2.675: f32 . 2);
+/// assert_eq!(result, "2.67,123456.79,2.67");
+///
+/// /// 字段访问与方法调用
+/// struct User { name: String, id: u32 }
+/// let user = User { name: String::from("Bob"), id: 7 };
+/// let items = vec![1, 2, 3];
+/// let result = concat_vars!(user.name: String, "#", user.id: u32, " items=", items.len());
+/// assert_eq!(result, "Bob#7 items=3");
+/// assert_eq!(user.name, "Bob");
+///
+/// /// 引用与智能指针
+/// use std::borrow::Cow;
+/// use std::rc::Rc;
+/// let id = &42u64;
+/// let tag: Rc<String> = Rc::new(String::from("rc"));
+/// let label: Cow<str> = Cow::Borrowed("cow");
+/// let result = concat_vars!(id: &u64, "-", tag: Rc<String>, "-", label: Cow<str>);
+/// assert_eq!(result, "42-rc-cow");
+///
+/// /// 任意实现了 `Display` 的类型
+/// use std::net::Ipv4Addr;
+/// let addr = Ipv4Addr::new(10, 0, 0, 1);
+/// let result = concat_vars!("addr=", addr: display, " port=", 8080: u16);
+/// assert_eq!(result, "addr=10.0.0.1 port=8080");
+///
+/// /// 非零整数与原子类型
+/// use std::num::NonZeroU32;
+/// use std::sync::atomic::{AtomicBool, AtomicU64};
+/// let id = NonZeroU32::new(7).unwrap();
+/// let hits = AtomicU64::new(1024);
+/// let ready = AtomicBool::new(true);
+/// let result = concat_vars!(sep = " "; id: NonZeroU32, hits: AtomicU64 as hex, hits: AtomicU64<SeqCst> > 6, ready: AtomicBool);
+/// assert_eq!(result, "7 400   1024 true");
+///
+/// /// 时间戳
+/// use std::time::Duration;
+/// let secs = 1_700_000_000u64;
+/// let since_epoch = Duration::from_millis(1_700_000_000_123);
+/// let result = concat_vars!("[", secs: timestamp, "] [", since_epoch: timestamp, "] ", since_epoch: timestamp as millis);
+/// assert_eq!(result, "[2023-11-14T22:13:20Z] [2023-11-14T22:13:20.123Z] 1700000000123");
+///
+/// /// 时长
+/// let elapsed = Duration::from_micros(1_234_567);
+/// let result = concat_vars!("took ", elapsed: Duration, " (", elapsed: Duration as ms, ", ", elapsed: Duration as ns, ")");
+/// assert_eq!(result, "took 1.234s (1234.567ms, 1234567000ns)");
+///
+/// /// 网络地址
+/// use std::net::{IpAddr, SocketAddr};
+/// let peer: SocketAddr = "[2001:db8::8]:443".parse().unwrap();
+/// let local: IpAddr = "10.0.0.1".parse().unwrap();
+/// let result = concat_vars!(local: IpAddr, " <- ", peer: SocketAddr);
+/// assert_eq!(result, "10.0.0.1 <- [2001:db8::8]:443");
+///
+/// /// 列表参数
+/// let ids = vec![3u32, 1, 2];
+/// let names = ["a", "b"];
+/// let result = concat_vars!("ids=", ids: [u32] sep ",", " names=", names: [&str] sep " | ", " hex=", ids: [u32] as hex);
+/// assert_eq!(result, "ids=3,1,2 names=a | b hex=312");
+///
+/// /// 数组与元组参数
+/// let rgba = [255u8, 128, 0, 255];
+/// let (x, y) = (1.5f64, -2.0f64);
+/// let result = concat_vars!("rgba(", rgba: [u8; 4] sep ", ", ") at (", (x, y): (f64, f64) . 1 sep ", ", ")");
+/// assert_eq!(result, "rgba(255, 128, 0, 255) at (1.5, -2.0)");
+///
+/// /// 地区格式
+/// let price = 1234.5f64;
+/// let result = concat_vars!(sep = "; "; price: f64 as locale(de) . 2, price: f64 as locale(de, grouped) . 2, price: f64 as locale(fr, grouped));
+/// assert_eq!(result, "1234,50; 1.234,50; 1 234,5");
+///
+/// /// 条件参数组
+/// let (debug_on, detail, code) = (true, "timeout", 504u16);
+/// let result = concat_vars!(sep = " "; "error", code: u16, if debug_on { "detail:", detail: &str }, if !debug_on { "(quiet)" });
+/// assert_eq!(result, "error 504 detail: timeout");
+///
+/// /// 未指定类型的字面量与变量混用
+/// let (base, suffix) = (String::from("/api/v"), "users");
+/// let result = concat_vars!(base: String, 2, '/', suffix: &str, "?active=", true);
+/// assert_eq!(result, "/api/v2/users?active=true");
+///
+/// /// 拼接预先编码的字节片段
+/// let cached: &[u8] = br#"{"id":7}"#;
+/// // 安全性：`cached` 来自 UTF-8 字面量
+/// let result = unsafe { concat_vars!("{\"user\":", cached: raw_bytes, "}") };
+/// assert_eq!(result, r#"{"user":{"id":7}}"#);
+///
+/// /// 参数较多时共用一个栈上缓冲区
+/// let (status, bytes, latency, peer) = (200u16, 5120u64, Duration::from_micros(830), local);
+/// let result = concat_vars!(scratch, sep = " "; "status=", status: u16, "bytes=", bytes: u64 as grouped, "latency=", latency: Duration as us, "peer=", peer: IpAddr);
+/// assert_eq!(result, "status= 200 bytes= 5,120 latency= 830.000us peer= 10.0.0.1");
 /// ```
 #[proc_macro]
 pub fn concat_vars(input: TokenStream) -> TokenStream {
     concat_vars_implement(input)
 }
 
-/// 自动为结构体生成 `new` 构造函数
+/// 将多个变量高效连接并追加到已有的 `String` 中
+/// - 与 [`concat_vars!`] 的参数规则完全相同，只是第一个参数为目标缓冲区 `&mut String`
+/// - 预先计算所需长度并一次性 `reserve`，随后直接写入缓冲区末尾
+/// - 复用同一个缓冲区时不会产生新的内存分配，适合在循环中构建日志行等场景
+/// - 默认按 `String::reserve` 预留，容量可能翻倍增长；指定 `tight;` 选项时改用 `reserve_exact`，不留多余容量
+///
+/// # 参数
+/// - 第一个参数：类型为 `&mut String` 的目标缓冲区
+/// - 其余参数：与 [`concat_vars!`] 相同，同样支持以 `;` 结尾的选项列表
+///
+/// # 返回值
+/// - 无返回值，结果追加在目标缓冲区原有内容之后
+///
+/// # 示例
+/// ```
+/// use proc_tools::concat_vars_into;
+/// let mut line = String::with_capacity(64);
+/// for id in 1..=2 {
+///     line.clear();
+///     concat_vars_into!(&mut line, "id=", id: i32, ", ok=", true: bool);
+///     assert_eq!(line, if id == 1 { "id=1, ok=true" } else { "id=2, ok=true" });
+/// }
+///
+/// let mut buf = String::from("prefix:");
+/// let name = "Alice";
+/// concat_vars_into!(&mut buf, name, 30: u8);
+/// assert_eq!(buf, "prefix:Alice30");
+///
+/// let mut key = String::from("user:");
+/// concat_vars_into!(&mut key, tight; 1_000_042: u32);
+/// assert_eq!(key.capacity(), key.len());
+/// ```
+#[proc_macro]
+pub fn concat_vars_into(input: TokenStream) -> TokenStream {
+    concat_vars_into_implement(input)
+}
+
+/// 将多个变量连接后直接写入 `std::io::Write` 或 `core::fmt::Write` 目标，不构建 `String`
+/// - 与 [`concat_vars!`] 的参数规则完全相同，只是第一个参数为写入目标，如 `&mut BufWriter<TcpStream>`、`&mut String`、`f`（`Formatter`）
+/// - 结果先在栈上缓冲区中拼接，再以一次 `write_all`/`write_str` 写入目标
+///   - 未指定 `max` 时缓冲区为 256 字节，结果更长时改用一次按精确长度的堆分配
+///   - 指定 `max` 时缓冲区按上限分配，始终不使用堆内存
+/// - 目标同时实现两个 trait 时按 `io::Write` 写入
+///
+/// # 参数
+/// - 第一个参数：写入目标，类型为 `&mut W`，`W` 实现了 `std::io::Write`（需启用 `std` 特性）或 `core::fmt::Write`
+/// - 其余参数：与 [`concat_vars!`] 相同，同样支持以 `;` 结尾的选项列表
+///
+/// # 返回值
+/// - `io::Write` 目标返回 `std::io::Result<()>`，`fmt::Write` 目标返回 `core::fmt::Result`
+///
+/// # 示例
+/// ```
+/// use proc_tools::concat_vars_write;
+/// use std::fmt;
+/// use std::io::{BufWriter, Write};
+///
+/// let mut out = BufWriter::new(Vec::new());
+/// let (id, latency) = (7u32, 1.26f64);
+/// concat_vars_write!(&mut out, "id=", id: u32, " latency=", latency: f64 . 1, "ms\n").unwrap();
+/// concat_vars_write!(&mut out, max = 16; "bytes=", 512: u16).unwrap();
+/// assert_eq!(out.into_inner().unwrap(), b"id=7 latency=1.3ms\nbytes=512");
+///
+/// struct Point(i32, i32);
+/// impl fmt::Display for Point {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         concat_vars_write!(f, "(", self.0: i32, ", ", self.1: i32, ")")
+///     }
+/// }
+/// assert_eq!(Point(3, -4).to_string(), "(3, -4)");
+/// ```
+#[proc_macro]
+pub fn concat_vars_write(input: TokenStream) -> TokenStream {
+    concat_vars_write_implement(input)
+}
+
+/// 将多个变量连接为字节序列 `Vec<u8>`，适合构建 HTTP/1 请求行、Redis 协议帧等按字节处理的数据
+/// - 与 [`concat_vars!`] 的参数规则和选项完全相同，同样预先计算长度并一次性分配
+/// - 结果不要求是有效的 UTF-8，`raw_bytes` 参数可以是任意字节，也不需要写在 `unsafe` 块中
+///
+/// # 参数
+/// - 与 [`concat_vars!`] 相同，不支持 `=> 类型` 指定结果类型
+///
+/// # 返回值
+/// - `Vec<u8>`，容量等于长度
+///
+/// # 示例
+/// ```
+/// use proc_tools::concat_vars_bytes;
+///
+/// let (method, path) = ("GET", "/index.html");
+/// let line = concat_vars_bytes!(method: &str, " ", path: &str, " HTTP/1.1\r\n");
+/// assert_eq!(line, b"GET /index.html HTTP/1.1\r\n");
+///
+/// /// Redis 批量字符串：`$长度\r\n内容\r\n`，内容可以是任意字节
+/// let payload: &[u8] = &[0xff, 0x00, b'k'];
+/// let frame = concat_vars_bytes!("$", payload.len(): usize, "\r\n", payload: raw_bytes, "\r\n");
+/// assert_eq!(frame, b"$3\r\n\xff\x00k\r\n");
+/// ```
+#[proc_macro]
+pub fn concat_vars_bytes(input: TokenStream) -> TokenStream {
+    concat_vars_bytes_implement(input)
+}
+
+/// 可失败的 [`concat_vars!`]，返回 `Result<String, E>`
+/// - 与 [`concat_vars!`] 的参数规则完全相同，额外支持注解为 `try_display` 的参数
+/// - `try_display` 参数通过 `TryConcat` trait 格式化：实现了 `Display` 的类型自动实现，错误类型为 `core::fmt::Error`，
+///   其他类型可自行实现 `proc_tools_core::utils_core::impl_to_ascii::TryConcat` 并返回自定义错误
+/// - 任一参数格式化失败时立即返回该错误，不会 panic，也不会返回部分结果
+///
+/// # 参数
+/// - 与 [`concat_vars!`] 相同，至少需要一个 `try_display` 参数
+/// - 同一次调用中所有 `try_display` 参数的错误类型必须相同
+///
+/// # 返回值
+/// - `Result<String, E>`，`E` 为 `try_display` 参数的错误类型
+/// - 以 `=> 类型` 指定结果类型时返回 `Result<类型, E>`
+///
+/// # 示例
+/// ```
+/// use proc_tools::try_concat_vars;
+/// use proc_tools_core::utils_core::impl_to_ascii::TryConcat;
+/// use std::fmt::Write;
+///
+/// struct Celsius(f64);
+/// impl TryConcat for Celsius {
+///     type Error = String;
+///     fn try_concat<W: Write + ?Sized>(&self, out: &mut W) -> Result<(), String> {
+///         if self.0 < -273.15 {
+///             return Err(format!("{} is below absolute zero", self.0));
+///         }
+///         write!(out, "{:.1}°C", self.0).map_err(|e| e.to_string())
+///     }
+/// }
+///
+/// let ok = try_concat_vars!("sensor ", 3: u8, ": ", Celsius(21.5): try_display);
+/// assert_eq!(ok, Ok(String::from("sensor 3: 21.5°C")));
+/// let err = try_concat_vars!("sensor ", 4: u8, ": ", Celsius(-300.0): try_display);
+/// assert_eq!(err, Err(String::from("-300 is below absolute zero")));
+/// ```
+#[proc_macro]
+pub fn try_concat_vars(input: TokenStream) -> TokenStream {
+    try_concat_vars_implement(input)
+}
+
+/// 自动为结构体生成 `new` 构造函数，为枚举的每个变体生成构造函数
 /// - 该构造函数接收所有字段作为参数并返回结构体实例。
 /// - 生成的函数参数顺序与结构体字段声明顺序一致
 /// - 提供编译时类型安全检查
+/// - 支持命名字段结构体、元组结构体和单元结构体
+///   - 元组结构体的参数依次命名为 `arg0`、`arg1`……，如 `struct Meters(f64)` 生成 `new(arg0: f64)`
+///   - 单元结构体生成无参数的 `new()`
+/// - 枚举为每个变体生成 `new_变体名`，变体名转换为蛇形命名，如 `KeyPressed { code: u32 }` 生成 `new_key_pressed(code: u32)`
+///   - 变体的字段规则与结构体相同，结构体选项和字段选项同样适用
+///   - 变体的文档注释复制到对应的构造函数上
+/// - `PhantomData<T>` 类型的字段不作为参数，自动以 `PhantomData` 初始化
+/// - 支持泛型结构体，类型参数、生命周期参数、常量泛型及其约束和 `where` 子句会带入生成的 `impl` 块
+///   - 包含引用的结构体同样适用，如 `struct View<'a> { data: &'a [u8] }` 生成 `impl<'a> View<'a>`
+/// - 结构体的文档注释会复制到 `new` 上（没有时为“创建 `类型名`”），并追加 `# 参数` 一节，
+///   按顺序列出各参数及对应字段的文档注释
 ///
-/// # 限制
-/// - 不支持泛型参数
-/// - 不支持生命周期参数
-/// - 不支持字段的默认值或可选参数
-/// - 不支持文档注释的保留
+/// # 结构体选项
+/// - `#[new(into)]`：所有参数都接受 `impl Into<字段类型>`，如 `String` 字段可直接传入 `&str`
+/// - `#[new(const)]`：生成 `pub const fn new`，可用于 `const` 和 `static` 的初始化
+///   - 不能与 `into` 和不带表达式的 `default` 一起使用，`default = "表达式"` 的表达式需能在常量上下文中求值
+/// - `#[new(vis = "pub(crate)")]`：指定构造函数的可见性，默认为 `pub`，`vis = ""` 为私有
+///   - `#[new(vis)]`：与结构体自身的可见性相同
+/// - `#[new(validate = "函数路径", error = "错误类型")]`：构造出值后以其引用调用 `fn(&Self) -> Result<(), 错误类型>`，
+///   构造函数改为返回 `Result<Self, 错误类型>`，用于检查非空、取值范围等不变量
+/// - `#[new(name = "create")]`：将构造函数命名为 `create`，用于与手写的 `new` 并存；枚举变体的构造函数以此为前缀，如 `create_closed`
+/// - `#[new(derive_default)]`：另外生成委托给 `new` 的 `Default` 实现，字段的默认值只需在 `#[new(default = ...)]` 中写一次
+///   - 作为参数的字段传入其类型的 `Default::default()`，不能与 `validate` 一起使用
+///
+/// # 字段选项
+/// - `#[new(default)]`：字段不作为参数，以 `Default::default()` 初始化
+/// - `#[new(default = "表达式")]`：字段不作为参数，以给定的表达式初始化，如 `#[new(default = "Vec::with_capacity(16)")]`
+/// - `#[new(into)]`：该参数接受 `impl Into<字段类型>`
+/// - `#[new(clone)]`：该参数接受 `&字段类型`，在构造函数中克隆，适用于调用方构造后仍需使用的 `Arc<T>`、`String` 等
+///   - 优先于结构体上的 `#[new(into)]`，不能与字段上的 `default`、`into` 一起使用
+/// - `#[new(order = N)]`：参数按 `N` 从小到大排列，而不是按字段声明顺序，字段增删或调整位置时参数的位置保持不变
+///   - 有字段指定 `order` 时，所有作为参数的字段都需要指定，且 `N` 不能重复
 ///
 /// # 示例
 /// 对于以下结构体：
 /// ```ignore
-/// #[derive_new]
+/// #[derive(New)]
 /// struct Point {
 ///     x: f64,
 ///     y: f64,
@@ -97,35 +516,498 @@ pub fn concat_vars(input: TokenStream) -> TokenStream {
 ///     }
 /// }
 /// ```
-#[proc_macro_derive(New)]
+///
+/// 泛型结构体：
+/// ```
+/// use proc_tools::New;
+///
+/// #[derive(New)]
+/// struct Ring<T, const N: usize>
+/// where
+///     T: Copy,
+/// {
+///     items: [T; N],
+///     head: usize,
+/// }
+///
+/// let ring = Ring::new([1u8, 2, 3], 1);
+/// assert_eq!(ring.items[ring.head], 2);
+///
+/// /// `PhantomData` 字段自动初始化
+/// use std::marker::PhantomData;
+/// #[derive(New)]
+/// struct Id<T> {
+///     raw: u64,
+///     _kind: PhantomData<T>,
+/// }
+///
+/// let id: Id<String> = Id::new(42);
+/// assert_eq!(id.raw, 42);
+///
+/// /// 字段类型中含逗号、字段带属性和可见性修饰时同样按字段解析
+/// use std::collections::HashMap;
+/// #[derive(New)]
+/// pub struct Registry {
+///     #[allow(dead_code)]
+///     pub(crate) names: HashMap<String, u32>,
+///     pub hooks: Vec<(fn(u32) -> u32, &'static str)>,
+/// }
+///
+/// let registry = Registry::new(HashMap::from([(String::from("a"), 1)]), vec![(|x| x + 1, "inc")]);
+/// assert_eq!((registry.names["a"], (registry.hooks[0].0)(1)), (1, 2));
+///
+/// /// 借用数据的结构体，生命周期约束同样保留
+/// #[derive(New)]
+/// struct View<'a, 'b: 'a, T: ?Sized> {
+///     data: &'a [u8],
+///     label: &'b T,
+/// }
+///
+/// let bytes = [1u8, 2, 3];
+/// let view = View::new(&bytes[1..], "tail");
+/// assert_eq!((view.data, view.label), (&[2u8, 3][..], "tail"));
+///
+/// /// 枚举的每个变体各有一个构造函数
+/// #[derive(New, Debug, PartialEq)]
+/// enum Event {
+///     KeyPressed { code: u32, shift: bool },
+///     Resized(u16, u16),
+///     Closed,
+/// }
+///
+/// assert_eq!(Event::new_key_pressed(13, false), Event::KeyPressed { code: 13, shift: false });
+/// assert_eq!(Event::new_resized(80, 24), Event::Resized(80, 24));
+/// assert_eq!(Event::new_closed(), Event::Closed);
+///
+/// /// 簿记字段使用默认值，不出现在参数中
+/// #[derive(New)]
+/// struct Session {
+///     user: String,
+///     port: u16,
+///     #[new(default)]
+///     hits: u64,
+///     #[new(default = "Vec::with_capacity(4)")]
+///     history: Vec<String>,
+/// }
+///
+/// let session = Session::new(String::from("alice"), 8080);
+/// assert_eq!((session.user.as_str(), session.port, session.hits), ("alice", 8080, 0));
+/// assert!(session.history.is_empty() && session.history.capacity() >= 4);
+///
+/// /// 参数接受 `impl Into<字段类型>`
+/// use std::path::PathBuf;
+/// #[derive(New)]
+/// #[new(into)]
+/// struct Mount {
+///     name: String,
+///     path: PathBuf,
+/// }
+///
+/// let mount = Mount::new("data", "/var/lib/data");
+/// assert_eq!((mount.name.as_str(), mount.path.to_str()), ("data", Some("/var/lib/data")));
+///
+/// /// 元组结构体与单元结构体
+/// #[derive(New)]
+/// struct Pair(u32, #[new(default)] Vec<u8>, &'static str);
+/// #[derive(New)]
+/// struct Marker;
+///
+/// let pair = Pair::new(7, "seven");
+/// assert_eq!((pair.0, pair.1.len(), pair.2), (7, 0, "seven"));
+/// let _marker: Marker = Marker::new();
+///
+/// /// 生成 `const fn`，用于静态配置表
+/// #[derive(New)]
+/// #[new(const)]
+/// struct Route {
+///     path: &'static str,
+///     port: u16,
+///     #[new(default = "None")]
+///     alias: Option<&'static str>,
+/// }
+///
+/// static ROUTES: [Route; 2] = [Route::new("/api", 8080), Route::new("/admin", 9090)];
+/// assert_eq!((ROUTES[1].path, ROUTES[1].port, ROUTES[1].alias), ("/admin", 9090, None));
+///
+/// /// 构造函数只在 crate 内可见
+/// mod pool {
+///     #[derive(proc_tools::New)]
+///     #[new(vis = "pub(crate)")]
+///     pub struct Slot(pub usize);
+/// }
+/// assert_eq!(pool::Slot::new(3).0, 3);
+///
+/// /// 构造后检查不变量
+/// #[derive(New, Debug)]
+/// #[new(validate = "Listener::check", error = "&'static str")]
+/// struct Listener {
+///     name: String,
+///     port: u16,
+/// }
+///
+/// impl Listener {
+///     fn check(&self) -> Result<(), &'static str> {
+///         if self.name.is_empty() {
+///             return Err("name must not be empty");
+///         }
+///         if self.port < 1024 {
+///             return Err("port must be at least 1024");
+///         }
+///         Ok(())
+///     }
+/// }
+///
+/// assert_eq!(Listener::new(String::from("web"), 8080).unwrap().port, 8080);
+/// assert_eq!(Listener::new(String::new(), 8080).unwrap_err(), "name must not be empty");
+/// assert_eq!(Listener::new(String::from("web"), 80).unwrap_err(), "port must be at least 1024");
+///
+/// /// 同时生成 `Default`，与 `new` 共用字段的默认值
+/// #[derive(New)]
+/// #[new(derive_default)]
+/// struct Retry {
+///     attempts: u32,
+///     #[new(default = "std::time::Duration::from_millis(500)")]
+///     backoff: std::time::Duration,
+/// }
+///
+/// let retry = Retry::default();
+/// assert_eq!((retry.attempts, retry.backoff.as_millis()), (0, 500));
+///
+/// /// 与手写的 `new` 并存
+/// #[derive(New)]
+/// #[new(name = "with_all")]
+/// struct Buffer {
+///     data: Vec<u8>,
+///     limit: usize,
+/// }
+///
+/// impl Buffer {
+///     fn new() -> Self {
+///         Self::with_all(Vec::new(), 4096)
+///     }
+/// }
+///
+/// assert_eq!(Buffer::new().limit, 4096);
+/// assert_eq!(Buffer::with_all(vec![1], 8).data, [1]);
+///
+/// /// 显式指定参数顺序
+/// #[derive(New)]
+/// struct Span {
+///     #[new(default)]
+///     label: String,
+///     #[new(order = 1)]
+///     end: usize,
+///     #[new(order = 0)]
+///     start: usize,
+/// }
+///
+/// let span = Span::new(3, 8);
+/// assert_eq!((span.start, span.end, span.label.as_str()), (3, 8, ""));
+///
+/// /// 共享的字段传引用，构造函数内部克隆
+/// use std::sync::Arc;
+/// #[derive(New)]
+/// struct Worker {
+///     id: u32,
+///     #[new(clone)]
+///     queue: Arc<Vec<u32>>,
+/// }
+///
+/// let queue = Arc::new(vec![1, 2, 3]);
+/// let workers = [Worker::new(0, &queue), Worker::new(1, &queue)];
+/// assert_eq!((Arc::strong_count(&queue), workers[1].id, workers[1].queue.len()), (3, 1, 3));
+/// ```
+#[proc_macro_derive(New, attributes(new))]
 pub fn derive_new(input: TokenStream) -> TokenStream {
     derive_new_implement(input)
 }
 
-/// 为结构体自动派生固定大小字节编码/解码实现的过程宏
+/// 为命名字段结构体生成构建器 `类型名Builder`，适合字段多、可选字段多，不便使用按位置传参的 `new` 的结构体
+/// - `类型名::builder()` 创建所有字段均未设置的构建器，与字段同名的方法设置字段并返回构建器，可以链式调用
+/// - `build()` 返回 `Result<类型, BuildError>`，必填字段未设置时返回 `proc_tools_core::utils_core::builder::BuildError`
+/// - 构建器、`builder()` 和各方法的可见性与结构体相同，支持泛型结构体
+///
+/// # 字段规则
+/// - 类型为 `Option<T>` 的字段是可选的，设置方法接收 `T`，未设置时为 `None`
+/// - `#[builder(default)]`：未设置时以 `Default::default()` 初始化
+/// - `#[builder(default = "表达式")]`：未设置时以给定的表达式初始化，表达式只在字段未设置时求值
+/// - `PhantomData` 字段没有设置方法，自动初始化
+/// - 其余字段为必填
+///
+/// # 示例
+/// ```
+/// use proc_tools::Builder;
+///
+/// #[derive(Builder)]
+/// struct Server {
+///     host: String,
+///     #[builder(default = "8080")]
+///     port: u16,
+///     #[builder(default)]
+///     workers: usize,
+///     tls_cert: Option<String>,
+/// }
+///
+/// let server = Server::builder().host(String::from("0.0.0.0")).tls_cert(String::from("cert.pem")).build().unwrap();
+/// assert_eq!((server.host.as_str(), server.port, server.workers), ("0.0.0.0", 8080, 0));
+/// assert_eq!(server.tls_cert.as_deref(), Some("cert.pem"));
+///
+/// let err = Server::builder().port(443).build().err().unwrap();
+/// assert_eq!(err.field(), "host");
+/// ```
+#[proc_macro_derive(Builder, attributes(builder))]
+pub fn derive_builder(input: TokenStream) -> TokenStream {
+    derive_builder_implement(input)
+}
+
+/// 为命名字段结构体的每个字段生成与字段同名的读取方法 `字段名(&self) -> &字段类型`
+/// - 字段的文档注释复制到读取方法上
+/// - 支持泛型结构体，可与 `#[derive(Setters)]`、`#[derive(New)]` 一起使用
+///
+/// # 结构体选项
+/// - `#[getset(vis = "pub(crate)")]`：生成方法的可见性，默认为 `pub`，`vis = ""` 为私有
+///
+/// # 字段选项
+/// - `#[getset(skip)]`：不为该字段生成方法
+/// - `#[getset(copy)]`：按值返回，适用于整数、`bool` 等 `Copy` 类型
+/// - `#[getset(vis = "...")]`：该字段方法的可见性，优先于结构体上的设置
+///
+/// # 示例
+/// ```
+/// use proc_tools::{Getters, New, Setters};
+///
+/// #[derive(New, Getters, Setters)]
+/// struct Account {
+///     /// 用户名
+///     name: String,
+///     #[getset(copy)]
+///     balance: u64,
+///     #[getset(skip)]
+///     password: String,
+/// }
+///
+/// let mut account = Account::new(String::from("alice"), 100, String::from("secret"));
+/// account.set_balance(120).set_name(String::from("bob"));
+/// assert_eq!((account.name().as_str(), account.balance()), ("bob", 120));
+/// assert_eq!(account.password, "secret");
+/// ```
+#[proc_macro_derive(Getters, attributes(getset))]
+pub fn derive_getters(input: TokenStream) -> TokenStream {
+    derive_getters_implement(input)
+}
+
+/// 为命名字段结构体的每个字段生成修改方法 `set_字段名(&mut self, value: 字段类型) -> &mut Self`
+/// - 返回 `&mut Self`，可以链式调用
+/// - 选项与 [`derive_getters`] 共用 `#[getset(...)]`，`skip` 和 `vis` 对两者同时生效，`copy` 只影响读取方法
+///
+/// # 示例
+/// ```
+/// use proc_tools::Setters;
+///
+/// #[derive(Setters, Default)]
+/// #[getset(vis = "pub(crate)")]
+/// struct Window {
+///     width: u32,
+///     height: u32,
+///     #[getset(skip)]
+///     id: u64,
+/// }
+///
+/// let mut window = Window::default();
+/// window.set_width(800).set_height(600);
+/// assert_eq!((window.width, window.height, window.id), (800, 600, 0));
+/// ```
+#[proc_macro_derive(Setters, attributes(getset))]
+pub fn derive_setters(input: TokenStream) -> TokenStream {
+    derive_setters_implement(input)
+}
+
+/// 为命名字段结构体的每个字段生成链式修改方法 `with_字段名(mut self, value: 字段类型) -> Self`
+/// - 按值接收并返回结构体，适合在 `new()`、`default()` 之后逐项定制配置
+/// - 选项与 [`derive_getters`] 共用 `#[getset(...)]`，支持 `skip` 和 `vis`
+///
+/// # 示例
+/// ```
+/// use proc_tools::WithMethods;
+///
+/// #[derive(WithMethods, Default)]
+/// struct ClientConfig {
+///     timeout_ms: u64,
+///     retries: u8,
+///     user_agent: Option<String>,
+/// }
+///
+/// let config = ClientConfig::default().with_timeout_ms(3000).with_user_agent(Some(String::from("demo/1.0")));
+/// assert_eq!((config.timeout_ms, config.retries, config.user_agent.as_deref()), (3000, 0, Some("demo/1.0")));
+/// ```
+#[proc_macro_derive(WithMethods, attributes(getset))]
+pub fn derive_with_methods(input: TokenStream) -> TokenStream {
+    derive_with_methods_implement(input)
+}
+
+/// 为结构体和无字段枚举自动派生固定大小字节编码/解码实现的过程宏
 /// - 此宏可以为包含固定大小字段的结构体自动生成字节序列化和反序列化方法。
-/// - 生成的实现使用小端字节序（little-endian）进行编码，适用于二进制协议和文件格式。
+/// - 生成的实现默认使用小端字节序（little-endian）进行编码，可改为大端字节序（网络字节序），适用于二进制协议和文件格式。
 ///
 /// # 特性
-/// - 自动生成 `to_bytes()` 方法将结构体序列化为字节数组
-/// - 自动生成 `from_bytes()` 方法从字节数组反序列化结构体
-/// - 提供 `SIZE` 常量表示结构体的固定字节大小
+/// - 生成 `proc_tools_core::ByteEncode` trait 的实现，调用方法前需要 `use proc_tools_core::ByteEncode;`，
+///   也可以用作泛型约束，如 `fn send<T: ByteEncode>(value: &T)`
+/// - `to_bytes()` 将结构体序列化为字节数组 `[u8; SIZE]`
+/// - `write_to(&mut [u8])` 直接序列化到给定缓冲区的开头，返回写入的字节数
+/// - `from_bytes()` 从字节切片反序列化结构体
+/// - `read_from(&mut impl Read)` 从 `TcpStream`、文件等读取恰好 `SIZE` 个字节并反序列化
+/// - `encode_slice(&[Self])`、`decode_slice(&[u8])` 批量处理连续存放的定长记录，`decode_iter(&[u8])` 借用输入逐条解码
+/// - `decode_into(&[u8], &mut MaybeUninit<Self>)` 解码到调用者提供的存储中；结构体带 `#[byte_encode(in_place)]` 时
+///   逐个字段直接写入目标位置，不在栈上构造完整的值再移动，适合几 KB 的大记录（生成的代码含 `unsafe`，因此需要显式开启）
+/// - 结构体额外生成常量 `LAYOUT: &[(&str, usize, usize)]`，按编码顺序列出各字段的名称、字节偏移和字节数（带版本号时为当前版本），
+///   以及 `describe(&[u8])`，返回按字段标注的十六进制转储，便于调试时打印报文
+/// - `#[byte_encode(debug_hex)]`：结构体额外生成 `Debug` 实现，每个字段显示为 `值 @偏移 [编码后的十六进制字节]`，
+///   便于对照协议文档排查格式错误的报文；跳过的字段不显示，字段类型需实现 `Debug`
+/// - 结构体的每个编码的字段生成偏移常量 `OFFSET_字段名`（字段名转为大写，元组结构体为 `OFFSET_0` 等），
+///   可以直接修改已编码数据中的单个字段，如事后填写长度，不需要重新编码整个结构体；同一组的位字段为所在字节的偏移
+/// - 结构体额外生成 `from_array(&[u8; SIZE])`，长度由类型保证，不需要长度检查，适合已校验过长度的热路径；
+///   带版本号的结构体、枚举和泛型类型不生成
+///   - 所有字段的解码都不会失败时（只有数值、数值数组、位字段等，没有 `bool`、`char`、嵌套类型、校验和），
+///     返回 `Self`，不需要错误处理，并额外生成不检查长度的 `from_bytes_unchecked(&[u8]) -> Self`，长度不足时 panic
+///   - 否则返回 `Result<Self, DecodeError>`，只检查字段的值
+/// - `SIZE` 常量表示结构体的固定字节大小
 /// - 支持基本数值类型和固定大小数组的编码
 /// - 编译时计算结构体大小，无运行时开销
 ///
 /// # 支持的类型
 /// - 所有整数类型 (`i8`, `u8`, `i16`, `u16`, `i32`, `u32`, `i64`, `u64`, `i128`, `u128`)
 /// - 所有浮点类型 (`f32`, `f64`)
-/// - 固定大小的字节数组 (`[u8; N]`)
-/// - 布尔类型 (`bool`) - 编码为 `u8` (0/1)
+/// - 固定大小的字节数组 (`[u8; N]`)，`N` 可以是常量表达式
+/// - 元素为上述数值、`bool`、`char` 的固定大小数组 (`[u16; N]`、`[f32; N]`、`[[i16; 3]; N]` 等)，逐个元素按字段的字节序编码
+/// - 实现了 `ByteEncode` 的类型（如同样派生了 `ByteEncode` 的结构体），委托给其实现，字节序由其自身决定
+/// - 布尔类型 (`bool`) - 编码为 `u8` (0/1)，解码时其他值返回 `DecodeError::InvalidBool`
+/// - 字符类型 (`char`) - 按字节序编码为 `u32` 标量值，解码时无效的 Unicode 标量值返回 `DecodeError::InvalidChar`
+/// - NonZero 整数类型 (`NonZeroU16`、`NonZero<u32>` 等) - 按内部整数编码，解码时值为 0 返回 `DecodeError::ZeroValue`
+/// - `usize`、`isize` 及其数组 - 大小依赖平台，必须用 `#[byte_encode(as = "u64")]` 指定编码类型，否则编译失败
+/// - 半精度浮点数 - `f32` 字段（或其数组）带 `#[byte_encode(as = "f16")]` 时编码为 2 字节的 IEEE 754 binary16
+/// - 定长字符串 - `String` 或 `[u8; N]` 字段带 `#[byte_encode(fixed_str = N)]` 时编码为补 NUL 的 UTF-8 字节
+///
+/// # 泛型
+/// - 支持带类型参数和常量参数的结构体，如 `struct Packet<T: ByteEncode> { header: Header, payload: T }`，
+///   泛型约束按原样带到生成的实现上，字段类型需要的约束（如 `T: ByteEncode`）需自行声明
+/// - `SIZE` 为各字段大小之和的常量表达式，如 `Header::SIZE + T::SIZE`
+/// - 由于 `SIZE` 依赖泛型参数时不能用作数组长度，泛型类型的 `to_bytes()` 返回长度为 `SIZE` 的 `Vec<u8>`，
+///   且不支持 `view` 和 `verify_layout`
+///
+/// # 元组结构体
+/// - 元组结构体的字段按下标顺序编码，与命名字段相同，字段选项同样适用，错误信息中的字段名为下标
+/// - 单字段元组结构体（新类型，如 `struct PortNumber(u16);`、`struct Token([u8; 16]);`）大小与内部类型相同，
+///   派生后可以作为其他结构体的字段，在协议结构体中保留领域类型
+/// - 新类型的字节序由其自身的 `#[byte_encode(endian = ...)]` 或字段上的选项决定，不受外层结构体影响
+///
+/// # 枚举
+/// - 只有无字段变体的枚举需要用 `#[repr(u8)]`、`#[repr(u16)]` 等指定判别值的整数类型，判别值可以显式指定
+/// - 编码为判别值，按枚举上的 `#[byte_encode(endian = ...)]` 决定字节序
+/// - 解码时未知的判别值返回 `DecodeError::InvalidEnumValue`
+/// - 可以作为结构体的字段，如报文中的类型字段
+/// - 变体带有一个元组字段（如 `Request(Request)`）时为消息联合：编码为一个字节的标签和变体的内容，
+///   内容补零到最大变体的大小，`SIZE` 为 1 加最大变体的大小；字段类型与结构体字段相同，可以混有无字段的变体
+///   - 标签为变体的判别值（带数据的变体显式指定判别值时需要 `#[repr(u8)]`），未指定时与 Rust 的规则相同，从 0 开始依次加 1
+///   - 解码时未知的标签返回 `DecodeError::InvalidEnumValue`，补零的部分不检查
+///
+/// # 字节序
+/// - `#[byte_encode(endian = "big")]`：结构体上设置所有字段默认的字节序，可选 `"big"`、`"little"`，默认为 `"little"`
+/// - `#[byte_encode(big_endian)]`、`#[byte_encode(little_endian)]`：字段上单独设置字节序，优先于结构体上的设置
+/// - 字节序只影响多字节的数值类型，`u8`、`i8` 和 `[u8; N]` 按原样编码
+///
+/// # 字段选项
+/// - `#[byte_encode(skip)]`：不编码该字段，解码时以 `Default::default()` 初始化，字段类型需实现 `Default`
+/// - `#[byte_encode(checksum = "crc32")]`：编码时忽略该字段的值，写入之前所有字节（含保留字节）的校验和，
+///   解码时校验，不匹配时返回 `DecodeError::ChecksumMismatch`
+///   - 可选 `"crc32"`（字段类型为 `u32`，按字节序编码）、`"sum8"`（字节回绕相加）、`"xor"`（字节异或），后两者的字段类型为 `u8`
+/// - `#[byte_encode(pad = N)]`：在该字段之前插入 N 个保留字节，编码时写入 0，解码时忽略其内容，`N` 可以是常量表达式
+///   - 与 `skip` 一起用于单元类型的占位字段，可以在末尾或任意位置表示保留区域，如 `#[byte_encode(skip, pad = 4)] _reserved: ()`
+/// - `#[byte_encode(width = N)]`：整数字段只编码低 N 个字节（如 24 位、48 位整数），按字节序取低位字节，
+///   超出范围的高位被丢弃；解码时无符号数高位补零，有符号数按符号位扩展
+/// - `#[byte_encode(with = "module")]`：由模块中的函数自定义该字段的编码，用于 BCD 数字等不常见的格式，模块需要提供：
+///   - `const SIZE: usize`：编码后的字节数
+///   - `fn encode(value: &T, buf: &mut [u8])`：`buf` 的长度恰好为 `SIZE`，初始全为 0
+///   - `fn decode(buf: &[u8]) -> Result<T, &'static str>`：`buf` 的长度恰好为 `SIZE`，
+///     返回的错误原因作为 `DecodeError::InvalidValue` 从 `from_bytes` 返回，并带上字段名
+///   - 不能与 `skip`、`checksum`、`bits` 和字段上的字节序选项一起使用
+/// - `#[byte_encode(as = "u64")]`：`usize`、`isize` 字段（或其数组）按指定的定长整数类型编码，使报文不依赖指针宽度，
+///   类型须与字段同为无符号或有符号；编码时按 `as` 转换（超出范围的高位被丢弃），
+///   解码时超出当前平台范围的值返回 `DecodeError::InvalidValue`（`u8`、`u16`、`i8`、`i16` 总能转换，不会失败）
+/// - `#[byte_encode(as = "f16")]`：`f32` 字段（或其数组）按字节序编码为半精度浮点数，编码时按最近偶数舍入，
+///   超出范围的值变为无穷大；解码时精确转换回 `f32`，常用于传感器数据等对精度要求不高的报文
+/// - `#[byte_encode(assert = "len as usize <= MAX_LEN")]`：解码出该字段后检查条件，以字段名（元组结构体为 `value`）引用字段的值，
+///   不满足时返回 `DecodeError::InvalidValue`，原因为 `assertion failed: 条件原文`，使校验与布局写在一起；
+///   编码时和视图类型的方法不检查，不能与 `skip` 一起使用
+/// - `#[byte_encode(fixed_str = 32)]`：字符串字段编码为 32 个字节的 UTF-8，不足的部分补 NUL，`N` 可以是常量表达式，
+///   常用于设备名、文件名等 C 语言中 `char name[32]` 形式的字段
+///   - 字段类型为 `String`（或实现了 `AsRef<str>` 和 `From<&str>` 的类型）时，超出长度的内容在字符边界截断；
+///     字段类型为 `[u8; M]` 时复制前 `min(M, N)` 个字节
+///   - 解码时去掉末尾的 NUL，内容不是有效的 UTF-8 时返回 `DecodeError::InvalidValue`
+///   - 不能与 `skip`、`checksum`、`width`、`with` 和结构体上的 `verify_layout` 一起使用
+///
+/// # 位字段
+/// - `#[byte_encode(bits = N)]`：字段只占用 N 位，字段类型为无符号整数或 `bool`（`bits = 1`）
+/// - 连续的位字段组成一组，总位数必须是 8 的倍数且不超过 128；第一个字段占用最高位，依次向低位排列，整组按结构体的字节序编码
+/// - 编码时只保留每个字段的低 N 位，与 C 语言的位域相同
+/// - 一组中只有第一个字段可以带 `pad`，位字段不能使用 `skip`、`checksum`、`since` 和字段上的字节序选项
+///
+/// # 版本
+/// - `#[byte_encode(version = N)]`：结构体上设置当前版本号（`u8`），编码时在开头写入一个字节的版本号，`SIZE` 包含该字节
+/// - `#[byte_encode(since = K)]`：字段在版本 K 中加入，版本号小于 K 的数据中没有该字段（及其保留字节），
+///   解码时使用 `#[byte_encode(default = "expr")]` 的值，默认为 `Default::default()`
+/// - 解码时按开头的版本号计算长度，`from_bytes` 接受任意不大于 N 的版本的数据，`read_from` 只读取该版本的字节数；
+///   版本号大于 N 时返回 `DecodeError::UnsupportedVersion`
+/// - 编码总是使用当前版本，不能与 `view` 一起使用
+///
+/// # 内存布局校验
+/// - `#[byte_encode(verify_layout)]`：结构体需带有 `#[repr(C, packed)]`，在编译时断言结构体的大小等于 `SIZE`，
+///   各字段在内存中的偏移、大小与编码位置一致，多字节数值的字节序与目标平台相同，不满足时编译失败
+/// - 额外生成 `as_bytes(&self) -> &[u8; SIZE]`，直接借用内存中的字节，结果与 `to_bytes()` 相同
+/// - 嵌套类型的字段要求其自身也带有 `verify_layout`；不能与 `skip`、`pad`、`checksum`、`version` 一起使用
+///
+/// # 视图类型
+/// - `#[byte_encode(view)]`：结构体上额外生成 `结构体名View<'a>`，借用恰好 `SIZE` 个字节，只在调用时解码单个字段，
+///   适合从大缓冲区中挑选少数字段，如按头部字段过滤报文
+/// - `View::new(&[u8])` 检查长度，`View::from_array(&[u8; SIZE])` 不会失败，`as_bytes()` 返回底层字节，`decode()` 解码出完整的结构体
+/// - 每个未跳过的字段生成同名方法（元组结构体的字段为 `field_0`、`field_1` 等），数值和数值数组直接返回值，`bool`、`char`、嵌套类型等可能解码失败的字段返回 `Result`
+/// - 校验和字段的方法只读出存储的值，不校验；需要校验时使用 `decode()`
+///
+/// # 帧
+/// - `#[byte_encode(frame(magic = b"PT", len = "u16"))]`：额外生成带帧头的编码和解码方法，帧头依次为魔数和内容的长度，
+///   适合直接收发 TCP 报文；`magic` 可省略，`len` 可选 `"u16"`、`"u32"`（默认），长度按类型的字节序编码，
+///   不带参数的 `frame` 只有 `u32` 长度
+/// - `to_framed_bytes()` 返回完整的一帧；`SIZE` 超出长度类型的范围时编译失败
+/// - `from_framed_bytes(&[u8])` 从恰好一帧的字节解码，魔数不匹配时返回 `DecodeError::InvalidMagic`，
+///   长度字段与实际内容不一致时返回 `DecodeError::UnexpectedLength`
+/// - `from_framed(&mut impl Read)` 从数据流读取一帧，长度超过 `SIZE` 时不读取内容，直接返回错误（`no_std` 下不生成）
+/// - 带版本号的结构体可以读取长度较短的旧版本帧
+///
+/// # serde
+/// - `#[byte_encode(serde = "hex")]`：额外生成 `serde::Serialize`/`Deserialize`，序列化为 `to_bytes()` 的小写十六进制字符串，
+///   同一个类型既可以写入二进制日志，也可以出现在 JSON 配置中
+/// - `#[byte_encode(serde = "bytes")]`：序列化为字节串，二进制格式中最紧凑，JSON 中为数字数组
+/// - 反序列化经过 `from_bytes`，长度或字段无效时返回格式的错误，描述来自 `DecodeError`
+/// - 需要启用 `proc-tools-core` 的 `serde` 特性；辅助模块 `proc_tools_core::utils_core::byte_encode_serde::{bytes, hex}`
+///   也可以用于其他类型字段上的 `#[serde(with = "...")]`
 ///
 /// # 错误处理
-/// - `from_bytes` 方法可能返回 `std::io::Error` 错误
-/// - 输入字节长度必须精确匹配 `SIZE` 常量
-/// - 所有字段必须能正确反序列化，否则返回错误
+/// - `from_bytes` 返回 `proc_tools_core::DecodeError`，指明失败的原因和字段，如长度不匹配时为
+///   `UnexpectedLength { expected, got }`，字段内容无效时带有字段名，可以用 `field()` 取出
+/// - `read_from` 返回 `std::io::Error`，解码失败时为 `InvalidData`，内部错误为 `DecodeError`；`DecodeError` 也可以用 `?` 转换为 `io::Error`
+/// - `write_to` 的缓冲区长度不能小于 `SIZE`，否则返回 `proc_tools_core::EncodeError::BufferTooSmall { needed, got }`，
+///   可以用 `?` 转换为 `InvalidInput` 类型的 `io::Error`
+///
+/// # no_std
+/// - 启用 `proc-tools` 的 `no_std` 特性后，生成的代码只使用 `core` 路径（泛型类型的 `Bytes` 使用 `alloc`），
+///   可以在 `#![no_std]` crate 中使用，此时 `proc-tools-core` 需关闭默认的 `std` 特性
+/// - 错误类型 `DecodeError`、`EncodeError` 不依赖 `std`；`read_from` 需要 `std`，不再生成，
+///   若 `proc-tools-core` 仍启用 `std`，则使用读取 `SIZE` 个字节的默认实现（带版本号的结构体只能读取当前版本）
 ///
 /// # 示例
-/// ```ignore
+/// ```
+/// use proc_tools::ByteEncode;
+/// use proc_tools_core::ByteEncode;
+///
 /// #[derive(ByteEncode)]
 /// struct PacketHeader {
 ///     version: u8,
@@ -147,8 +1029,669 @@ pub fn derive_new(input: TokenStream) -> TokenStream {
 ///
 /// // 从字节数组反序列化
 /// let decoded = PacketHeader::from_bytes(&bytes).unwrap();
+/// assert_eq!((decoded.packet_type, decoded.checksum), (100, [0x12, 0x34, 0x56, 0x78]));
+///
+/// // 长度已知时不需要错误处理
+/// assert_eq!(PacketHeader::from_array(&bytes).length, 1024);
+///
+/// // 直接写入更大的缓冲区
+/// let mut frame = [0u8; 64];
+/// assert_eq!(header.write_to(&mut frame).unwrap(), PacketHeader::SIZE);
+/// assert_eq!(&frame[..PacketHeader::SIZE], bytes);
+///
+/// // 已校验过长度的切片不再检查长度，多余的字节被忽略
+/// assert_eq!(PacketHeader::from_bytes_unchecked(&frame).length, 1024);
+///
+/// // 含有 `bool` 或嵌套类型时 `from_array` 仍会检查字段的值
+/// #[derive(ByteEncode)]
+/// struct Tagged {
+///     header: PacketHeader,
+///     urgent: bool,
+/// }
+///
+/// let mut tagged = [0u8; Tagged::SIZE];
+/// tagged[..PacketHeader::SIZE].copy_from_slice(&bytes);
+/// tagged[PacketHeader::SIZE] = 1;
+/// assert!(Tagged::from_array(&tagged).unwrap().urgent);
+/// tagged[PacketHeader::SIZE] = 2;
+/// assert!(Tagged::from_array(&tagged).is_err());
+///
+/// // 从实现了 `Read` 的数据源连续读取
+/// let mut reader = std::io::Cursor::new(frame);
+/// assert_eq!(PacketHeader::read_from(&mut reader).unwrap().length, 1024);
+/// assert_eq!(reader.position(), PacketHeader::SIZE as u64);
+///
+/// // 批量处理连续存放的记录
+/// let file = PacketHeader::encode_slice(&[header, PacketHeader { version: 2, ..decoded }]);
+/// assert_eq!(file.len(), 2 * PacketHeader::SIZE);
+/// let records = PacketHeader::decode_slice(&file).unwrap();
+/// assert_eq!((records[0].version, records[1].version), (1, 2));
+/// assert_eq!(PacketHeader::decode_iter(&file[..PacketHeader::SIZE + 3]).filter(Result::is_err).count(), 1);
+///
+/// // 字段布局
+/// assert_eq!(PacketHeader::LAYOUT[2], ("length", 3, 4));
+///
+/// // 直接修改已编码数据中的字段
+/// let mut patched = bytes;
+/// let offset = PacketHeader::OFFSET_LENGTH;
+/// patched[offset..offset + 4].copy_from_slice(&2048u32.to_le_bytes());
+/// assert_eq!(PacketHeader::from_bytes(&patched).unwrap().length, 2048);
+/// assert_eq!(PacketHeader::describe(&bytes).to_string().lines().nth(1), Some("   1  packet_type  64 00"));
+/// ```
+///
+/// 按网络字节序编码，个别字段保持小端：
+/// ```
+/// use proc_tools::ByteEncode;
+/// use proc_tools_core::ByteEncode;
+///
+/// #[derive(ByteEncode)]
+/// #[byte_encode(endian = "big")]
+/// struct UdpHeader {
+///     src_port: u16,
+///     dst_port: u16,
+///     length: u16,
+///     #[byte_encode(little_endian)]
+///     checksum: u16,
+/// }
+///
+/// let header = UdpHeader { src_port: 0x1234, dst_port: 53, length: 8, checksum: 0xABCD };
+/// let bytes = header.to_bytes();
+/// assert_eq!(bytes, [0x12, 0x34, 0x00, 0x35, 0x00, 0x08, 0xCD, 0xAB]);
+/// assert_eq!(UdpHeader::from_bytes(&bytes).unwrap().src_port, 0x1234);
+/// ```
+///
+/// 布尔和字符字段：
+/// ```
+/// use proc_tools::ByteEncode;
+/// use proc_tools_core::ByteEncode;
+///
+/// #[derive(ByteEncode)]
+/// struct Flags {
+///     enabled: bool,
+///     level: u8,
+/// }
+///
+/// assert_eq!(Flags { enabled: true, level: 3 }.to_bytes(), [1, 3]);
+/// assert!(Flags::from_bytes(&[0, 3]).is_ok_and(|flags| !flags.enabled));
+/// let err = Flags::from_bytes(&[2, 3]).err().unwrap();
+/// assert_eq!(err, proc_tools_core::DecodeError::InvalidBool { field: "enabled", value: 2 });
+/// assert_eq!(err.to_string(), "bool field `enabled` must be 0 or 1, got 2");
+///
+/// #[derive(ByteEncode)]
+/// struct KeyEvent {
+///     key: char,
+///     pressed: bool,
+/// }
+///
+/// let bytes = KeyEvent { key: '中', pressed: true }.to_bytes();
+/// assert_eq!(bytes, [0x2D, 0x4E, 0, 0, 1]);
+/// assert_eq!(KeyEvent::from_bytes(&bytes).unwrap().key, '中');
+/// // 0xD800 是代理项，不是有效的字符
+/// assert!(KeyEvent::from_bytes(&[0x00, 0xD8, 0, 0, 1]).is_err());
+/// ```
+///
+/// 数值数组：
+/// ```
+/// use proc_tools::ByteEncode;
+/// use proc_tools_core::ByteEncode;
+///
+/// #[derive(ByteEncode)]
+/// #[byte_encode(endian = "big")]
+/// struct SensorFrame {
+///     samples: [i16; 3],
+///     #[byte_encode(little_endian)]
+///     gain: [f32; 2],
+/// }
+///
+/// let frame = SensorFrame { samples: [1, -1, 256], gain: [1.0, 0.5] };
+/// let bytes = frame.to_bytes();
+/// assert_eq!(SensorFrame::SIZE, 14);
+/// assert_eq!(&bytes[..6], [0x00, 0x01, 0xFF, 0xFF, 0x01, 0x00]);
+/// let decoded = SensorFrame::from_bytes(&bytes).unwrap();
+/// assert_eq!((decoded.samples, decoded.gain), (frame.samples, frame.gain));
+/// ```
+///
+/// 跳过字段和保留区域：
+/// ```
+/// use proc_tools::ByteEncode;
+/// use proc_tools_core::ByteEncode;
+///
+/// #[derive(ByteEncode)]
+/// struct DiskEntry {
+///     kind: u8,
+///     #[byte_encode(pad = 3)]
+///     offset: u32,
+///     #[byte_encode(skip)]
+///     cached: Option<Vec<u8>>,
+///     #[byte_encode(skip, pad = 2)]
+///     _reserved: (),
+/// }
+///
+/// let entry = DiskEntry { kind: 1, offset: 0x10, cached: Some(vec![0; 16]), _reserved: () };
+/// let bytes = entry.to_bytes();
+/// assert_eq!(bytes, [1, 0, 0, 0, 0x10, 0, 0, 0, 0, 0]);
+/// assert!(DiskEntry::from_bytes(&bytes).unwrap().cached.is_none());
+/// ```
+///
+/// 校验和：
+/// ```
+/// use proc_tools::ByteEncode;
+/// use proc_tools_core::ByteEncode;
+///
+/// #[derive(ByteEncode)]
+/// struct Frame {
+///     command: u8,
+///     payload: [u8; 4],
+///     #[byte_encode(checksum = "crc32")]
+///     crc: u32,
+/// }
+///
+/// let mut bytes = Frame { command: 1, payload: *b"ping", crc: 0 }.to_bytes();
+/// let frame = Frame::from_bytes(&bytes).unwrap();
+/// assert_eq!(frame.crc, proc_tools_core::utils_core::checksum::crc32(&bytes[..5]));
+///
+/// // 传输中损坏的报文无法解码
+/// bytes[2] ^= 0x01;
+/// assert!(Frame::from_bytes(&bytes).is_err());
+/// ```
+///
+/// NonZero 和元组结构体：
+/// ```
+/// use proc_tools::ByteEncode;
+/// use proc_tools_core::ByteEncode;
+/// use std::num::NonZeroU32;
+///
+/// #[derive(ByteEncode, Debug, Clone, Copy, PartialEq)]
+/// #[byte_encode(endian = "big")]
+/// struct PortNumber(u16);
+///
+/// #[derive(ByteEncode)]
+/// #[byte_encode(endian = "big")]
+/// struct Session {
+///     id: NonZeroU32,
+///     port: PortNumber,
+/// }
+///
+/// let session = Session { id: NonZeroU32::new(7).unwrap(), port: PortNumber(8080) };
+/// let bytes = session.to_bytes();
+/// assert_eq!(bytes, [0, 0, 0, 7, 0x1F, 0x90]);
+/// assert_eq!(Session::from_bytes(&bytes).unwrap().port, PortNumber(8080));
+/// assert!(Session::from_bytes(&[0, 0, 0, 0, 0x1F, 0x90]).is_err());
+///
+/// #[derive(ByteEncode, Debug, PartialEq)]
+/// struct Token([u8; 4]);
+///
+/// #[derive(ByteEncode, Debug, PartialEq)]
+/// struct Point(i16, i16, #[byte_encode(big_endian)] u16);
+///
+/// assert_eq!(Token::from_bytes(&[1, 2, 3, 4]).unwrap(), Token([1, 2, 3, 4]));
+/// assert_eq!(Point(-1, 2, 3).to_bytes(), [0xFF, 0xFF, 2, 0, 0, 3]);
+/// assert_eq!(Point::from_bytes(&[0xFF, 0xFF, 2, 0, 0, 3]).unwrap(), Point(-1, 2, 3));
+/// ```
+///
+/// 泛型：
+/// ```
+/// use proc_tools::ByteEncode;
+/// use proc_tools_core::ByteEncode;
+///
+/// #[derive(ByteEncode)]
+/// #[byte_encode(endian = "big")]
+/// struct Header {
+///     kind: u8,
+///     len: u16,
+/// }
+///
+/// #[derive(ByteEncode)]
+/// struct Packet<T: ByteEncode, const N: usize> {
+///     header: Header,
+///     payload: T,
+///     tail: [u8; N],
+/// }
+///
+/// let packet = Packet { header: Header { kind: 1, len: 2 }, payload: Header { kind: 2, len: 0 }, tail: [0xEE] };
+/// assert_eq!(Packet::<Header, 1>::SIZE, 7);
+/// assert_eq!(packet.to_bytes(), vec![1, 0, 2, 2, 0, 0, 0xEE]);
+/// assert_eq!(Packet::<Header, 1>::from_bytes(&packet.to_bytes()).unwrap().payload.kind, 2);
+/// ```
+///
+/// 24 位整数：
+/// ```
+/// use proc_tools::ByteEncode;
+/// use proc_tools_core::ByteEncode;
+///
+/// #[derive(ByteEncode, Debug, PartialEq)]
+/// #[byte_encode(endian = "big")]
+/// struct Chunk {
+///     kind: u8,
+///     #[byte_encode(width = 3)]
+///     len: u32,
+///     #[byte_encode(width = 3)]
+///     offset: i32,
+/// }
+///
+/// let chunk = Chunk { kind: 1, len: 0x012345, offset: -2 };
+/// assert_eq!(Chunk::SIZE, 7);
+/// assert_eq!(chunk.to_bytes(), [1, 0x01, 0x23, 0x45, 0xFF, 0xFF, 0xFE]);
+/// assert_eq!(Chunk::from_bytes(&chunk.to_bytes()).unwrap(), chunk);
+/// ```
+///
+/// 帧：
+/// ```
+/// use proc_tools::ByteEncode;
+/// use proc_tools_core::DecodeError;
+///
+/// #[derive(ByteEncode, Debug, PartialEq)]
+/// #[byte_encode(endian = "big", frame(magic = b"PT", len = "u16"))]
+/// struct Login {
+///     user_id: u32,
+///     admin: bool,
+/// }
+///
+/// let login = Login { user_id: 7, admin: false };
+/// let frame = login.to_framed_bytes();
+/// assert_eq!(frame, [b'P', b'T', 0, 5, 0, 0, 0, 7, 0]);
+/// assert_eq!(Login::from_framed_bytes(&frame).unwrap(), login);
+/// assert_eq!(Login::from_framed_bytes(&frame[1..]), Err(DecodeError::InvalidMagic));
+///
+/// let mut stream = std::io::Cursor::new([frame.clone(), frame].concat());
+/// assert_eq!(Login::from_framed(&mut stream).unwrap(), login);
+/// assert_eq!(Login::from_framed(&mut stream).unwrap(), login);
+/// assert!(Login::from_framed(&mut stream).is_err());
+/// ```
+///
+/// 十六进制 `Debug`：
+/// ```
+/// use proc_tools::ByteEncode;
+///
+/// #[derive(ByteEncode)]
+/// #[byte_encode(endian = "big", debug_hex)]
+/// struct Record {
+///     kind: u8,
+///     len: u16,
+/// }
+///
+/// let record = Record { kind: 2, len: 300 };
+/// assert_eq!(format!("{:?}", record), "Record { kind: 2 @0 [02], len: 300 @1 [01 2c] }");
+/// ```
+///
+/// 解码到预先分配的存储：
+/// ```
+/// use proc_tools::ByteEncode;
+/// use proc_tools_core::ByteEncode;
+/// use std::mem::MaybeUninit;
+///
+/// #[derive(ByteEncode)]
+/// #[byte_encode(in_place)]
+/// struct Record {
+///     id: u32,
+///     payload: [u8; 2048],
+/// }
+///
+/// let bytes = Record { id: 9, payload: [0xAB; 2048] }.to_bytes();
+/// let mut slot = Box::new(MaybeUninit::<Record>::uninit());
+/// Record::decode_into(&bytes, &mut slot).unwrap();
+/// // SAFETY: `decode_into` 成功后 `slot` 已初始化
+/// let record = unsafe { slot.assume_init() };
+/// assert_eq!((record.id, record.payload[2047]), (9, 0xAB));
+/// ```
+///
+/// 半精度浮点数：
+/// ```
+/// use proc_tools::ByteEncode;
+/// use proc_tools_core::ByteEncode;
+///
+/// #[derive(ByteEncode)]
+/// struct ImuSample {
+///     #[byte_encode(as = "f16")]
+///     temperature: f32,
+///     #[byte_encode(as = "f16")]
+///     accel: [f32; 3],
+/// }
+///
+/// let sample = ImuSample { temperature: 36.5, accel: [0.0, -9.81, 0.25] };
+/// assert_eq!(ImuSample::SIZE, 8);
+/// let decoded = ImuSample::from_array(&sample.to_bytes());
+/// assert_eq!(decoded.temperature, 36.5);
+/// assert_eq!(decoded.accel, [0.0, -9.8125, 0.25]);
+/// ```
+///
+/// 定长字符串：
+/// ```
+/// use proc_tools::ByteEncode;
+/// use proc_tools_core::{ByteEncode, DecodeError};
+///
+/// #[derive(ByteEncode, Debug, PartialEq)]
+/// struct DeviceInfo {
+///     id: u16,
+///     #[byte_encode(fixed_str = 8)]
+///     name: String,
+///     #[byte_encode(fixed_str = 4)]
+///     vendor: [u8; 4],
+/// }
+///
+/// let info = DeviceInfo { id: 1, name: "温度计".into(), vendor: *b"ACME" };
+/// assert_eq!(DeviceInfo::SIZE, 14);
+/// let bytes = info.to_bytes();
+/// // "温度计" 为 9 个字节，在字符边界截断为 6 个字节，再补 NUL
+/// assert_eq!(&bytes[2..10], "温度\0\0".as_bytes());
+/// assert_eq!(DeviceInfo::from_bytes(&bytes).unwrap().name, "温度");
+///
+/// let mut invalid = bytes;
+/// invalid[2] = 0xFF;
+/// assert!(matches!(DeviceInfo::from_bytes(&invalid), Err(DecodeError::InvalidValue { field: "name", .. })));
+/// ```
+///
+/// 解码时校验：
+/// ```
+/// use proc_tools::ByteEncode;
+/// use proc_tools_core::{ByteEncode, DecodeError};
+///
+/// const MAX_PAYLOAD: usize = 1024;
+///
+/// #[derive(ByteEncode, Debug)]
+/// struct Header {
+///     #[byte_encode(assert = "version <= 3")]
+///     version: u8,
+///     #[byte_encode(assert = "length as usize <= MAX_PAYLOAD")]
+///     length: u16,
+/// }
+///
+/// assert!(Header::from_bytes(&[3, 0x00, 0x04]).is_ok());
+/// let err = Header::from_bytes(&[3, 0x01, 0x04]).unwrap_err();
+/// assert_eq!(err, DecodeError::InvalidValue { field: "length", reason: "assertion failed: length as usize <= MAX_PAYLOAD" });
+/// ```
+///
+/// `usize` 字段：
+/// ```
+/// use proc_tools::ByteEncode;
+/// use proc_tools_core::ByteEncode;
+///
+/// #[derive(ByteEncode, Debug, PartialEq)]
+/// struct IndexEntry {
+///     #[byte_encode(as = "u64")]
+///     offset: usize,
+///     #[byte_encode(as = "u16")]
+///     len: usize,
+/// }
+///
+/// let entry = IndexEntry { offset: 4096, len: 512 };
+/// assert_eq!(IndexEntry::SIZE, 10);
+/// assert_eq!(IndexEntry::from_bytes(&entry.to_bytes()).unwrap(), entry);
+/// ```
+///
+/// 自定义字段编码：
+/// ```
+/// use proc_tools::ByteEncode;
+/// use proc_tools_core::ByteEncode;
+///
+/// /// 两个字节的 BCD 编码，每 4 位表示一个十进制数字
+/// mod bcd {
+///     pub const SIZE: usize = 2;
+///
+///     pub fn encode(value: &u16, buf: &mut [u8]) {
+///         let digits = [value / 1000 % 10, value / 100 % 10, value / 10 % 10, value % 10];
+///         buf[0] = (digits[0] << 4 | digits[1]) as u8;
+///         buf[1] = (digits[2] << 4 | digits[3]) as u8;
+///     }
+///
+///     pub fn decode(buf: &[u8]) -> Result<u16, &'static str> {
+///         let mut value = 0;
+///         for digit in buf.iter().flat_map(|b| [b >> 4, b & 0x0F]) {
+///             if digit > 9 {
+///                 return Err("invalid BCD digit");
+///             }
+///             value = value * 10 + digit as u16;
+///         }
+///         Ok(value)
+///     }
+/// }
+///
+/// #[derive(ByteEncode, Debug, PartialEq)]
+/// struct Date {
+///     #[byte_encode(with = "bcd")]
+///     year: u16,
+///     month: u8,
+///     day: u8,
+/// }
+///
+/// let date = Date { year: 2024, month: 5, day: 17 };
+/// assert_eq!(date.to_bytes(), [0x20, 0x24, 5, 17]);
+/// assert_eq!(Date::from_bytes(&[0x20, 0x24, 5, 17]).unwrap(), date);
+/// assert_eq!(Date::from_bytes(&[0x2A, 0x24, 5, 17]).err().unwrap().to_string(), "invalid value in field `year`: invalid BCD digit");
+/// ```
+///
+/// 位字段：
+/// ```
+/// use proc_tools::ByteEncode;
+/// use proc_tools_core::ByteEncode;
+///
+/// // MQTT 固定报头的第一个字节
+/// #[derive(ByteEncode, Debug, PartialEq)]
+/// struct FixedHeader {
+///     #[byte_encode(bits = 4)]
+///     packet_type: u8,
+///     #[byte_encode(bits = 1)]
+///     dup: bool,
+///     #[byte_encode(bits = 2)]
+///     qos: u8,
+///     #[byte_encode(bits = 1)]
+///     retain: bool,
+///     remaining_len: u8,
+/// }
+///
+/// let header = FixedHeader { packet_type: 3, dup: false, qos: 1, retain: true, remaining_len: 10 };
+/// assert_eq!(header.to_bytes(), [0x33, 10]);
+/// assert_eq!(FixedHeader::from_bytes(&[0x33, 10]).unwrap(), header);
+///
+/// // IPv4 的标志和分片偏移共用 16 位
+/// #[derive(ByteEncode)]
+/// #[byte_encode(endian = "big")]
+/// struct Fragment {
+///     #[byte_encode(bits = 3)]
+///     flags: u8,
+///     #[byte_encode(bits = 13)]
+///     offset: u16,
+/// }
+///
+/// assert_eq!(Fragment { flags: 0b010, offset: 185 }.to_bytes(), [0x40, 0xB9]);
+/// ```
+///
+/// 版本：
+/// ```
+/// use proc_tools::ByteEncode;
+/// use proc_tools_core::ByteEncode;
+///
+/// #[derive(ByteEncode, Debug, PartialEq)]
+/// #[byte_encode(version = 2, endian = "big")]
+/// struct Hello {
+///     node_id: u16,
+///     #[byte_encode(since = 2, default = "1500")]
+///     mtu: u16,
+/// }
+///
+/// assert_eq!(Hello::SIZE, 5);
+/// assert_eq!(Hello { node_id: 7, mtu: 9000 }.to_bytes(), [2, 0, 7, 0x23, 0x28]);
+/// // 版本 1 的数据没有 `mtu` 字段
+/// assert_eq!(Hello::from_bytes(&[1, 0, 7]).unwrap(), Hello { node_id: 7, mtu: 1500 });
+/// assert_eq!(Hello::read_from(&mut &[1, 0, 7, 0xFF][..]).unwrap().mtu, 1500);
+/// assert!(Hello::from_bytes(&[3, 0, 7, 0, 0]).is_err());
+/// ```
+///
+/// 内存布局校验：
+/// ```
+/// use proc_tools::ByteEncode;
+/// use proc_tools_core::ByteEncode;
+///
+/// #[derive(ByteEncode)]
+/// #[repr(C, packed)]
+/// #[byte_encode(verify_layout)]
+/// struct Sample {
+///     channel: u8,
+///     timestamp: u32,
+///     values: [i16; 3],
+/// }
+///
+/// let sample = Sample { channel: 2, timestamp: 1000, values: [-1, 0, 1] };
+/// assert_eq!(sample.as_bytes(), &sample.to_bytes());
+/// ```
+///
+/// 视图类型：
+/// ```
+/// use proc_tools::ByteEncode;
+/// use proc_tools_core::ByteEncode;
+///
+/// #[derive(ByteEncode)]
+/// #[byte_encode(endian = "big", view)]
+/// struct Ipv4Header {
+///     version_ihl: u8,
+///     tos: u8,
+///     total_len: u16,
+///     #[byte_encode(pad = 4)]
+///     ttl: u8,
+///     protocol: u8,
+///     checksum: u16,
+///     src: [u8; 4],
+///     dst: [u8; 4],
+/// }
+///
+/// let mut packet = [0u8; 64];
+/// packet[..Ipv4Header::SIZE].copy_from_slice(&[0x45, 0, 0, 64, 0, 0, 0, 0, 64, 17, 0, 0, 10, 0, 0, 1, 10, 0, 0, 2]);
+/// let view = Ipv4HeaderView::new(&packet[..Ipv4Header::SIZE]).unwrap();
+/// assert_eq!(view.protocol(), 17);
+/// assert_eq!(view.total_len(), 64);
+/// assert_eq!(view.dst(), [10, 0, 0, 2]);
+/// assert_eq!(view.decode().unwrap().ttl, 64);
+/// assert!(Ipv4HeaderView::new(&packet).is_err());
+/// ```
+///
+/// serde：
+/// ```
+/// use proc_tools::ByteEncode;
+/// use proc_tools_core::ByteEncode;
+///
+/// #[derive(ByteEncode, Debug, PartialEq)]
+/// #[byte_encode(endian = "big", serde = "hex")]
+/// struct DeviceId {
+///     vendor: u16,
+///     serial: u32,
+/// }
+///
+/// #[derive(ByteEncode, Debug, PartialEq)]
+/// #[byte_encode(serde = "bytes")]
+/// struct Version(u8, u8);
+///
+/// let id = DeviceId { vendor: 0x1234, serial: 0xDEADBEEF };
+/// let json = serde_json::to_string(&id).unwrap();
+/// assert_eq!(json, r#""1234deadbeef""#);
+/// assert_eq!(serde_json::from_str::<DeviceId>(r#""1234DEADBEEF""#).unwrap(), id);
+/// assert!(serde_json::from_str::<DeviceId>(r#""1234""#).is_err());
+///
+/// assert_eq!(serde_json::to_string(&Version(1, 2)).unwrap(), "[1,2]");
+/// assert_eq!(serde_json::from_str::<Version>("[1,2]").unwrap(), Version(1, 2));
+/// ```
+///
+/// 枚举：
+/// ```
+/// use proc_tools::ByteEncode;
+/// use proc_tools_core::ByteEncode;
+///
+/// #[derive(ByteEncode, Debug, PartialEq)]
+/// #[repr(u16)]
+/// #[byte_encode(endian = "big")]
+/// enum MessageType {
+///     Hello = 1,
+///     Data = 0x0100,
+///     Bye,
+/// }
+///
+/// #[derive(ByteEncode)]
+/// struct Message {
+///     kind: MessageType,
+///     len: u8,
+/// }
+///
+/// let bytes = Message { kind: MessageType::Data, len: 4 }.to_bytes();
+/// assert_eq!(bytes, [0x01, 0x00, 4]);
+/// assert_eq!(MessageType::from_bytes(&[0x01, 0x01]).unwrap(), MessageType::Bye);
+/// assert!(Message::from_bytes(&[0x00, 0x07, 4]).is_err());
+/// ```
+///
+/// 枚举不生成 `from_array`：
+/// ```compile_fail
+/// use proc_tools::ByteEncode;
+///
+/// #[derive(ByteEncode)]
+/// #[repr(u8)]
+/// enum Flag {
+///     Off,
+///     On,
+/// }
+///
+/// let flag = Flag::from_array(&[1]);
+/// ```
+///
+/// 消息联合：
+/// ```
+/// use proc_tools::ByteEncode;
+/// use proc_tools_core::ByteEncode;
+///
+/// #[derive(ByteEncode, Debug, PartialEq)]
+/// struct Request {
+///     id: u32,
+///     op: u8,
+/// }
+///
+/// #[derive(ByteEncode, Debug, PartialEq)]
+/// struct Response {
+///     id: u32,
+///     ok: bool,
+/// }
+///
+/// #[derive(ByteEncode, Debug, PartialEq)]
+/// #[repr(u8)]
+/// enum Envelope {
+///     Request(Request) = 1,
+///     Response(Response),
+///     Heartbeat = 0xFF,
+/// }
+///
+/// assert_eq!(Envelope::SIZE, 6);
+/// let bytes = Envelope::Response(Response { id: 9, ok: true }).to_bytes();
+/// assert_eq!(bytes, [2, 9, 0, 0, 0, 1]);
+/// assert_eq!(Envelope::Heartbeat.to_bytes(), [0xFF, 0, 0, 0, 0, 0]);
+/// assert_eq!(Envelope::from_bytes(&bytes).unwrap(), Envelope::Response(Response { id: 9, ok: true }));
+/// assert!(Envelope::from_bytes(&[3, 0, 0, 0, 0, 0]).is_err());
+/// ```
+///
+/// 嵌套结构体：
+/// ```
+/// use proc_tools::ByteEncode;
+/// use proc_tools_core::ByteEncode;
+///
+/// #[derive(ByteEncode, Debug, PartialEq)]
+/// struct MacAddr {
+///     octets: [u8; 6],
+/// }
+///
+/// #[derive(ByteEncode)]
+/// #[byte_encode(endian = "big")]
+/// struct EthernetHeader {
+///     dst: MacAddr,
+///     src: MacAddr,
+///     ether_type: u16,
+/// }
+///
+/// let header = EthernetHeader {
+///     dst: MacAddr { octets: [0xFF; 6] },
+///     src: MacAddr { octets: [0x02, 0, 0, 0, 0, 0x01] },
+///     ether_type: 0x0800,
+/// };
+/// let bytes = header.to_bytes();
+/// assert_eq!(EthernetHeader::SIZE, 14);
+/// assert_eq!(&bytes[12..], [0x08, 0x00]);
+/// assert_eq!(EthernetHeader::from_bytes(&bytes).unwrap().src, header.src);
 /// ```
-#[proc_macro_derive(ByteEncode)]
+#[proc_macro_derive(ByteEncode, attributes(byte_encode))]
 pub fn derive_byte_encode(input: TokenStream) -> TokenStream {
     byte_encode_implement(input)
 }